@@ -20,6 +20,7 @@ use crate::{
         CallContextField, Op, Operation, OperationContainer, PaddingOp, RWCounter, StartOp,
         StepStateField, StepStateOp, RW,
     },
+    precompile::PrecompileCalls,
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
@@ -29,7 +30,11 @@ pub use call::{Call, CallContext, CallKind};
 pub use chunk::ChunkContext;
 use core::fmt::Debug;
 use eth_types::{
-    self, geth_types,
+    self,
+    evm_types::{
+        GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED, MAX_REFUND_QUOTIENT_OF_GAS_USED_EIP2200,
+    },
+    geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
     Address, GethExecStep, GethExecTrace, ToWord, Word,
 };
@@ -68,6 +73,196 @@ pub struct FeatureConfig {
     /// shouldn't be included in a mainnet block. However, rollup developers might want to
     /// include invalid tx in the L2 block to support forced exit feature.
     pub invalid_tx: bool,
+    /// Add the coinbase to the access list at tx start (EIP-3651)
+    ///
+    /// Enabled since Shanghai. Only disable it for traces of earlier forks, where the coinbase
+    /// starts cold.
+    pub warm_coinbase: bool,
+    /// Limit the init code of CREATE and CREATE2 to 49152 bytes and charge 2 gas per word of
+    /// init code (EIP-3860)
+    ///
+    /// Enabled since Shanghai. Only disable it for traces of earlier forks.
+    pub limit_init_code_size: bool,
+    /// Treat the block as post-merge, where opcode 0x44 pushes PREVRANDAO (taken from the block's
+    /// mix hash) instead of DIFFICULTY
+    ///
+    /// Enabled since Paris. Only disable it for traces of earlier forks.
+    pub is_merged: bool,
+    /// Take the gas left at the start of each tx from the witness instead of constraining it to
+    /// the tx gas minus the intrinsic gas
+    ///
+    /// Only meant for tests probing out of gas boundaries, where the execution is driven with an
+    /// arbitrary starting gas. Never enable it for a real block, as it lets the prover pick the
+    /// gas of every tx: the EVM circuit only configures it in tests and with the `test-util`
+    /// feature.
+    pub override_gas_left: bool,
+    /// Skip the rw permutation (fingerprint) assignment of the EVM circuit and leave its chunk
+    /// continuity public inputs unconstrained
+    ///
+    /// Only meant to speed up unit tests of single chunk blocks. The EVM circuit only configures
+    /// it in tests and with the `test-util` feature, and refuses to synthesize a chunk of a
+    /// multi-chunk block with it enabled.
+    pub disable_rw_permutation: bool,
+    /// Hardfork whose gas rules price SSTORE and its refunds, cap the refund at the end of the
+    /// tx, and set the base gas costs of the precompiles in the fixed precompile info table of
+    /// the EVM circuit
+    pub hardfork: Hardfork,
+}
+
+/// Hardfork selecting the gas rules that differ between the supported forks
+///
+/// Only SSTORE and the precompiles are priced by the hardfork: the other storage and account
+/// accesses are always charged the EIP-2929 costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Hardfork {
+    /// EIP-2200 costs and refunds, from Istanbul until Berlin: an SSTORE that is a no-op or
+    /// writes to a dirty slot costs `SLOAD_GAS` (800), there is no cold access surcharge,
+    /// clearing a slot refunds 15000 gas and the refund is capped at 1/2 of the gas used.
+    /// EIP-1108 costs of the BN256 precompiles and EIP-198 modexp without a minimum cost
+    Istanbul,
+    /// EIP-2200 refunds on top of the EIP-2929 warm/cold access costs, in Berlin: clearing a slot
+    /// refunds 15000 gas and the refund is capped at 1/2 of the gas used. EIP-2565 modexp with a
+    /// minimum cost of 200 gas
+    Berlin,
+    /// EIP-3529 refunds on top of the EIP-2929 warm/cold access costs, since London: clearing a
+    /// slot refunds 4800 gas and the refund is capped at 1/5 of the gas used
+    #[default]
+    London,
+}
+
+impl Hardfork {
+    /// Gas of an SSTORE that is a no-op or writes to a dirty slot, on top of any cold access
+    /// surcharge
+    pub fn sload_gas(&self) -> u64 {
+        match self {
+            Self::Istanbul => GasCost::SLOAD_GAS_EIP2200,
+            Self::Berlin | Self::London => GasCost::WARM_ACCESS,
+        }
+    }
+
+    /// Gas of an SSTORE changing a clean slot that isn't empty, on top of any cold access
+    /// surcharge
+    pub fn sstore_reset(&self) -> u64 {
+        match self {
+            Self::Istanbul => GasCost::SSTORE_RESET_EIP2200,
+            Self::Berlin | Self::London => GasCost::SSTORE_RESET,
+        }
+    }
+
+    /// Surcharge of an SSTORE to a storage slot that isn't in the access list
+    pub fn cold_sload(&self) -> u64 {
+        match self {
+            Self::Istanbul => 0,
+            Self::Berlin | Self::London => GasCost::COLD_SLOAD,
+        }
+    }
+
+    /// Gas refunded for clearing a storage slot
+    pub fn sstore_clears_schedule(&self) -> u64 {
+        match self {
+            Self::Istanbul | Self::Berlin => GasCost::SSTORE_CLEARS_SCHEDULE_EIP2200,
+            Self::London => GasCost::SSTORE_CLEARS_SCHEDULE,
+        }
+    }
+
+    /// The refund of a tx is capped at the gas used divided by this quotient
+    pub fn max_refund_quotient(&self) -> u64 {
+        match self {
+            Self::Istanbul | Self::Berlin => MAX_REFUND_QUOTIENT_OF_GAS_USED_EIP2200 as u64,
+            Self::London => MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
+        }
+    }
+
+    /// Gas cost of an SSTORE writing `value` over `value_prev` to a slot holding
+    /// `original_value` at the start of the tx.
+    pub fn gas_cost(
+        &self,
+        value: Word,
+        value_prev: Word,
+        original_value: Word,
+        is_warm: bool,
+    ) -> u64 {
+        let warm_case_gas = if value_prev == value {
+            self.sload_gas()
+        } else if original_value == value_prev {
+            if original_value.is_zero() {
+                GasCost::SSTORE_SET
+            } else {
+                self.sstore_reset()
+            }
+        } else {
+            self.sload_gas()
+        };
+        if is_warm {
+            warm_case_gas
+        } else {
+            warm_case_gas + self.cold_sload()
+        }
+    }
+
+    /// Tx refund after an SSTORE writing `value` over `value_prev` to a slot holding
+    /// `original_value` at the start of the tx.
+    ///
+    /// The clause tags (like "delete slot (2.1.2b)") and the control flow follow
+    /// [`makeGasSStoreFunc` in go-ethereum](https://github.com/ethereum/go-ethereum/blob/9fd8825d5a196edde6d8ef81382979875145b346/core/vm/operations_acl.go#L27).
+    pub fn tx_refund(
+        &self,
+        tx_refund_prev: u64,
+        value: Word,
+        value_prev: Word,
+        original_value: Word,
+    ) -> u64 {
+        let mut tx_refund = tx_refund_prev;
+
+        // The "clearing slot refund" and "resetting value refund" are ADDED together,
+        // they are NOT MUTUALLY EXCLUSIVE.
+        // Search "Apply both of the following clauses" in EIP-2200 for more details.
+        // There can be five total kinds of refund:
+        // 1. -SSTORE_CLEARS_SCHEDULE
+        // 2. SSTORE_CLEARS_SCHEDULE
+        // 3. SSTORE_SET - SLOAD_GAS
+        // 4. SSTORE_RESET - SLOAD_GAS
+        // 5. -SSTORE_CLEARS_SCHEDULE + SSTORE_RESET - SLOAD_GAS
+        // The last case can happen if (original_value, prev_value, value) be (v,0,v)
+        // where v != 0,
+        // then both "clearing slot refund" and "resetting value refund" are non zero.
+
+        if value_prev != value {
+            // refund related to clearing slot
+            // "delete slot (2.1.2b)" can be safely merged in "delete slot (2.2.1.2)"
+            if !original_value.is_zero() {
+                if value_prev.is_zero() {
+                    // recreate slot (2.2.1.1)
+                    tx_refund -= self.sstore_clears_schedule()
+                }
+                if value.is_zero() {
+                    // delete slot (2.2.1.2)
+                    tx_refund += self.sstore_clears_schedule()
+                }
+            }
+
+            // refund related to resetting value
+            if original_value == value {
+                if original_value.is_zero() {
+                    // reset to original inexistent slot (2.2.2.1)
+                    tx_refund += GasCost::SSTORE_SET - self.sload_gas();
+                } else {
+                    // reset to original existing slot (2.2.2.2)
+                    tx_refund += self.sstore_reset() - self.sload_gas();
+                }
+            }
+        }
+
+        tx_refund
+    }
+
+    /// Base gas cost of a precompile call
+    pub fn base_gas_cost(&self, precompile: PrecompileCalls) -> u64 {
+        match (self, precompile) {
+            (Self::Istanbul, PrecompileCalls::Modexp) => GasCost::PRECOMPILE_MODEXP_EIP198,
+            _ => precompile.base_gas_cost(),
+        }
+    }
 }
 
 impl Default for FeatureConfig {
@@ -77,6 +272,12 @@ impl Default for FeatureConfig {
             free_first_tx: false,
             enable_eip1559: true,
             invalid_tx: false,
+            warm_coinbase: true,
+            limit_init_code_size: true,
+            is_merged: true,
+            override_gas_left: false,
+            disable_rw_permutation: false,
+            hardfork: Hardfork::London,
         }
     }
 }
@@ -84,7 +285,36 @@ impl Default for FeatureConfig {
 impl FeatureConfig {
     /// Check if we are mainnet config
     pub fn is_mainnet(&self) -> bool {
-        self.zero_difficulty && !self.free_first_tx && self.enable_eip1559 && !self.invalid_tx
+        self.zero_difficulty
+            && !self.free_first_tx
+            && self.enable_eip1559
+            && !self.invalid_tx
+            && self.warm_coinbase
+            && self.limit_init_code_size
+            && self.is_merged
+            && !self.override_gas_left
+            && !self.disable_rw_permutation
+            && self.hardfork == Hardfork::London
+    }
+
+    /// Maximum init code size of CREATE and CREATE2. Without the EIP-3860 limit, it is only
+    /// bounded by the maximum expanded memory address.
+    pub fn max_init_code_size(&self) -> u64 {
+        if self.limit_init_code_size {
+            2 * eth_types::evm_types::MAX_CODE_SIZE
+        } else {
+            eth_types::evm_types::MAX_EXPANDED_MEMORY_ADDRESS
+        }
+    }
+
+    /// Gas charged by CREATE and CREATE2 per word of init code, on top of the hashing cost of
+    /// CREATE2.
+    pub fn init_code_word_gas(&self) -> u64 {
+        if self.limit_init_code_size {
+            eth_types::evm_types::INIT_CODE_WORD_GAS
+        } else {
+            0
+        }
     }
 }
 
@@ -290,6 +520,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             tx,
             tx_ctx,
             max_rws: self.circuits_params.max_rws(),
+            feature_config: self.feature_config,
         }
     }
 
@@ -380,7 +611,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
         let last_copy = self.block.copy_events.len();
         // Generate EndChunk and proceed to the next if it's not the last chunk
         // Set next step pre-state as end_chunk state
-        self.set_end_chunk(&next_ops, Some(&tx));
+        self.set_end_chunk(&next_ops, Some(&tx))?;
 
         // need to update next_ops.rwc to catch block_ctx.rwc in `set_end_chunk`
         next_ops.rwc = self.block_ctx.rwc;
@@ -597,7 +828,11 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
         self.chunks[self.chunk_ctx.idx].begin_chunk = Some(begin_chunk);
     }
 
-    fn set_end_chunk(&mut self, next_step: &ExecStep, tx: Option<&Transaction>) {
+    fn set_end_chunk(
+        &mut self,
+        next_step: &ExecStep,
+        tx: Option<&Transaction>,
+    ) -> Result<(), Error> {
         let mut end_chunk = ExecStep {
             exec_state: ExecState::EndChunk,
             rwc: next_step.rwc,
@@ -607,22 +842,25 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             ..ExecStep::default()
         };
         self.gen_chunk_associated_steps(&mut end_chunk, RW::WRITE, tx);
-        self.gen_chunk_padding(&mut end_chunk);
+        self.gen_chunk_padding(&mut end_chunk)?;
         self.chunks[self.chunk_ctx.idx].end_chunk = Some(end_chunk);
+        Ok(())
     }
 
-    fn gen_chunk_padding(&mut self, step: &mut ExecStep) {
+    fn gen_chunk_padding(&mut self, step: &mut ExecStep) -> Result<(), Error> {
         // rwc index start from 1
         let end_rwc = self.chunk_ctx.rwc.0;
         let total_rws = end_rwc - 1;
         let max_rws = self.cur_chunk().fixed_param.max_rws;
 
-        assert!(
-            total_rws < max_rws,
-            "total_rws <= max_rws, total_rws={}, max_rws={}",
-            total_rws,
-            max_rws
-        );
+        if total_rws >= max_rws {
+            log::error!(
+                "total_rws >= max_rws, total_rws={}, max_rws={}",
+                total_rws,
+                max_rws
+            );
+            return Err(Error::RwsNotEnough(max_rws, end_rwc));
+        }
 
         let mut padding = step.clone();
         padding.exec_state = ExecState::Padding;
@@ -661,6 +899,7 @@ impl<'a, C: CircuitsParams> CircuitInputBuilder<C> {
             }
         }
         self.chunks[self.chunk_ctx.idx].padding = Some(padding);
+        Ok(())
     }
 
     /// Get the i-th mutable chunk
@@ -780,7 +1019,7 @@ impl CircuitInputBuilder<FixedCParams> {
                     last_call.clone(),
                 );
             } else {
-                self.set_end_chunk(&dummy_next_step, None);
+                self.set_end_chunk(&dummy_next_step, None)?;
 
                 self.commit_chunk_ctx(
                     true,
@@ -849,7 +1088,7 @@ impl CircuitInputBuilder<FixedCParams> {
         }
 
         // EndBlock step should also be padded to max_rws similar to EndChunk
-        self.gen_chunk_padding(&mut end_block);
+        self.gen_chunk_padding(&mut end_block)?;
         self.block.end_block = end_block;
         Ok(())
     }
@@ -1145,7 +1384,9 @@ pub fn build_state_code_db(
     codes: HashMap<Address, Vec<u8>>,
 ) -> (StateDB, CodeDB) {
     let mut sdb = StateDB::new();
+    let mut code_hashes = HashMap::new();
     for proof in proofs {
+        code_hashes.insert(proof.address, proof.code_hash);
         let mut storage = HashMap::new();
         for storage_proof in proof.storage_proof {
             storage.insert(storage_proof.key, storage_proof.value);
@@ -1162,8 +1403,12 @@ pub fn build_state_code_db(
     }
 
     let mut code_db = CodeDB::default();
-    for (_address, code) in codes {
-        code_db.insert(code.clone());
+    for (address, code) in codes {
+        // The code hash of the proof saves hashing large bytecodes again
+        match code_hashes.get(&address) {
+            Some(code_hash) => code_db.insert_with_hash(*code_hash, code),
+            None => code_db.insert(code),
+        };
     }
     (sdb, code_db)
 }
@@ -1297,7 +1542,13 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         history_hashes: Vec<Word>,
         prev_state_root: Word,
     ) -> Result<CircuitInputBuilder<FixedCParams>, Error> {
-        let block = Block::new(self.chain_id, history_hashes, prev_state_root, eth_block)?;
+        let block = Block::new(
+            self.chain_id,
+            history_hashes,
+            prev_state_root,
+            eth_block,
+            self.feature_config.is_merged,
+        )?;
         let builder = CircuitInputBuilder::new(
             sdb,
             code_db,
@@ -60,7 +60,7 @@ pub struct Block {
     pub number: Word,
     /// time
     pub timestamp: Word,
-    /// difficulty
+    /// difficulty, or PREVRANDAO for post-merge blocks
     pub difficulty: Word,
     /// base fee
     pub base_fee: Word,
@@ -90,11 +90,16 @@ pub struct Block {
 
 impl Block {
     /// Create a new block.
+    ///
+    /// When `is_merged` is set, a zero difficulty header is taken as a post-merge one and the
+    /// `difficulty` field holds the PREVRANDAO value from its mix hash, as fed to the tracer by
+    /// `BlockConstants`. Otherwise the header difficulty is used as is.
     pub fn new(
         chain_id: Word,
         history_hashes: Vec<Word>,
         prev_state_root: Word,
         eth_block: &eth_types::Block<eth_types::Transaction>,
+        is_merged: bool,
     ) -> Result<Self, Error> {
         if eth_block.base_fee_per_gas.is_none() {
             // FIXME: resolve this once we have proper EIP-1559 support
@@ -116,7 +121,7 @@ impl Block {
                 .low_u64()
                 .into(),
             timestamp: eth_block.timestamp,
-            difficulty: if eth_block.difficulty.is_zero() {
+            difficulty: if is_merged && eth_block.difficulty.is_zero() {
                 eth_block
                     .mix_hash
                     .unwrap_or_default()
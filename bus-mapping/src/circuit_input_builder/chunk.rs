@@ -1,5 +1,5 @@
 use super::{Call, ExecStep, FixedCParams};
-use crate::operation::RWCounter;
+use crate::{operation::RWCounter, Error};
 
 #[derive(Debug, Default, Clone)]
 pub struct Chunk {
@@ -81,6 +81,44 @@ impl ChunkContext {
         }
     }
 
+    /// Create the context of chunk `idx` out of `total_chunks`, covering the global rw
+    /// counters `[initial_rwc, end_rwc)`.  The tx and copy ranges are left empty, to be set
+    /// in a later phase.
+    ///
+    /// Returns an error if the rw range is empty, if `idx` is out of bounds, or if the first
+    /// chunk doesn't start at rw counter 1.
+    pub fn with_rw_range(
+        idx: usize,
+        total_chunks: usize,
+        initial_rwc: usize,
+        end_rwc: usize,
+    ) -> Result<Self, Error> {
+        if idx >= total_chunks {
+            return Err(Error::InternalError("chunk index exceeds total chunks"));
+        }
+        if initial_rwc >= end_rwc {
+            return Err(Error::InternalError(
+                "chunk initial_rwc must be below end_rwc",
+            ));
+        }
+        if idx == 0 && initial_rwc != 1 {
+            return Err(Error::InternalError(
+                "first chunk must start at rw counter 1",
+            ));
+        }
+        Ok(Self {
+            rwc: RWCounter::new(),
+            idx,
+            total_chunks,
+            initial_rwc,
+            end_rwc,
+            initial_tx_index: 0,
+            end_tx_index: 0,
+            initial_copy_index: 0,
+            end_copy_index: 0,
+        })
+    }
+
     /// Proceed the context to next chunk, record the initial rw counter
     /// update the chunk idx and reset the inner rw counter
     pub fn bump(&mut self, initial_rwc: usize, initial_tx: usize, initial_copy: usize) {
@@ -105,3 +143,30 @@ impl ChunkContext {
         self.total_chunks - self.idx - 1 == 0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_rw_range_accepts_valid_ranges() {
+        let first = ChunkContext::with_rw_range(0, 2, 1, 100).unwrap();
+        assert!(first.is_first_chunk());
+        let last = ChunkContext::with_rw_range(1, 2, 100, 200).unwrap();
+        assert!(last.is_last_chunk());
+        assert_eq!((last.initial_rwc, last.end_rwc), (100, 200));
+    }
+
+    #[test]
+    fn with_rw_range_rejects_invalid_ranges() {
+        // chunk index out of bounds
+        assert!(ChunkContext::with_rw_range(2, 2, 100, 200).is_err());
+        assert!(ChunkContext::with_rw_range(0, 0, 1, 100).is_err());
+        // empty or inverted rw range
+        assert!(ChunkContext::with_rw_range(1, 2, 100, 100).is_err());
+        assert!(ChunkContext::with_rw_range(1, 2, 200, 100).is_err());
+        // first chunk not starting at rw counter 1
+        assert!(ChunkContext::with_rw_range(0, 2, 0, 100).is_err());
+        assert!(ChunkContext::with_rw_range(0, 2, 2, 100).is_err());
+    }
+}
@@ -300,7 +300,9 @@ impl CopyEvent {
         self.rw_counter(self.bytes.len() * 2) - self.rw_counter(step_index)
     }
 
-    /// Number of rw operations performed by this copy event
+    /// Number of rw operations performed by this copy event, the `rwc_inc` of
+    /// its copy table lookup: one rw per byte read from memory (up to
+    /// `src_addr_end`), plus one per byte written to memory or to a tx log.
     pub fn rw_counter_delta(&self) -> u64 {
         self.rw_counter_increase(self.bytes.len() * 2)
     }
@@ -418,3 +420,50 @@ pub const N_PAIRING_PER_OP: usize = 4;
 
 /// The number of bytes taken to represent a pair (G1, G2).
 pub const N_BYTES_PER_PAIR: usize = 192;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy_event(
+        src_type: CopyDataType,
+        src_addr_end: u64,
+        dst_type: CopyDataType,
+        len: usize,
+    ) -> CopyEvent {
+        CopyEvent {
+            src_addr: 0,
+            src_addr_end,
+            src_type,
+            src_id: NumberOrHash::Number(1),
+            dst_addr: 0,
+            dst_type,
+            dst_id: NumberOrHash::Number(1),
+            log_id: None,
+            rw_counter_start: RWCounter(1),
+            bytes: vec![(0xff, false); len],
+        }
+    }
+
+    #[test]
+    fn copy_event_rw_counter_delta() {
+        let calldata_to_memory = copy_event(CopyDataType::TxCalldata, 10, CopyDataType::Memory, 10);
+        assert_eq!(calldata_to_memory.rw_counter_delta(), 10);
+
+        let memory_to_memory = copy_event(CopyDataType::Memory, 10, CopyDataType::Memory, 10);
+        assert_eq!(memory_to_memory.rw_counter_delta(), 20);
+
+        // bytes past the end of the source are padding, not memory reads
+        let memory_out_of_bound = copy_event(CopyDataType::Memory, 4, CopyDataType::Memory, 10);
+        assert_eq!(memory_out_of_bound.rw_counter_delta(), 14);
+
+        let memory_to_log = copy_event(CopyDataType::Memory, 10, CopyDataType::TxLog, 10);
+        assert_eq!(memory_to_log.rw_counter_delta(), 20);
+
+        let memory_to_hash = copy_event(CopyDataType::Memory, 10, CopyDataType::RlcAcc, 10);
+        assert_eq!(memory_to_hash.rw_counter_delta(), 10);
+
+        let code_to_memory = copy_event(CopyDataType::Bytecode, 10, CopyDataType::Memory, 10);
+        assert_eq!(code_to_memory.rw_counter_delta(), 10);
+    }
+}
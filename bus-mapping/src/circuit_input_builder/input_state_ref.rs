@@ -2,8 +2,8 @@
 
 use super::{
     get_call_memory_offset_length, get_create_init_code, Block, BlockContext, Call, CallContext,
-    CallKind, ChunkContext, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, PrecompileEvent,
-    Transaction, TransactionContext,
+    CallKind, ChunkContext, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, FeatureConfig,
+    PrecompileEvent, Transaction, TransactionContext,
 };
 use crate::{
     error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError},
@@ -13,13 +13,14 @@ use crate::{
         StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
         RW,
     },
-    precompile::{is_precompiled, PrecompileCalls},
+    precompile::{is_precompiled, PrecompileCalls, N_BYTES_EC_PAIR},
     state_db::{CodeDB, StateDB},
     Error,
 };
 use eth_types::{
     evm_types::{
         gas_utils::memory_expansion_gas_cost, GasCost, MemoryAddress, OpcodeId, StackAddress,
+        MAX_CODE_SIZE,
     },
     Address, Bytecode, GethExecStep, ToAddress, ToBigEndian, ToWord, Word, H256, U256,
 };
@@ -45,6 +46,8 @@ pub struct CircuitInputStateRef<'a> {
     pub tx_ctx: &'a mut TransactionContext,
     /// Max rw number limit
     pub max_rws: Option<usize>,
+    /// Feature config
+    pub feature_config: FeatureConfig,
 }
 
 impl<'a> CircuitInputStateRef<'a> {
@@ -1474,7 +1477,7 @@ impl<'a> CircuitInputStateRef<'a> {
                 if call.is_create() {
                     let offset = step.stack.nth_last(0)?;
                     let length = step.stack.nth_last(1)?;
-                    if length > Word::from(0x6000u64) {
+                    if length > Word::from(MAX_CODE_SIZE) {
                         return Ok(Some(ExecError::MaxCodeSizeExceeded));
                     } else if length > Word::zero()
                         && !call_ctx.memory.is_empty()
@@ -1593,7 +1596,31 @@ impl<'a> CircuitInputStateRef<'a> {
                 // Therefore we postpone the oog handling to the implementor of callop.
                 if is_precompiled(&code_address) {
                     let precompile_call: PrecompileCalls = code_address[19].into();
+                    // A call whose input length doesn't fit in 64 bits runs out of gas
+                    // on the memory expansion, before reaching the precompile.
+                    let args_length = u64::try_from(step.stack.nth_last(match step.op {
+                        OpcodeId::CALL | OpcodeId::CALLCODE => 4,
+                        _ => 3,
+                    })?)
+                    .map_err(|_| {
+                        Error::InvalidGethExecStep(
+                            "precompile call input length wider than 64 bits",
+                            Box::new(step.clone()),
+                        )
+                    })?;
                     match precompile_call {
+                        // A malformed ecPairing input fails before any arithmetic, so the
+                        // call is proven by the ecPairing gadget, which consumes all its gas.
+                        PrecompileCalls::Bn128Pairing
+                            if args_length % N_BYTES_EC_PAIR as u64 != 0 =>
+                        {
+                            log::trace!(
+                                "ecPairing input length {} is not a multiple of {}",
+                                args_length,
+                                N_BYTES_EC_PAIR,
+                            );
+                            return Ok(None);
+                        }
                         PrecompileCalls::Sha256
                         | PrecompileCalls::Ripemd160
                         | PrecompileCalls::Blake2F
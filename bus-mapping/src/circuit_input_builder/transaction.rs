@@ -1,16 +1,18 @@
 //! Transaction & TransactionContext utility module.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use eth_types::{evm_types::Memory, geth_types, GethExecTrace};
 use ethers_core::utils::get_contract_address;
+use itertools::Itertools;
 
 use crate::{
+    error::{GasError, MemoryGrowthError},
     state_db::{CodeDB, StateDB},
     Error,
 };
 
-use super::{call::ReversionGroup, Call, CallContext, CallKind, CodeSource, ExecStep};
+use super::{call::ReversionGroup, Call, CallContext, CallKind, CodeSource, ExecState, ExecStep};
 
 #[derive(Debug, Default, Clone)]
 /// Context of a [`Transaction`] which can mutate in an [`ExecStep`].
@@ -275,6 +277,17 @@ impl Transaction {
         &mut self.steps
     }
 
+    /// Return the gas used by this transaction, i.e. its gas limit minus the
+    /// gas left at EndTx, or 0 before its EndTx step is generated.  The
+    /// receipt's cumulative gas used grows by this amount.
+    pub fn gas_used(&self) -> u64 {
+        self.steps
+            .iter()
+            .rev()
+            .find(|step| step.exec_state == ExecState::EndTx)
+            .map_or(0, |step| self.gas() - step.gas_left)
+    }
+
     /// Return the list of calls of this transaction.
     pub fn calls(&self) -> &[Call] {
         &self.calls
@@ -304,6 +317,64 @@ impl Transaction {
         self.steps.is_empty()
     }
 
+    /// Check that the gas left of every opcode step is the gas left of the
+    /// previous step in the same call minus its gas cost, and return the index
+    /// of the first step breaking the chain.
+    ///
+    /// Transitions into and out of sub calls (CALL*, CREATE*, and the return
+    /// to the caller) move gas between calls and are skipped, as are the steps
+    /// following an error.
+    pub fn validate_gas_chain(&self) -> Result<(), GasError> {
+        for (idx, (prev, step)) in self.steps.iter().tuple_windows().enumerate() {
+            let (ExecState::Op(prev_op), ExecState::Op(_)) = (&prev.exec_state, &step.exec_state)
+            else {
+                continue;
+            };
+            if prev.call_index != step.call_index
+                || prev_op.is_call_or_create()
+                || prev.error.is_some()
+            {
+                continue;
+            }
+            let expected = prev.gas_left.saturating_sub(prev.gas_cost);
+            if step.gas_left != expected {
+                return Err(GasError {
+                    step_index: idx + 1,
+                    expected,
+                    found: step.gas_left,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the memory word size never decreases between the opcode
+    /// steps of a call, and return the first step where it shrinks.
+    ///
+    /// Every call has its own memory, so the size is tracked per call: a sub
+    /// call starts from an empty memory and the caller resumes with the size it
+    /// had before the sub call.
+    pub fn validate_memory_growth(&self) -> Result<(), MemoryGrowthError> {
+        let mut memory_word_sizes = HashMap::new();
+        for (idx, step) in self.steps.iter().enumerate() {
+            if !matches!(step.exec_state, ExecState::Op(_)) {
+                continue;
+            }
+            let memory_word_size = step.memory_word_size();
+            if let Some(previous) = memory_word_sizes.insert(step.call_index, memory_word_size) {
+                if memory_word_size < previous {
+                    return Err(MemoryGrowthError {
+                        step_index: idx,
+                        call_index: step.call_index,
+                        previous,
+                        found: memory_word_size,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Constructor for padding tx in tx circuit
     pub fn padding_tx(id: usize) -> Self {
         Self {
@@ -320,3 +391,171 @@ impl std::ops::Deref for Transaction {
         &self.tx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::BlockData, operation::TxReceiptField};
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    #[test]
+    fn gas_used_transfer() {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let tx = &builder.block.txs()[0];
+        assert_eq!(tx.gas_used(), 21000);
+
+        let cumulative_gas_used = builder
+            .block
+            .container
+            .tx_receipt
+            .iter()
+            .find(|op| op.op().field == TxReceiptField::CumulativeGasUsed)
+            .map(|op| op.op().value)
+            .unwrap();
+        assert_eq!(cumulative_gas_used, tx.gas_used());
+    }
+
+    #[test]
+    fn gas_used_two_txs() {
+        let block: GethData = TestContext::<2, 2>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! {
+                PUSH1(0x01)
+                PUSH1(0x02)
+                ADD
+                POP
+                STOP
+            }),
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[1].address);
+                txs[1].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let txs = builder.block.txs();
+        assert_eq!(txs[0].gas_used(), 21000);
+        // 3 gas for each PUSH1 and the ADD, and 2 for the POP
+        assert_eq!(txs[1].gas_used(), 21000 + 3 * 3 + 2);
+
+        let cumulative_gas_used = builder
+            .block
+            .container
+            .tx_receipt
+            .iter()
+            .filter(|op| op.rw().is_write() && op.op().field == TxReceiptField::CumulativeGasUsed)
+            .map(|op| (op.op().tx_id, op.op().value))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(cumulative_gas_used[&1], txs[0].gas_used());
+        assert_eq!(
+            cumulative_gas_used[&2] - cumulative_gas_used[&1],
+            txs[1].gas_used()
+        );
+    }
+
+    #[test]
+    fn validate_gas_chain() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            POP
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut tx = builder.block.txs()[0].clone();
+        assert_eq!(tx.validate_gas_chain(), Ok(()));
+
+        let idx = tx
+            .steps()
+            .iter()
+            .position(|step| step.exec_state == ExecState::Op(OpcodeId::ADD))
+            .unwrap();
+        let expected = tx.steps()[idx].gas_left;
+        tx.steps_mut()[idx].gas_left += 1;
+        assert_eq!(
+            tx.validate_gas_chain(),
+            Err(GasError {
+                step_index: idx,
+                expected,
+                found: expected + 1,
+            })
+        );
+    }
+
+    #[test]
+    fn validate_memory_growth() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x00)
+            MLOAD
+            POP
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let mut tx = builder.block.txs()[0].clone();
+        assert_eq!(tx.validate_memory_growth(), Ok(()));
+
+        // Shrink the memory back to empty after the MSTORE expanded it
+        let idx = tx
+            .steps()
+            .iter()
+            .position(|step| step.exec_state == ExecState::Op(OpcodeId::MLOAD))
+            .unwrap();
+        let call_index = tx.steps()[idx].call_index;
+        assert_eq!(tx.steps()[idx].memory_word_size(), 1);
+        tx.steps_mut()[idx].memory_size = 0;
+        assert_eq!(
+            tx.validate_memory_growth(),
+            Err(MemoryGrowthError {
+                step_index: idx,
+                call_index,
+                previous: 1,
+                found: 0,
+            })
+        );
+    }
+}
@@ -167,6 +167,53 @@ pub enum DepthError {
     Create2,
 }
 
+/// Inconsistent gas accounting between two consecutive steps of a
+/// transaction, see
+/// [`Transaction::validate_gas_chain`](crate::circuit_input_builder::Transaction::validate_gas_chain).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasError {
+    /// Index of the offending step in the transaction.
+    pub step_index: usize,
+    /// Gas left expected from the gas left and gas cost of the previous step.
+    pub expected: u64,
+    /// Gas left found in the offending step.
+    pub found: u64,
+}
+
+impl Display for GasError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "step {} has gas_left {} but {} is expected from the previous step",
+            self.step_index, self.found, self.expected
+        )
+    }
+}
+
+/// Memory of a call shrinking between two of its steps, see
+/// [`Transaction::validate_memory_growth`](crate::circuit_input_builder::Transaction::validate_memory_growth).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryGrowthError {
+    /// Index of the offending step in the transaction.
+    pub step_index: usize,
+    /// Index of the call the offending step belongs to.
+    pub call_index: usize,
+    /// Memory word size at the previous step of the same call.
+    pub previous: u64,
+    /// Memory word size found in the offending step.
+    pub found: u64,
+}
+
+impl Display for MemoryGrowthError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "step {} of call {} has memory word size {} but the call already had {}",
+            self.step_index, self.call_index, self.found, self.previous
+        )
+    }
+}
+
 /// EVM Execution Error
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ExecError {
@@ -89,9 +89,9 @@ impl Opcode for Balance {
 mod balance_tests {
     use super::*;
     use crate::{
-        circuit_input_builder::ExecState,
+        circuit_input_builder::{ExecState, FeatureConfig},
         mock::BlockData,
-        operation::{AccountOp, CallContextOp, StackOp, RW},
+        operation::{AccountOp, CallContextOp, StackOp, Target, RW},
         state_db::CodeDB,
     };
     use eth_types::{
@@ -118,6 +118,70 @@ mod balance_tests {
         test_ok(true, true);
     }
 
+    #[test]
+    fn test_balance_of_coinbase() {
+        // BeginTx adds the coinbase to the access list only with EIP-3651, so
+        // that BALANCE finds it warm
+        assert_eq!(balance_of_coinbase(true), (1, true));
+        assert_eq!(balance_of_coinbase(false), (0, false));
+    }
+
+    /// Number of access list writes of the coinbase in BeginTx, and whether
+    /// the coinbase is warm when `BALANCE coinbase` accesses it.
+    fn balance_of_coinbase(warm_coinbase: bool) -> (usize, bool) {
+        let coinbase = address!("0x00000000000000000000000000000000c014ba5e");
+        let code = bytecode! {
+            .op_balance(coinbase)
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(code);
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block.author(coinbase).number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder_with_feature(FeatureConfig {
+                warm_coinbase,
+                ..Default::default()
+            })
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let access_list = &builder.block.container.tx_access_list_account;
+        let steps = builder.block.txs()[0].steps();
+        assert_eq!(steps[0].exec_state, ExecState::BeginTx);
+        let begin_tx_coinbase_writes = steps[0]
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| {
+                op_ref.target() == Target::TxAccessListAccount
+                    && access_list[op_ref.as_usize()].op().address == coinbase
+            })
+            .count();
+
+        let step = steps
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BALANCE))
+            .unwrap();
+        let operation = &access_list[step.bus_mapping_instance[4].as_usize()];
+        assert_eq!(operation.op().address, coinbase);
+        (begin_tx_coinbase_writes, operation.op().is_warm_prev)
+    }
+
     fn test_ok(exists: bool, is_warm: bool) {
         let address = address!("0xaabbccddee000000000000000000000000000000");
 
@@ -3,12 +3,15 @@ use crate::{
     circuit_input_builder::{
         Call, CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
     },
-    operation::{AccountField, AccountOp, CallContextField, TxReceiptField, TxRefundOp, RW},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountStorageOp, TxReceiptField,
+        TxRefundOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
 use eth_types::{
-    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED, PRECOMPILE_COUNT},
+    evm_types::{GasCost, PRECOMPILE_COUNT},
     ToWord, Word,
 };
 use ethers_core::utils::get_contract_address;
@@ -76,8 +79,14 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
-    // Add caller, callee and coinbase (for EIP-3651) to access list.
-    for address in [call.caller_address, call.address, state.block.coinbase] {
+    // Add caller, callee and coinbase (for EIP-3651) to access list.  When the coinbase is also
+    // the caller or callee it's written again with `is_warm_prev` set, and stays a single warm
+    // account.
+    let mut addresses = vec![call.caller_address, call.address];
+    if state.feature_config.warm_coinbase {
+        addresses.push(state.block.coinbase);
+    }
+    for address in addresses {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
             &mut exec_step,
@@ -88,9 +97,40 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
+    // Add the accounts and storage keys of the tx access list (EIP-2930).  The ones already warm,
+    // like the coinbase, are written with `is_warm_prev` set.  The BeginTx gadget doesn't
+    // support these writes yet.
+    for item in state.tx.access_list.clone().unwrap_or_default().0 {
+        let is_warm_prev = !state.sdb.add_account_to_access_list(item.address);
+        state.tx_accesslist_account_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            item.address,
+            true,
+            is_warm_prev,
+        )?;
+        for key in item.storage_keys {
+            let key = Word::from_big_endian(key.as_bytes());
+            let is_warm_prev = !state
+                .sdb
+                .add_account_storage_to_access_list((item.address, key));
+            state.push_op(
+                &mut exec_step,
+                RW::WRITE,
+                TxAccessListAccountStorageOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: item.address,
+                    key,
+                    is_warm: true,
+                    is_warm_prev,
+                },
+            )?;
+        }
+    }
+
     let init_code_gas_cost = if state.tx.is_create() {
         // Calculate gas cost of init code for EIP-3860.
-        (state.tx.call_data.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
+        (state.tx.call_data.len() as u64 + 31) / 32 * state.feature_config.init_code_word_gas()
     } else {
         0
     };
@@ -295,8 +335,9 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         },
     )?;
 
-    let effective_refund =
-        refund.min((state.tx.gas() - exec_step.gas_left) / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+    let effective_refund = refund.min(
+        (state.tx.gas() - exec_step.gas_left) / state.feature_config.hardfork.max_refund_quotient(),
+    );
     let (found, caller_account) = state.sdb.get_account(&call.caller_address);
     if !found {
         return Err(Error::AccountNotFound(call.caller_address));
@@ -19,12 +19,24 @@ impl Opcode for Calldatasize {
         let geth_step = &geth_steps[0];
         let mut exec_step = state.new_step(geth_step)?;
         let value = geth_steps[1].stack.last()?;
-        state.call_context_read(
-            &mut exec_step,
-            state.call()?.call_id,
-            CallContextField::CallDataLength,
-            value,
-        )?;
+        let (is_root, call_id) = (state.call()?.is_root, state.call()?.call_id);
+        // The root call reads the calldata length from the tx, an internal call
+        // from its own call context.
+        if is_root {
+            state.call_context_read(
+                &mut exec_step,
+                call_id,
+                CallContextField::TxId,
+                state.tx_ctx.id().into(),
+            )?;
+        } else {
+            state.call_context_read(
+                &mut exec_step,
+                call_id,
+                CallContextField::CallDataLength,
+                value,
+            )?;
+        }
 
         state.stack_write(
             &mut exec_step,
@@ -92,8 +104,8 @@ mod calldatasize_tests {
                 RW::READ,
                 &CallContextOp {
                     call_id,
-                    field: CallContextField::CallDataLength,
-                    value: call_data_size,
+                    field: CallContextField::TxId,
+                    value: 1.into(),
                 }
             )
         );
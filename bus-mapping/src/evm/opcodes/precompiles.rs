@@ -28,7 +28,8 @@ pub fn gen_associated_ops(
 
     let (opt_event, aux_data) = match precompile {
         PrecompileCalls::Ecrecover => opt_data_ecrecover(input_bytes, output_bytes, return_bytes),
-        PrecompileCalls::Identity => (
+        // The pairing check itself is not proven yet, only its input length and gas.
+        PrecompileCalls::Identity | PrecompileCalls::Bn128Pairing => (
             None,
             Some(PrecompileAuxData::Base {
                 input_bytes: input_bytes.to_vec(),
@@ -107,12 +107,21 @@ impl Opcode for Sstore {
             },
         )?;
 
+        // The refund is computed from the configured hardfork instead of being taken from the
+        // trace, which always follows the rules of the tracer's hardfork.
+        let refund_prev = state.sdb.refund();
+        let refund = state.feature_config.hardfork.tx_refund(
+            refund_prev,
+            value,
+            value_prev,
+            committed_value,
+        );
         state.push_op_reversible(
             &mut exec_step,
             TxRefundOp {
                 tx_id: state.tx_ctx.id(),
-                value_prev: state.sdb.refund(),
-                value: geth_step.refund,
+                value_prev: refund_prev,
+                value: refund,
             },
         )?;
 
@@ -50,6 +50,7 @@ impl<C: CircuitsParams> BlockData<C> {
                 self.history_hashes.clone(),
                 Word::default(),
                 &self.eth_block,
+                feature_config.is_merged,
             )
             .unwrap(),
             self.circuits_params,
@@ -59,6 +59,10 @@ pub(crate) fn execute_precompiled(
     }
 }
 
+/// Length of a pair of G1 and G2 points in the input of the ecPairing
+/// precompile.
+pub const N_BYTES_EC_PAIR: usize = 192;
+
 /// Addresses of the precompiled contracts.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum PrecompileCalls {
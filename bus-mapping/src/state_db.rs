@@ -33,6 +33,15 @@ impl CodeDB {
         hash
     }
 
+    /// Insert code indexed by a code hash that is already known, e.g. from a
+    /// trace or a proof, skipping the keccak of the code.  The hash is trusted,
+    /// and only checked against the code in debug builds.
+    pub fn insert_with_hash(&mut self, hash: Hash, code: Vec<u8>) -> Hash {
+        debug_assert_eq!(hash, Self::hash(&code), "code hash mismatch");
+        self.0.insert(hash, code);
+        hash
+    }
+
     /// Compute hash of given code.
     pub fn hash(code: &[u8]) -> Hash {
         H256(keccak256(code))
@@ -398,4 +407,27 @@ mod statedb_tests {
         assert!(found);
         assert_eq!(value, &Word::from(102));
     }
+
+    #[test]
+    fn codedb_insert_with_hash() {
+        let code = vec![0x60, 0x01, 0x60, 0x02, 0x01, 0x00];
+        let hash = CodeDB::hash(&code);
+
+        let mut trusted = CodeDB::default();
+        let mut recomputed = CodeDB::default();
+        assert_eq!(trusted.insert_with_hash(hash, code.clone()), hash);
+        assert_eq!(recomputed.insert(code.clone()), hash);
+        assert_eq!(
+            trusted.get_from_h256(&hash),
+            recomputed.get_from_h256(&hash)
+        );
+        assert_eq!(trusted.get_from_h256(&hash).unwrap().code(), code);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "code hash mismatch")]
+    fn codedb_insert_with_wrong_hash() {
+        CodeDB::default().insert_with_hash(CodeDB::empty_code_hash(), vec![0x00]);
+    }
 }
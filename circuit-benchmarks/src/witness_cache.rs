@@ -0,0 +1,60 @@
+//! Witness cache benchmarks
+
+#[cfg(test)]
+mod witness_cache_benches {
+    use ark_std::{end_timer, start_timer};
+    use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
+    use eth_types::{geth_types::GethData, Bytecode};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::TestContext;
+    use std::env::var;
+    use zkevm_circuits::witness::{block_convert, Block};
+
+    #[cfg_attr(not(feature = "benches"), ignore)]
+    #[test]
+    fn bench_witness_cache() {
+        // Unique string used by bench results module for parsing the result
+        const BENCHMARK_ID: &str = "Witness cache";
+
+        let adds: usize = var("ADDS")
+            .unwrap_or("10000".to_string())
+            .parse()
+            .expect("Cannot parse ADDS env var as usize");
+
+        let mut code = Bytecode::default();
+        for _ in 0..adds {
+            code.op_add(1, 2).op_pop();
+        }
+        code.op_stop();
+
+        let data: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data_with_params(
+            data.clone(),
+            FixedCParams {
+                max_rws: 1 << 17,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder()
+        .handle_block(&data.eth_block, &data.geth_traces)
+        .unwrap();
+
+        let start1 = start_timer!(|| format!("{} block conversion", BENCHMARK_ID));
+        let block = block_convert::<Fr>(&builder).unwrap();
+        end_timer!(start1);
+
+        let start2 = start_timer!(|| format!("{} serialization", BENCHMARK_ID));
+        let mut bytes = Vec::new();
+        block.serialize_to(&mut bytes).unwrap();
+        end_timer!(start2);
+        println!("{} encoded size: {} bytes", BENCHMARK_ID, bytes.len());
+
+        let start3 = start_timer!(|| format!("{} deserialization", BENCHMARK_ID));
+        let cached = Block::<Fr>::deserialize_from(bytes.as_slice()).unwrap();
+        end_timer!(start3);
+
+        assert_eq!(block.rws.0.len(), cached.rws.0.len());
+    }
+}
@@ -1,5 +1,5 @@
 //! EVM byte code generator
-use crate::{evm_types::OpcodeId, keccak256, Bytes, Hash, ToBigEndian, ToWord, Word};
+use crate::{evm_types::OpcodeId, keccak256, Bytes, Field, Hash, ToBigEndian, ToWord, Word};
 use std::{collections::HashMap, fmt::Display, iter, str::FromStr};
 /// Error type for Bytecode related failures
 #[derive(Debug)]
@@ -73,6 +73,15 @@ impl Bytecode {
         Hash::from_slice(&self.hash().to_be_bytes())
     }
 
+    /// Get the random linear combination of the code bytes with `challenge`,
+    /// the keccak input the bytecode circuit looks up for the code hash.  The
+    /// first byte is multiplied by the highest power of `challenge`.
+    pub fn keccak_input_rlc<F: Field>(&self, challenge: F) -> F {
+        self.code.iter().fold(F::ZERO, |rlc, elem| {
+            rlc * challenge + F::from(elem.value as u64)
+        })
+    }
+
     /// Get the bytecode element at an index.
     pub fn get(&self, index: usize) -> Option<(u8, bool)> {
         self.code.get(index).map(|elem| (elem.value, elem.is_code))
@@ -558,6 +567,7 @@ impl_other_opcodes! {
 #[cfg(test)]
 mod tests {
     use crate::Bytecode;
+    use halo2_proofs::halo2curves::bn256::Fr;
 
     #[test]
     fn test_bytecode_roundtrip() {
@@ -578,4 +588,13 @@ mod tests {
         };
         assert_eq!(Bytecode::from(code.code()), code);
     }
+
+    #[test]
+    fn test_keccak_input_rlc() {
+        let code = Bytecode::from(vec![0x60, 0x01, 0x00]);
+        let r = Fr::from(0x100);
+        let expected = (Fr::from(0x60) * r + Fr::from(0x01)) * r + Fr::from(0x00);
+        assert_eq!(code.keccak_input_rlc(r), expected);
+        assert_eq!(Bytecode::default().keccak_input_rlc(r), Fr::zero());
+    }
 }
@@ -20,8 +20,12 @@ pub use transient_storage::TransientStorage;
 pub const INVALID_INIT_CODE_FIRST_BYTE: u8 = 0xef;
 /// Once per word of the init code when creating a contract.
 pub const INIT_CODE_WORD_GAS: u64 = 2;
+/// Maximum code size to permit in a contract (EIP-170).
+pub const MAX_CODE_SIZE: u64 = 0x6000;
 /// Quotient for max refund of gas used
 pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
+/// Quotient for max refund of gas used before EIP-3529
+pub const MAX_REFUND_QUOTIENT_OF_GAS_USED_EIP2200: usize = 2;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
 
@@ -44,8 +48,7 @@ mod gas_create {
 
     /// Maximum init code size to permit in a creation transaction and create instructions.
     pub const MAX_INIT_CODE_SIZE: u64 = 2 * super::MAX_CODE_SIZE;
-    /// Once per word of the init code when creating a contract.
-    pub const INIT_CODE_WORD_GAS: u64 = 2;
+    pub use super::INIT_CODE_WORD_GAS;
     /// Gas per code word for CREATE.
     pub const CREATE_GAS_PER_CODE_WORD: u64 = INIT_CODE_WORD_GAS;
     /// Gas per code word for CREATE2.
@@ -105,9 +108,16 @@ impl GasCost {
     pub const SSTORE_SET: u64 = 20000;
     /// Constant cost for a storage reset
     pub const SSTORE_RESET: u64 = 2900;
+    /// Constant cost for a storage reset before EIP-2929, as set by EIP-2200.
+    pub const SSTORE_RESET_EIP2200: u64 = 5000;
+    /// Constant cost for reading a storage slot before EIP-2929, as set by EIP-1884. EIP-2200
+    /// charges it for an SSTORE that is a no-op or writes to a dirty slot.
+    pub const SLOAD_GAS_EIP2200: u64 = 800;
     /// Constant cost for a storage clear. EIP-3529 changed it to 4800 from
     /// 15000.
     pub const SSTORE_CLEARS_SCHEDULE: u64 = 4800;
+    /// Constant cost for a storage clear before EIP-3529, as set by EIP-2200.
+    pub const SSTORE_CLEARS_SCHEDULE_EIP2200: u64 = 15000;
     /// Constant cost for a non-creation transaction
     pub const TX: u64 = 21000;
     /// Constant cost for a creation transaction
@@ -151,6 +161,9 @@ impl GasCost {
     pub const PRECOMPILE_BN256PAIRING_PER_PAIR: u64 = 34000;
     /// Base gas cost for precompile call: MODEXP
     pub const PRECOMPILE_MODEXP: u64 = 200; // eip255
+    /// Base gas cost of MODEXP before EIP-2565. EIP-198 charges only the
+    /// complexity based cost, without any minimum.
+    pub const PRECOMPILE_MODEXP_EIP198: u64 = 0;
     /// Minimum gas cost for precompile calls: MODEXP
     pub const PRECOMPILE_MODEXP_MIN: u64 = 200;
     /// Base gas cost for precompile call: BLAKE2F
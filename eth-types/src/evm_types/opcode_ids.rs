@@ -679,9 +679,10 @@ impl OpcodeId {
         }
     }
 
-    /// Returns invalid stack pointers of `OpcodeId`
-    pub fn invalid_stack_ptrs(&self) -> Vec<u32> {
-        let (min_stack_ptr, max_stack_ptr): (u32, u32) = match self {
+    /// Returns the range `(min_stack_ptr, max_stack_ptr)` of stack pointers
+    /// with which `OpcodeId` runs without stack error.
+    pub fn valid_stack_ptr_range(&self) -> (u32, u32) {
+        match self {
             // `min_stack_pointer` 0 means stack overflow never happen, for example, `OpcodeId::ADD`
             // can only encounter underflow error, but never encounter overflow error.
             // `max_stack_pointer` means max stack pointer for op code normally run. for example,
@@ -834,7 +835,25 @@ impl OpcodeId {
             OpcodeId::REVERT => (0, 1022),
             OpcodeId::SELFDESTRUCT => (0, 1023),
             _ => (0, 0),
-        };
+        }
+    }
+
+    /// Returns `(min_stack, max_stack)` of `OpcodeId`, where `min_stack` is
+    /// the number of stack items the opcode requires and `max_stack` the
+    /// highest stack height, before or after the opcode, when it runs on
+    /// exactly `min_stack` items.  It underflows on a stack of `n` items when
+    /// `n < min_stack`, and overflows when `n - min_stack + max_stack > 1024`.
+    /// For example `OpcodeId::PUSH1` is `(0, 1)`, `OpcodeId::DUP16` is
+    /// `(16, 17)` and `OpcodeId::ADD` is `(2, 2)`.
+    pub fn stack_requirements(&self) -> (u32, u32) {
+        let (min_stack_ptr, max_stack_ptr) = self.valid_stack_ptr_range();
+        let min_stack = 1024 - max_stack_ptr;
+        (min_stack, min_stack + min_stack_ptr)
+    }
+
+    /// Returns invalid stack pointers of `OpcodeId`
+    pub fn invalid_stack_ptrs(&self) -> Vec<u32> {
+        let (min_stack_ptr, max_stack_ptr) = self.valid_stack_ptr_range();
 
         debug_assert!(max_stack_ptr <= 1024);
 
@@ -1199,7 +1218,8 @@ impl FromStr for OpcodeId {
             "COINBASE" => OpcodeId::COINBASE,
             "TIMESTAMP" => OpcodeId::TIMESTAMP,
             "NUMBER" => OpcodeId::NUMBER,
-            "DIFFICULTY" => OpcodeId::DIFFICULTY,
+            // Renamed to PREVRANDAO in Paris (EIP-4399)
+            "DIFFICULTY" | "PREVRANDAO" => OpcodeId::DIFFICULTY,
             "GASLIMIT" => OpcodeId::GASLIMIT,
             "SLOAD" => OpcodeId::SLOAD,
             "SSTORE" => OpcodeId::SSTORE,
@@ -1287,4 +1307,16 @@ mod opcode_ids_tests {
         assert_eq!(OpcodeId::LOG2.data_len(), 0);
         assert_eq!(OpcodeId::CALLCODE.data_len(), 0);
     }
+
+    #[test]
+    fn prevrandao_from_str() {
+        assert_eq!(
+            OpcodeId::from_str("DIFFICULTY").unwrap(),
+            OpcodeId::DIFFICULTY
+        );
+        assert_eq!(
+            OpcodeId::from_str("PREVRANDAO").unwrap(),
+            OpcodeId::DIFFICULTY
+        );
+    }
 }
@@ -3,7 +3,9 @@
 use crate::{
     evm_types::{self, GasCost},
     keccak256,
-    sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
+    sign_types::{
+        biguint_to_32bytes_le, ct_option_ok_or, is_high_s, recover_pk, SignData, SECP256K1_Q,
+    },
     AccessList, Address, Block, Bytecode, Bytes, Error, GethExecTrace, Hash, ToBigEndian,
     ToLittleEndian, ToWord, Word, H256, U64,
 };
@@ -15,7 +17,10 @@ use ethers_core::{
     utils::get_contract_address,
 };
 use ethers_signers::{LocalWallet, Signer};
-use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
+use halo2_proofs::halo2curves::{
+    group::{ff::PrimeField, prime::PrimeCurveAffine},
+    secp256k1::{self, Secp256k1Affine},
+};
 use num::Integer;
 use num_bigint::BigUint;
 use serde::{Serialize, Serializer};
@@ -394,7 +399,9 @@ impl Transaction {
             ..Default::default()
         }
     }
-    /// Return the SignData associated with this Transaction.
+    /// Return the SignData associated with this Transaction.  A signature with an s in the upper
+    /// half of the curve order is invalid (EIP-2), and returns a SignData with the public key at
+    /// infinity, which recovers the zero address.
     pub fn sign_data(&self, chain_id: u64) -> Result<SignData, Error> {
         let sig_r_le = self.r.to_le_bytes();
         let sig_s_le = self.s.to_le_bytes();
@@ -414,7 +421,13 @@ impl Transaction {
             .v
             .checked_sub(35 + chain_id * 2)
             .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
-        let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
+        // EIP-2: a signature with a high s is invalid for a tx, which is marked by the public key
+        // at infinity, i.e. the zero address.
+        let pk = if is_high_s(&sig_s) {
+            Secp256k1Affine::identity()
+        } else {
+            recover_pk(v, &self.r, &self.s, &msg_hash)?
+        };
         // msg_hash = msg_hash % q
         let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
         let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
@@ -44,6 +44,13 @@ pub fn sign(
     (sig_r, sig_s, sig_v)
 }
 
+/// Return whether `s` is in the upper half of the curve order.  Such signatures
+/// are rejected for txs by EIP-2, as (r, n - s) is an alternative valid
+/// signature of the same message.
+pub fn is_high_s(s: &secp256k1::Fq) -> bool {
+    BigUint::from_bytes_le(&s.to_bytes()) > &*SECP256K1_Q >> 1
+}
+
 /// Signature data required by the SignVerify Chip as input to verify a
 /// signature.
 #[derive(Clone, Debug)]
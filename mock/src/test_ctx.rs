@@ -113,6 +113,11 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         Fb: FnOnce(&mut MockBlock, Vec<MockTransaction>) -> &mut MockBlock,
         FAcc: FnOnce([&mut MockAccount; NACC]),
     {
+        // Only the most recent 256 hashes are reachable by `BLOCKHASH`
+        let history_hashes = history_hashes.map(|mut history_hashes| {
+            history_hashes.drain(..history_hashes.len().saturating_sub(256));
+            history_hashes
+        });
         let test_ctx2 = TestContext2::<NACC, NTX, 0>::new_with_logger_config(
             history_hashes,
             acc_fns,
@@ -137,6 +142,10 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
     /// the `func_block` to the block, where each of these functions can
     /// mutate their target using the builder pattern. Finally an
     /// execution trace is generated of the resulting input block and state.
+    ///
+    /// `history_hashes` are the hashes of the blocks preceding this one, the
+    /// latest one being the last, returned by `BLOCKHASH` for in-window block
+    /// numbers.  Only the most recent 256 hashes are kept.
     pub fn new<FAcc, FTx, Fb>(
         history_hashes: Option<Vec<Word>>,
         acc_fns: FAcc,
@@ -170,6 +179,19 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
             |block, _txs| block,
         )
     }
+
+    /// Returns the total gas used by all the transactions of the block, as
+    /// reported by the geth traces.  This is the value that ends up in the
+    /// `CumulativeGasUsed` of the last transaction receipt, so it can be used
+    /// to double check that a test exercises the gas it's expected to, or to
+    /// size `max_evm_rows` before proving.
+    ///
+    /// Note that the per-step `gas_cost` of the struct logs doesn't account for
+    /// the intrinsic gas of the transactions, which is why the gas used per
+    /// trace is summed instead.
+    pub fn total_gas_used(&self) -> u64 {
+        self.geth_traces.iter().map(|trace| trace.gas).sum()
+    }
 }
 
 /// Collection of helper functions which contribute to specific routines on the
@@ -204,7 +226,7 @@ pub mod helpers {
 mod tests {
     use eth_types::{address, U256, U64};
 
-    use super::{eth, TestContext};
+    use super::{eth, helpers::*, TestContext};
 
     #[test]
     fn test_nonce() {
@@ -243,4 +265,44 @@ mod tests {
         assert_eq!(block.accounts[0].nonce, U64::from(0));
         assert_eq!(block.accounts[1].nonce, U64::from(100));
     }
+
+    #[test]
+    fn test_total_gas_used() {
+        // A plain transfer only pays the intrinsic gas.
+        let block = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000000"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(address!("0x000000000000000000000000000000000cafe001"))
+                    .balance(eth(10));
+            },
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap();
+        assert_eq!(block.total_gas_used(), 21000);
+
+        // Two transfers in the same block accumulate.
+        let block = TestContext::<2, 2>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000000"))
+                    .balance(eth(10));
+                accs[1]
+                    .address(address!("0x000000000000000000000000000000000cafe001"))
+                    .balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+                txs[1].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+        assert_eq!(block.total_gas_used(), 2 * 21000);
+    }
 }
@@ -1,6 +1,6 @@
 //! Mock types and functions to generate Test environments for ZKEVM tests
 
-use crate::{withdrawal::MockWithdrawal, MockAccount, MockBlock, MockTransaction};
+use crate::{withdrawal::MockWithdrawal, InvalidTxReason, MockAccount, MockBlock, MockTransaction};
 use eth_types::{
     geth_types::{Account, BlockConstants, GethData, Withdrawal},
     Block, Error, GethExecTrace, Transaction, Word,
@@ -99,6 +99,15 @@ impl<const NACC: usize, const NTX: usize, const NWD: usize> TestContext2<NACC, N
                 if tx.nonce.is_none() {
                     tx.nonce(from_acc.nonce + acc_tx_count[pos]);
                 }
+                match tx.invalid_reason {
+                    Some(InvalidTxReason::InvalidNonce) => {
+                        tx.nonce(from_acc.nonce + acc_tx_count[pos] + 1);
+                    }
+                    Some(InvalidTxReason::InsufficientBalance) => {
+                        tx.value(from_acc.balance + 1);
+                    }
+                    _ => (),
+                }
                 if !tx.invalid {
                     acc_tx_count[pos] += 1;
                 }
@@ -2,8 +2,8 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    evm_types::GasCost, geth_types::Transaction as GethTransaction, word, AccessList, Address,
+    Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
@@ -112,6 +112,18 @@ impl AddrOrWallet {
         }
     }
 }
+/// Reason for which a [`MockTransaction`] is made invalid through
+/// [`MockTransaction::make_invalid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidTxReason {
+    /// The nonce of the tx is higher than the nonce of the sender.
+    InvalidNonce,
+    /// The sender can't afford `gas * gas_price + value`.
+    InsufficientBalance,
+    /// The gas limit of the tx is lower than its intrinsic gas.
+    IntrinsicGasTooLow,
+}
+
 #[derive(Debug, Clone)]
 /// Mock structure which represents a Transaction and can be used for tests.
 /// It contains all the builder-pattern methods required to be able to specify
@@ -137,6 +149,7 @@ pub struct MockTransaction {
     pub max_fee_per_gas: Option<Word>,
     pub chain_id: Word,
     pub invalid: bool,
+    pub invalid_reason: Option<InvalidTxReason>,
 }
 
 impl Default for MockTransaction {
@@ -162,6 +175,7 @@ impl Default for MockTransaction {
             max_fee_per_gas: None,
             chain_id: *MOCK_CHAIN_ID,
             invalid: false,
+            invalid_reason: None,
         }
     }
 }
@@ -351,6 +365,19 @@ impl MockTransaction {
         self
     }
 
+    /// Mark the transaction as invalid for the given `reason`.  The
+    /// transaction fields that depend on the sender state (nonce and value)
+    /// are overridden by the [`TestContext`](crate::TestContext) once the
+    /// accounts are known, so that the tx is rejected for exactly that reason.
+    pub fn make_invalid(&mut self, reason: InvalidTxReason) -> &mut Self {
+        self.invalid = true;
+        self.invalid_reason = Some(reason);
+        if reason == InvalidTxReason::IntrinsicGasTooLow {
+            self.gas = Word::from(GasCost::TX - 1);
+        }
+        self
+    }
+
     /// Force the nonce field for the MockTransaction.
     /// Can only be used for invalid transactions.
     pub fn set_nonce(&mut self, nonce: u64) -> &mut Self {
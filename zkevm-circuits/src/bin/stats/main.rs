@@ -24,7 +24,8 @@ use zkevm_circuits::{
             N_U8_LOOKUPS,
         },
         step::ExecutionState,
-        EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs,
+        table::CustomFixedTables,
+        EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs, EvmCircuitParams,
     },
     exp_circuit::ExpCircuitConfig,
     keccak_circuit::{KeccakCircuitConfig, KeccakCircuitConfigArgs},
@@ -135,7 +136,7 @@ fn copy_states_stats() {
 /// cell consumers of each EVM Cell type.
 fn get_exec_steps_occupancy() {
     let mut meta = ConstraintSystem::<Fr>::default();
-    let circuit = EvmCircuit::configure_with_params(&mut meta, FeatureConfig::default());
+    let circuit = EvmCircuit::configure_with_params(&mut meta, EvmCircuitParams::default());
 
     let report = circuit.0.execution.instrument().clone().analyze();
     macro_rules! gen_report {
@@ -371,6 +372,8 @@ fn record_stats<F: eth_types::Field>(
             sig_table,
             chunk_ctx_config,
             feature_config,
+            custom_fixed_tables: CustomFixedTables::default(),
+            enabled_execution_states: None,
         },
     );
     stats.record("evm", meta);
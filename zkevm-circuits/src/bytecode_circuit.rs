@@ -16,7 +16,7 @@ use crate::{
     },
     table::{BytecodeFieldTag, BytecodeTable, KeccakTable, LookupTable},
     util::{
-        get_push_size,
+        count_advice_columns, get_push_size,
         word::{empty_code_hash_word_value, Word32, WordExpr, WordLoHi},
         Challenges, Expr, SubCircuit, SubCircuitConfig,
     },
@@ -789,6 +789,17 @@ impl<F: Field> BytecodeCircuit<F> {
             max_rows,
         }
     }
+
+    /// Creates a BytecodeCircuit for the raw `codes`, assigned in the given
+    /// order and independently of any block
+    pub fn new_from_bytecodes(codes: Vec<Vec<u8>>, max_rows: usize) -> Self {
+        let rows: BytecodeCircuitAssignment<F> = codes.clone().into();
+        Self {
+            bytecodes: codes.into(),
+            rows,
+            max_rows,
+        }
+    }
 }
 
 impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
@@ -800,6 +811,22 @@ impl<F: Field> SubCircuit<F> for BytecodeCircuit<F> {
         6
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let bytecode_table = BytecodeTable::construct(meta);
+            let keccak_table = KeccakTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            BytecodeCircuitConfig::new(
+                meta,
+                BytecodeCircuitConfigArgs {
+                    bytecode_table,
+                    keccak_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     fn new_from_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> Self {
         Self::new(block.bytecodes.clone(), chunk.fixed_param.max_bytecode)
     }
@@ -1,9 +1,15 @@
 use super::{BytecodeCircuit, BytecodeCircuitRow};
-use crate::util::{log2_ceil, unusable_rows, SubCircuit};
+use crate::{
+    table::KeccakTable,
+    util::{log2_ceil, unusable_rows, Challenges, SubCircuit},
+};
 use bus_mapping::{evm::OpcodeId, state_db::CodeDB};
-use eth_types::Field;
-use halo2_proofs::{arithmetic::Field as Halo2Field, dev::MockProver, halo2curves::bn256::Fr};
+use eth_types::{bytecode, Field, Word};
+use halo2_proofs::{
+    arithmetic::Field as Halo2Field, circuit::Value, dev::MockProver, halo2curves::bn256::Fr,
+};
 use log::error;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 
 #[test]
 fn bytecode_circuit_unusable_rows() {
@@ -94,6 +100,37 @@ fn bytecode_push() {
     .verify(true);
 }
 
+/// Tests the push data of a PUSH32 isn't code, even the bytes equal to JUMPDEST
+#[test]
+fn bytecode_push32_data_and_jumpdest() {
+    let k = 9;
+    let mut code = vec![OpcodeId::PUSH32.as_u8()];
+    code.extend([OpcodeId::JUMPDEST.as_u8(); 32]);
+    code.push(OpcodeId::JUMPDEST.as_u8());
+    let circuit = BytecodeCircuit::<Fr>::new_from_bytecodes(
+        vec![code],
+        2usize.pow(k) - BytecodeCircuit::<Fr>::unusable_rows(),
+    );
+
+    // Header row followed by one row per byte
+    assert_eq!(circuit.rows.len(), 1 + 34);
+    let is_code = circuit.rows[1..]
+        .iter()
+        .map(|row| row.is_code == Fr::ONE)
+        .collect::<Vec<_>>();
+    assert!(is_code[0]);
+    assert!(is_code[1..33].iter().all(|is_code| !is_code));
+    assert!(is_code[33]);
+    let push_data_left = circuit.rows[1..]
+        .iter()
+        .map(|row| row.push_data_left)
+        .collect::<Vec<_>>();
+    assert_eq!(push_data_left[..3], [0, 32, 31]);
+    assert_eq!(push_data_left[32..], [1, 0]);
+
+    circuit.verify(true);
+}
+
 /// Test invalid code_hash data
 #[test]
 fn bytecode_invalid_hash_data() {
@@ -238,3 +275,23 @@ fn bytecode_soundness_bug_1() {
         })
         .verify(false);
 }
+
+/// Tests that `Bytecode::keccak_input_rlc` matches the input RLC of the keccak
+/// table row the bytecode circuit looks up for the code hash
+#[test]
+fn bytecode_keccak_input_rlc() {
+    let challenge = Fr::random(ChaCha20Rng::seed_from_u64(0));
+    let challenges = Challenges::mock(Value::known(challenge), Value::known(challenge));
+    // distinct bytes so that a reversed or truncated input gives another RLC
+    let word = Word::from_big_endian(&(1..=32).collect::<Vec<u8>>());
+    let code = bytecode! {
+        PUSH32(word)
+        PUSH1(0x00)
+        MSTORE
+        STOP
+    };
+
+    let [_, input_rlc, input_len, ..] = KeccakTable::assignments(&code.code(), &challenges)[0];
+    input_len.assert_if_known(|input_len| *input_len == Fr::from(code.codesize() as u64));
+    input_rlc.assert_if_known(|input_rlc| *input_rlc == code.keccak_input_rlc(challenge));
+}
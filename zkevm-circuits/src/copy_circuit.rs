@@ -16,7 +16,7 @@ use crate::{
         BytecodeFieldTag, BytecodeTable, CopyTable, LookupTable, RwTable, TxContextFieldTag,
         TxTable,
     },
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, Challenges, SubCircuit, SubCircuitConfig},
     witness,
     witness::{Chunk, Rw, RwMap, Transaction},
 };
@@ -47,6 +47,39 @@ const UNUSED_ROWS: usize = 2;
 // Rows to disable, so they do not query into Halo2 reserved rows.
 const DISABLED_ROWS: usize = 2;
 
+/// The copy events need more rows than `max_copy_rows`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "copy events need {copy_rows_needed} rows + {reserved_rows} reserved, but max_copy_rows = {max_copy_rows}"
+)]
+pub struct CopyRowsOverflow {
+    /// Rows needed by the copy events
+    pub copy_rows_needed: usize,
+    /// Rows enabled but not used and disabled rows at the end of the circuit
+    pub reserved_rows: usize,
+    /// Max number of rows in copy circuit
+    pub max_copy_rows: usize,
+}
+
+/// Returns the number of padding rows to enable after the rows of
+/// `copy_events`, or the row counts if they don't fit in `max_copy_rows`.
+pub fn copy_filler_rows(
+    copy_events: &[CopyEvent],
+    max_copy_rows: usize,
+) -> Result<usize, CopyRowsOverflow> {
+    let copy_rows_needed = copy_events.iter().map(|c| c.bytes.len() * 2).sum::<usize>();
+    let reserved_rows = DISABLED_ROWS + UNUSED_ROWS;
+
+    if copy_rows_needed + reserved_rows > max_copy_rows {
+        return Err(CopyRowsOverflow {
+            copy_rows_needed,
+            reserved_rows,
+            max_copy_rows,
+        });
+    }
+    Ok(max_copy_rows - copy_rows_needed - DISABLED_ROWS)
+}
+
 /// The rw table shared between evm circuit and state circuit
 #[derive(Clone, Debug)]
 pub struct CopyCircuitConfig<F> {
@@ -244,6 +277,32 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
+        meta.create_gate("padding row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            for (name, column) in [
+                ("is_first == 0 for padding row", is_first),
+                ("is_last == 0 for padding row", is_last),
+                ("id_lo == 0 for padding row", id.lo()),
+                ("id_hi == 0 for padding row", id.hi()),
+                ("addr == 0 for padding row", addr),
+                ("bytes_left == 0 for padding row", bytes_left),
+                ("value == 0 for padding row", value),
+                ("value_acc_rlc == 0 for padding row", value_acc_rlc),
+                ("rlc_acc == 0 for padding row", rlc_acc),
+                ("rw_counter == 0 for padding row", rw_counter),
+                ("rwc_inc_left == 0 for padding row", rwc_inc_left),
+                ("is_code == 0 for padding row", is_code),
+            ] {
+                cb.require_zero(name, meta.query_advice(column, Rotation::cur()));
+            }
+
+            cb.gate(and::expr([
+                meta.query_fixed(q_enable, Rotation::cur()),
+                tag.value_equals(CopyDataType::Padding, Rotation::cur())(meta),
+            ]))
+        });
+
         meta.create_gate(
             "Last Step (check value accumulator) Memory => Bytecode or RlcAcc",
             |meta: &mut halo2_proofs::plonk::VirtualCells<F>| {
@@ -605,13 +664,10 @@ impl<F: Field> CopyCircuitConfig<F> {
         max_copy_rows: usize,
         challenges: Challenges<Value<F>>,
     ) -> Result<(), Error> {
-        let copy_rows_needed = copy_events.iter().map(|c| c.bytes.len() * 2).sum::<usize>();
-
-        assert!(
-            copy_rows_needed + DISABLED_ROWS + UNUSED_ROWS <= max_copy_rows,
-            "copy rows not enough {copy_rows_needed} + 4 vs {max_copy_rows}"
-        );
-        let filler_rows = max_copy_rows - copy_rows_needed - DISABLED_ROWS;
+        let filler_rows = copy_filler_rows(copy_events, max_copy_rows).map_err(|err| {
+            log::error!("copy padding overflow: {err}");
+            Error::Synthesis
+        })?;
 
         let tag_chip = BinaryNumberChip::construct(self.copy_table_tag);
         let lt_chip = LtChip::construct(self.addr_lt_addr_end);
@@ -854,6 +910,28 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
         6
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let tx_table = TxTable::construct(meta);
+            let rw_table = RwTable::construct(meta);
+            let bytecode_table = BytecodeTable::construct(meta);
+            let q_enable = meta.fixed_column();
+            let copy_table = CopyTable::construct(meta, q_enable);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            CopyCircuitConfig::new(
+                meta,
+                CopyCircuitConfigArgs {
+                    tx_table,
+                    rw_table,
+                    bytecode_table,
+                    copy_table,
+                    q_enable,
+                    challenges,
+                },
+            );
+        })
+    }
+
     fn new_from_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> Self {
         let chunked_copy_events = block
             .copy_events
@@ -2,7 +2,7 @@ use crate::{
     copy_circuit::*,
     evm_circuit::{test::rand_bytes, witness::block_convert},
     util::unusable_rows,
-    witness::{chunk_convert, Block},
+    witness::{Block, Chunk},
 };
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, FixedCParams},
@@ -10,8 +10,10 @@ use bus_mapping::{
 };
 use eth_types::{bytecode, geth_types::GethData, ToWord, Word};
 use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem, Error},
 };
 use mock::{
     test_ctx::helpers::account_0_code_account_1_no_code, Sha3CodeGen, TestContext, MOCK_ACCOUNTS,
@@ -180,7 +182,7 @@ fn gen_tx_log_data() -> CircuitInputBuilder<FixedCParams> {
 fn copy_circuit_valid_calldatacopy() {
     let builder = gen_calldatacopy_data();
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     assert_eq!(test_copy_circuit_from_block(14, block, chunk), Ok(()));
 }
 
@@ -188,7 +190,7 @@ fn copy_circuit_valid_calldatacopy() {
 fn copy_circuit_valid_codecopy() {
     let builder = gen_codecopy_data();
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     assert_eq!(test_copy_circuit_from_block(10, block, chunk), Ok(()));
 }
 
@@ -196,7 +198,7 @@ fn copy_circuit_valid_codecopy() {
 fn copy_circuit_valid_extcodecopy() {
     let builder = gen_extcodecopy_data();
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     assert_eq!(test_copy_circuit_from_block(14, block, chunk), Ok(()));
 }
 
@@ -204,7 +206,7 @@ fn copy_circuit_valid_extcodecopy() {
 fn copy_circuit_valid_sha3() {
     let builder = gen_sha3_data();
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     assert_eq!(test_copy_circuit_from_block(14, block, chunk), Ok(()));
 }
 
@@ -212,7 +214,7 @@ fn copy_circuit_valid_sha3() {
 fn copy_circuit_valid_tx_log() {
     let builder = gen_tx_log_data();
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     assert_eq!(test_copy_circuit_from_block(10, block, chunk), Ok(()));
 }
 
@@ -225,7 +227,7 @@ fn copy_circuit_invalid_calldatacopy() {
         builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
 
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
 
     assert_error_matches(
         test_copy_circuit_from_block(14, block, chunk),
@@ -242,7 +244,7 @@ fn copy_circuit_invalid_codecopy() {
         builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
 
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
 
     assert_error_matches(
         test_copy_circuit_from_block(10, block, chunk),
@@ -259,7 +261,7 @@ fn copy_circuit_invalid_extcodecopy() {
         builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
 
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
 
     assert_error_matches(
         test_copy_circuit_from_block(14, block, chunk),
@@ -276,7 +278,7 @@ fn copy_circuit_invalid_sha3() {
         builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
 
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
 
     assert_error_matches(
         test_copy_circuit_from_block(14, block, chunk),
@@ -293,7 +295,7 @@ fn copy_circuit_invalid_tx_log() {
         builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
 
     let block = block_convert::<Fr>(&builder).unwrap();
-    let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
 
     assert_error_matches(
         test_copy_circuit_from_block(10, block, chunk),
@@ -301,6 +303,138 @@ fn copy_circuit_invalid_tx_log() {
     );
 }
 
+#[test]
+fn copy_circuit_padding_overflow() {
+    let builder = gen_tx_log_data();
+    let block = block_convert::<Fr>(&builder).unwrap();
+    let copy_rows_needed = block
+        .copy_events
+        .iter()
+        .map(|c| c.bytes.len() * 2)
+        .sum::<usize>();
+
+    assert_eq!(
+        copy_filler_rows(&block.copy_events, copy_rows_needed),
+        Err(CopyRowsOverflow {
+            copy_rows_needed,
+            reserved_rows: 4,
+            max_copy_rows: copy_rows_needed,
+        })
+    );
+
+    let circuit = CopyCircuit::<Fr>::new(block.copy_events, copy_rows_needed);
+    let result = MockProver::<Fr>::run(10, &circuit, vec![]);
+    assert!(matches!(result, Err(Error::Synthesis)));
+}
+
+/// Copy circuit without copy events, whose `value` cell at `tampered_row`, if any, is set to one
+/// after the padding rows are assigned
+struct TamperedPaddingCircuit {
+    max_copy_rows: usize,
+    tampered_row: Option<usize>,
+}
+
+impl Circuit<Fr> for TamperedPaddingCircuit {
+    type Config = <CopyCircuit<Fr> as Circuit<Fr>>::Config;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self { ..*self }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        CopyCircuit::<Fr>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        (config, _): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let external_data = ExternalData::default();
+        config.tx_table.load(
+            &mut layouter,
+            &external_data.txs,
+            external_data.max_txs,
+            external_data.max_calldata,
+        )?;
+        config.rw_table.load(
+            &mut layouter,
+            &external_data.rws.table_assignments(true),
+            external_data.max_rws,
+            external_data.prev_chunk_last_rw,
+        )?;
+        config
+            .bytecode_table
+            .load(&mut layouter, external_data.bytecodes)?;
+
+        let tag_chip = BinaryNumberChip::construct(config.copy_table_tag);
+        let lt_chip = LtChip::construct(config.addr_lt_addr_end);
+        lt_chip.load(&mut layouter)?;
+
+        let filler_rows = copy_filler_rows(&[], self.max_copy_rows).unwrap();
+        layouter.assign_region(
+            || "assign copy table",
+            |mut region| {
+                let mut offset = 0;
+                for _ in 0..filler_rows {
+                    config.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        true,
+                        &tag_chip,
+                        &lt_chip,
+                    )?;
+                }
+                for _ in 0..DISABLED_ROWS {
+                    config.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        false,
+                        &tag_chip,
+                        &lt_chip,
+                    )?;
+                }
+                if let Some(row) = self.tampered_row {
+                    region.assign_advice(
+                        || "tampered value",
+                        config.value,
+                        row,
+                        || Value::known(Fr::from(1)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn copy_circuit_non_zero_padding_row() {
+    let run = |tampered_row| {
+        let circuit = TamperedPaddingCircuit {
+            max_copy_rows: 32,
+            tampered_row,
+        };
+        MockProver::<Fr>::run(10, &circuit, vec![])
+            .unwrap()
+            .verify()
+    };
+    assert_eq!(run(None), Ok(()));
+
+    let errors = run(Some(3)).expect_err("a non-zero padding row is accepted");
+    assert!(
+        errors.iter().any(|error| matches!(
+            error,
+            VerifyFailure::ConstraintNotSatisfied { constraint, .. }
+                if constraint.to_string().contains("value == 0 for padding row")
+        )),
+        "{:?}",
+        errors
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     let builder = gen_tx_log_data();
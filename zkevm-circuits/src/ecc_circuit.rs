@@ -41,7 +41,7 @@ use crate::{
     evm_circuit::{param::N_BYTES_WORD, EvmCircuit},
     keccak_circuit::KeccakCircuit,
     table::{EccTable, LookupTable},
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, Challenges, SubCircuit, SubCircuitConfig},
     witness::Block,
 };
 
@@ -1335,6 +1335,20 @@ impl<F: Field, const XI_0: i64> SubCircuit<F> for EccCircuit<F, XI_0> {
         .unwrap()
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let ecc_table = EccTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            EccCircuitConfig::new(
+                meta,
+                EccCircuitConfigArgs {
+                    ecc_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     fn synthesize_sub(
         &self,
         config: &Self::Config,
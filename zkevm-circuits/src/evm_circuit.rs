@@ -16,7 +16,10 @@ pub(crate) mod util;
 pub(crate) mod test;
 #[cfg(feature = "test-circuits")]
 pub use self::EvmCircuit as TestEvmCircuit;
-use self::{step::HasExecutionState, witness::rw::ToVec};
+use self::{
+    step::{ExecutionState, HasExecutionState},
+    witness::rw::ToVec,
+};
 
 pub use crate::witness;
 use crate::{
@@ -25,15 +28,22 @@ use crate::{
         BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable,
         SigTable, TxTable, UXTable,
     },
-    util::{chunk_ctx::ChunkContextConfig, Challenges, SubCircuit, SubCircuitConfig},
+    util::{
+        chunk_ctx::ChunkContextConfig, count_advice_columns, log2_ceil, Challenges, SubCircuit,
+        SubCircuitConfig,
+    },
     witness::{Chunk, RwMap},
 };
-use bus_mapping::{circuit_input_builder::FeatureConfig, evm::OpcodeId};
+use bus_mapping::{
+    circuit_input_builder::{FeatureConfig, Hardfork},
+    evm::OpcodeId,
+};
 use eth_types::Field;
 use execution::ExecutionConfig;
 use itertools::Itertools;
+use std::collections::HashSet;
 use strum::IntoEnumIterator;
-use table::FixedTableTag;
+use table::{CustomFixedTables, FixedTableTag};
 use witness::Block;
 
 /// EvmCircuitConfig implements verification of execution trace of a block.
@@ -91,6 +101,11 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub chunk_ctx_config: ChunkContextConfig<F>,
     /// Feature config
     pub feature_config: FeatureConfig,
+    /// Extra fixed table rows for experimental gadgets
+    pub custom_fixed_tables: CustomFixedTables<F>,
+    /// Execution states to configure, all of them if None.  The internal states are always
+    /// enabled, and assigning a step of a disabled state fails.
+    pub enabled_execution_states: Option<HashSet<ExecutionState>>,
 }
 
 impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
@@ -113,8 +128,24 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             sig_table,
             chunk_ctx_config,
             feature_config,
+            custom_fixed_tables,
+            enabled_execution_states,
         }: Self::ConfigArgs,
     ) -> Self {
+        // The gas left at the start of a tx and the rw permutation must be
+        // constrained in a real circuit
+        #[cfg(not(any(test, feature = "test-util")))]
+        {
+            assert!(
+                !feature_config.override_gas_left,
+                "FeatureConfig::override_gas_left is only available in tests"
+            );
+            assert!(
+                !feature_config.disable_rw_permutation,
+                "FeatureConfig::disable_rw_permutation is only available in tests"
+            );
+        }
+
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
 
         let execution = Box::new(ExecutionConfig::configure(
@@ -135,6 +166,8 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &chunk_ctx_config.is_first_chunk,
             &chunk_ctx_config.is_last_chunk,
             feature_config,
+            custom_fixed_tables,
+            enabled_execution_states,
         ));
 
         fixed_table.iter().enumerate().for_each(|(idx, &col)| {
@@ -181,17 +214,25 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
 }
 
 impl<F: Field> EvmCircuitConfig<F> {
-    /// Load fixed table
+    /// Load fixed table, with the rows of the custom fixed tables appended after
+    /// the given tags. The precompile info rows hold the base gas costs of
+    /// `hardfork`.
     pub fn load_fixed_table(
         &self,
         layouter: &mut impl Layouter<F>,
         fixed_table_tags: Vec<FixedTableTag>,
+        hardfork: Hardfork,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "fixed table",
             |mut region| {
                 for (offset, row) in std::iter::once([F::ZERO; 4])
-                    .chain(fixed_table_tags.iter().flat_map(|tag| tag.build()))
+                    .chain(
+                        fixed_table_tags
+                            .iter()
+                            .flat_map(|tag| tag.build_with_hardfork(hardfork)),
+                    )
+                    .chain(self.execution.custom_fixed_tables.build())
                     .enumerate()
                 {
                     for (column, value) in self.fixed_table.iter().zip_eq(row) {
@@ -213,6 +254,29 @@ pub struct EvmCircuit<F: Field> {
     /// Chunk
     pub chunk: Option<Chunk<F>>,
     fixed_table_tags: Vec<FixedTableTag>,
+    custom_fixed_tables: CustomFixedTables<F>,
+    enabled_execution_states: Option<HashSet<ExecutionState>>,
+}
+
+/// EVM Circuit configuration parameters
+#[derive(Clone, Debug, Default)]
+pub struct EvmCircuitParams<F> {
+    /// Feature config
+    pub feature_config: FeatureConfig,
+    /// Extra fixed table rows for experimental gadgets
+    pub custom_fixed_tables: CustomFixedTables<F>,
+    /// Execution states to configure, all of them if None.  The internal
+    /// states are always enabled.
+    pub enabled_execution_states: Option<HashSet<ExecutionState>>,
+}
+
+/// A step of the block is in an execution state that isn't enabled in the
+/// circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("execution state {execution_state:?} is not enabled in this circuit")]
+pub struct DisabledExecutionState {
+    /// Execution state of the step
+    pub execution_state: ExecutionState,
 }
 
 impl<F: Field> EvmCircuit<F> {
@@ -222,8 +286,57 @@ impl<F: Field> EvmCircuit<F> {
             block: Some(block),
             chunk: Some(chunk),
             fixed_table_tags: FixedTableTag::iter().collect(),
+            custom_fixed_tables: CustomFixedTables::default(),
+            enabled_execution_states: None,
         }
     }
+
+    /// Load the rows of `custom_fixed_tables` in the fixed table, after the
+    /// standard tags, for experimental gadgets to look them up.
+    pub fn with_custom_fixed_tables(mut self, custom_fixed_tables: CustomFixedTables<F>) -> Self {
+        self.custom_fixed_tables = custom_fixed_tables;
+        self
+    }
+
+    /// Only configure the gadgets of `enabled_execution_states` and of the
+    /// internal states.  The selectors of the other states are constrained to
+    /// zero, so a block with a step in one of them can't be proven.
+    pub fn with_enabled_execution_states(
+        mut self,
+        enabled_execution_states: HashSet<ExecutionState>,
+    ) -> Self {
+        self.enabled_execution_states = Some(enabled_execution_states);
+        self
+    }
+
+    /// Returns the first step of the block whose execution state isn't
+    /// enabled in the circuit, if any.
+    pub fn check_enabled_execution_states(&self) -> Result<(), DisabledExecutionState> {
+        let (Some(states), Some(block)) = (&self.enabled_execution_states, &self.block) else {
+            return Ok(());
+        };
+        match block
+            .txs
+            .iter()
+            .flat_map(|tx| tx.steps())
+            .map(|step| step.execution_state())
+            .find(|state| !state.is_internal() && !states.contains(state))
+        {
+            Some(execution_state) => Err(DisabledExecutionState { execution_state }),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of rows of the fixed table: the all-zero first row, the rows
+    /// of the fixed table tags and the ones of the custom fixed tables.
+    pub(crate) fn num_rows_required_for_fixed_table(&self) -> usize {
+        1 + self
+            .fixed_table_tags
+            .iter()
+            .map(|tag| tag.build::<F>().count())
+            .sum::<usize>()
+            + self.custom_fixed_tables.num_rows()
+    }
     #[cfg(any(test, feature = "test-circuits"))]
     /// Construct the EvmCircuit with only subset of Fixed table tags required by tests to save
     /// testing time
@@ -233,9 +346,22 @@ impl<F: Field> EvmCircuit<F> {
             block: Some(block),
             chunk: Some(chunk),
             fixed_table_tags,
+            custom_fixed_tables: CustomFixedTables::default(),
+            enabled_execution_states: None,
         }
     }
     #[cfg(any(test, feature = "test-circuits"))]
+    /// Degree of the circuit needed by the block, the chunk and the fixed
+    /// table, custom fixed tables included.
+    pub(crate) fn get_test_degree(&self) -> u32 {
+        let block = self.block.as_ref().unwrap();
+        let chunk = self.chunk.as_ref().unwrap();
+        std::cmp::max(
+            block.get_test_degree(chunk),
+            log2_ceil(Self::unusable_rows() + self.num_rows_required_for_fixed_table()),
+        )
+    }
+    #[cfg(any(test, feature = "test-circuits"))]
     /// Calculate which rows are "actually" used in the circuit
     pub(crate) fn get_active_rows(block: &Block<F>, chunk: &Chunk<F>) -> (Vec<usize>, Vec<usize>) {
         let max_offset = Self::get_num_rows_required(block, chunk);
@@ -256,21 +382,30 @@ impl<F: Field> EvmCircuit<F> {
             chunk.fixed_param.max_evm_rows + 1
         }
     }
-    /// Compute the minimum number of rows required to process the block
+    /// Compute the minimum number of rows required to process the block: the
+    /// rows of the BeginChunk step and of the steps of the chunk, plus one row
+    /// for EndBlock/EndChunk and at least one unused one
     fn get_min_num_rows_required(block: &Block<F>, chunk: &Chunk<F>) -> usize {
-        let mut num_rows = 0;
-        for transaction in &block.txs {
-            for step in transaction.steps() {
-                if chunk.chunk_context.initial_rwc <= step.rwc.0
-                    || step.rwc.0 < chunk.chunk_context.end_rwc
-                {
-                    num_rows += step.execution_state().get_step_height();
-                }
-            }
-        }
+        let chunk_txs = block
+            .txs
+            .get(chunk.chunk_context.initial_tx_index..chunk.chunk_context.end_tx_index)
+            .unwrap_or_default();
+        let begin_chunk_rows = chunk
+            .begin_chunk
+            .as_ref()
+            .map(|step| step.execution_state().step_height())
+            .unwrap_or_default();
+        let step_rows: usize = chunk_txs
+            .iter()
+            .flat_map(|tx| tx.steps())
+            .filter(|step| {
+                chunk.chunk_context.initial_rwc <= step.rwc.0
+                    && step.rwc.0 < chunk.chunk_context.end_rwc
+            })
+            .map(|step| step.execution_state().step_height())
+            .sum();
 
-        // It must have one row for EndBlock/EndChunk and at least one unused one
-        num_rows + 2
+        begin_chunk_rows + step_rows + 2
     }
 }
 
@@ -283,6 +418,12 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
         MAX_STEP_HEIGHT + STEP_STATE_HEIGHT + 3
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            <Self as Circuit<F>>::configure_with_params(meta, EvmCircuitParams::default());
+        })
+    }
+
     fn new_from_block(block: &witness::Block<F>, chunk: &witness::Chunk<F>) -> Self {
         Self::new(block.clone(), chunk.clone())
     }
@@ -314,12 +455,50 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
         let block = self.block.as_ref().unwrap();
         let chunk = self.chunk.as_ref().unwrap();
 
-        config.load_fixed_table(layouter, self.fixed_table_tags.clone())?;
+        if let Err(err) = self.check_enabled_execution_states() {
+            log::error!("{}", err);
+            return Err(Error::Synthesis);
+        }
+
+        let disable_rw_permutation = block.feature_config.disable_rw_permutation;
+        if disable_rw_permutation && !cfg!(any(test, feature = "test-util")) {
+            log::error!("rw permutation can only be disabled in tests");
+            return Err(Error::Synthesis);
+        }
+        if disable_rw_permutation && chunk.chunk_context.total_chunks > 1 {
+            log::error!(
+                "rw permutation can't be disabled for a block of {} chunks",
+                chunk.chunk_context.total_chunks
+            );
+            return Err(Error::Synthesis);
+        }
+
+        config.load_fixed_table(
+            layouter,
+            self.fixed_table_tags.clone(),
+            block.feature_config.hardfork,
+        )?;
 
         let _max_offset_index = config
             .execution
             .assign_block(layouter, block, chunk, challenges)?;
 
+        if disable_rw_permutation {
+            // Only the rw table is needed for the lookups of the execution steps.
+            return layouter.assign_region(
+                || "evm circuit",
+                |mut region| {
+                    config.rw_table.load_with_region(
+                        &mut region,
+                        &chunk.chrono_rws.table_assignments(true),
+                        chunk.fixed_param.max_rws,
+                        chunk.prev_chunk_last_chrono_rw,
+                    )?;
+                    Ok(())
+                },
+            );
+        }
+
         let (rw_rows_padding, _) = RwMap::table_assignments_padding(
             &chunk.chrono_rws.table_assignments(true),
             chunk.fixed_param.max_rws,
@@ -400,6 +579,10 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
             ],
         ]
     }
+
+    fn instance_layout() -> Vec<usize> {
+        vec![5, 6]
+    }
 }
 
 /// create fixed_table_tags needed given witness block
@@ -442,7 +625,8 @@ pub(crate) mod cached {
         static ref CACHE: Cache = {
             let mut meta = ConstraintSystem::<Fr>::default();
             // Cached EVM circuit is configured with Mainnet FeatureConfig
-            let config = EvmCircuit::<Fr>::configure_with_params(&mut meta, FeatureConfig::default());
+            let config =
+                EvmCircuit::<Fr>::configure_with_params(&mut meta, EvmCircuitParams::default());
             Cache { cs: meta, config }
         };
     }
@@ -488,25 +672,110 @@ pub(crate) mod cached {
     }
 }
 
-// Always exported because of `EXECUTION_STATE_HEIGHT_MAP`
-impl<F: Field> Circuit<F> for EvmCircuit<F> {
-    type Config = (EvmCircuitConfig<F>, Challenges);
-    type FloorPlanner = SimpleFloorPlanner;
-    type Params = FeatureConfig;
+#[cfg(any(feature = "test-util", test))]
+pub(crate) mod mock_challenges {
+    use super::*;
+    use halo2_proofs::{arithmetic::Field as _, halo2curves::bn256::Fr};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    /// Wrapper over the EvmCircuit that replaces the challenges of the prover
+    /// with constant mock values derived from a `seed`.  Running the same
+    /// witness with different seeds catches constraints that only hold for
+    /// specific challenge values.
+    pub struct EvmCircuitMockChallenges {
+        circuit: EvmCircuit<Fr>,
+        seed: u64,
+    }
 
-    fn without_witnesses(&self) -> Self {
-        Self::default()
+    /// The keccak input and lookup input challenges for `seed`, which are
+    /// distinct from each other.
+    fn mock_challenges(seed: u64) -> (Fr, Fr) {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let keccak_input = Fr::random(&mut rng);
+        let lookup_input = Fr::random(&mut rng);
+        assert_ne!(keccak_input, lookup_input);
+        (keccak_input, lookup_input)
     }
 
-    /// Try to get the [`FeatureConfig`] from the block or fallback to default
-    fn params(&self) -> Self::Params {
-        self.block
-            .as_ref()
-            .map(|block| block.feature_config)
-            .unwrap_or_default()
+    impl Circuit<Fr> for EvmCircuitMockChallenges {
+        type Config = EvmCircuitConfig<Fr>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = (EvmCircuitParams<Fr>, u64);
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                circuit: self.circuit.without_witnesses(),
+                seed: self.seed,
+            }
+        }
+
+        fn params(&self) -> Self::Params {
+            (self.circuit.params(), self.seed)
+        }
+
+        fn configure_with_params(
+            meta: &mut ConstraintSystem<Fr>,
+            (params, seed): Self::Params,
+        ) -> Self::Config {
+            let (keccak_input, lookup_input) = mock_challenges(seed);
+            let (config, _) = EvmCircuit::configure_with_challenges(meta, params, |_| {
+                (
+                    (),
+                    Challenges::mock(
+                        Expression::Constant(keccak_input),
+                        Expression::Constant(lookup_input),
+                    ),
+                )
+            });
+            config
+        }
+
+        fn configure(_meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            unreachable!();
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let (keccak_input, lookup_input) = mock_challenges(self.seed);
+            self.circuit.synthesize_with_challenges(
+                &config,
+                &Challenges::mock(Value::known(keccak_input), Value::known(lookup_input)),
+                &mut layouter,
+            )
+        }
     }
 
-    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
+    impl EvmCircuitMockChallenges {
+        pub(crate) fn get_test_circuit_from_block(
+            block: Block<Fr>,
+            chunk: Chunk<Fr>,
+            seed: u64,
+        ) -> Self {
+            Self {
+                circuit: EvmCircuit::<Fr>::get_test_circuit_from_block(block, chunk),
+                seed,
+            }
+        }
+
+        pub(crate) fn instance(&self) -> Vec<Vec<Fr>> {
+            self.circuit.instance()
+        }
+    }
+}
+
+impl<F: Field> EvmCircuit<F> {
+    /// Configure the EVM circuit and its tables with the challenges returned by
+    /// `challenges`, which is called right after the tables are constructed and
+    /// may return any extra config needed to later compute the challenge
+    /// values.
+    pub(crate) fn configure_with_challenges<C>(
+        meta: &mut ConstraintSystem<F>,
+        params: EvmCircuitParams<F>,
+        challenges: impl FnOnce(&mut ConstraintSystem<F>) -> (C, Challenges<Expression<F>>),
+    ) -> (EvmCircuitConfig<F>, C) {
         let tx_table = TxTable::construct(meta);
         let rw_table = RwTable::construct(meta);
         let bytecode_table = BytecodeTable::construct(meta);
@@ -517,8 +786,7 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let exp_table = ExpTable::construct(meta);
         let u8_table = UXTable::construct(meta);
         let u16_table = UXTable::construct(meta);
-        let challenges = Challenges::construct(meta);
-        let challenges_expr = challenges.exprs(meta);
+        let (challenges_config, challenges_expr) = challenges(meta);
         let chunk_ctx_config = ChunkContextConfig::new(meta, &challenges_expr);
 
         let sig_table = SigTable::construct(meta);
@@ -538,69 +806,122 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     u16_table,
                     sig_table,
                     chunk_ctx_config,
-                    feature_config: params,
+                    feature_config: params.feature_config,
+                    custom_fixed_tables: params.custom_fixed_tables,
+                    enabled_execution_states: params.enabled_execution_states,
                 },
             ),
-            challenges,
+            challenges_config,
         )
     }
 
-    fn configure(_meta: &mut ConstraintSystem<F>) -> Self::Config {
-        unreachable!();
-    }
-
-    fn synthesize(
+    /// Load the tables and assign the witness of the EVM circuit with the
+    /// given challenge values.
+    pub(crate) fn synthesize_with_challenges(
         &self,
-        config: Self::Config,
-        mut layouter: impl Layouter<F>,
+        config: &EvmCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
         let block = self.block.as_ref().unwrap();
         let chunk = self.chunk.as_ref().unwrap();
 
-        let (config, challenges) = config;
-        let challenges = challenges.values(&mut layouter);
-
         config.tx_table.load(
-            &mut layouter,
+            layouter,
             &block.txs,
             chunk.fixed_param.max_txs,
             chunk.fixed_param.max_calldata,
         )?;
-        chunk.chrono_rws.check_rw_counter_sanity();
+        if !block.skip_rw_value_check {
+            chunk.chrono_rws.check_rw_counter_sanity();
+        }
         config
             .bytecode_table
-            .load(&mut layouter, block.bytecodes.clone())?;
-        config.block_table.load(&mut layouter, &block.context)?;
-        config
-            .copy_table
-            .load(&mut layouter, block, chunk, &challenges)?;
+            .load(layouter, block.bytecodes.clone())?;
+        config.block_table.load(layouter, &block.context)?;
+        config.copy_table.load(layouter, block, chunk, challenges)?;
         config
             .keccak_table
-            .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
-        config.exp_table.load(&mut layouter, block, chunk)?;
+            .dev_load(layouter, &block.sha3_inputs, challenges)?;
+        config.exp_table.load(layouter, block, chunk)?;
 
-        config.u8_table.load(&mut layouter)?;
-        config.u16_table.load(&mut layouter)?;
-        config.sig_table.dev_load(&mut layouter, block)?;
+        config.u8_table.load(layouter)?;
+        config.u16_table.load(layouter)?;
+        config.sig_table.dev_load(layouter, block)?;
 
         // synthesize chunk context
         config.chunk_ctx_config.assign_chunk_context(
-            &mut layouter,
+            layouter,
             &chunk.chunk_context,
             Self::get_num_rows_required(block, chunk) - 1,
         )?;
 
-        self.synthesize_sub(&config, &challenges, &mut layouter)
+        self.synthesize_sub(config, challenges, layouter)
+    }
+}
+
+// Always exported because of `EXECUTION_STATE_HEIGHT_MAP`
+impl<F: Field> Circuit<F> for EvmCircuit<F> {
+    type Config = (EvmCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = EvmCircuitParams<F>;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            custom_fixed_tables: self.custom_fixed_tables.clone(),
+            enabled_execution_states: self.enabled_execution_states.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Try to get the [`FeatureConfig`] from the block or fallback to default
+    fn params(&self) -> Self::Params {
+        EvmCircuitParams {
+            feature_config: self
+                .block
+                .as_ref()
+                .map(|block| block.feature_config)
+                .unwrap_or_default(),
+            custom_fixed_tables: self.custom_fixed_tables.clone(),
+            enabled_execution_states: self.enabled_execution_states.clone(),
+        }
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
+        Self::configure_with_challenges(meta, params, |meta| {
+            let challenges = Challenges::construct(meta);
+            let challenges_expr = challenges.exprs(meta);
+            (challenges, challenges_expr)
+        })
+    }
+
+    fn configure(_meta: &mut ConstraintSystem<F>) -> Self::Config {
+        unreachable!();
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let (config, challenges) = config;
+        let challenges = challenges.values(&mut layouter);
+
+        self.synthesize_with_challenges(&config, &challenges, &mut layouter)
     }
 }
 
 #[cfg(test)]
 mod evm_circuit_stats {
     use crate::{
-        evm_circuit::EvmCircuit,
+        evm_circuit::{
+            execution::ExecutionConfig, param::STEP_WIDTH, step::ExecutionState, table::Table,
+            EvmCircuit, EvmCircuitConfig, EvmCircuitParams,
+        },
+        super_circuit::SuperCircuit,
         test_util::CircuitTestBuilder,
-        util::{unusable_rows, SubCircuit},
-        witness::{block_convert, chunk_convert},
+        util::{unusable_rows, Challenges, SubCircuit},
+        witness::{block_convert, Block, Chunk},
     };
     use bus_mapping::{
         circuit_input_builder::{FeatureConfig, FixedCParams},
@@ -608,7 +929,15 @@ mod evm_circuit_stats {
     };
 
     use eth_types::{address, bytecode, geth_types::GethData, Word};
-    use halo2_proofs::{self, dev::MockProver, halo2curves::bn256::Fr};
+    use halo2_proofs::{
+        self,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use itertools::Itertools;
+    use std::{cmp::Reverse, collections::BTreeMap};
 
     use mock::test_ctx::{
         helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
@@ -618,9 +947,12 @@ mod evm_circuit_stats {
     #[test]
     fn evm_circuit_unusable_rows() {
         let computed = EvmCircuit::<Fr>::unusable_rows();
-        let mainnet_config = FeatureConfig::default();
-        let invalid_tx_config = FeatureConfig {
-            invalid_tx: true,
+        let mainnet_config = EvmCircuitParams::default();
+        let invalid_tx_config = EvmCircuitParams {
+            feature_config: FeatureConfig {
+                invalid_tx: true,
+                ..Default::default()
+            },
             ..Default::default()
         };
 
@@ -634,6 +966,206 @@ mod evm_circuit_stats {
         )
     }
 
+    #[test]
+    fn evm_circuit_no_unused_stored_expressions() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (config, _) =
+            EvmCircuit::<Fr>::configure_with_params(&mut meta, EvmCircuitParams::default());
+        let unused = config.execution.instrument().unused_stored_expressions();
+        assert!(unused.is_empty(), "unused stored expressions: {:?}", unused);
+    }
+
+    #[test]
+    fn evm_circuit_cell_usage() {
+        let cell_usage = ExecutionState::step_heights();
+
+        assert!(cell_usage.values().all(|height| *height > 0));
+
+        // Print the gadgets that dominate the step width first
+        println!("| execution state | height | cells |");
+        for (execution_state, height) in cell_usage
+            .into_iter()
+            .sorted_by_key(|(_, height)| Reverse(*height))
+        {
+            println!(
+                "| {:?} | {} | {} |",
+                execution_state,
+                height,
+                height * STEP_WIDTH
+            );
+        }
+    }
+
+    #[test]
+    fn evm_circuit_lookup_counts() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (config, _) =
+            EvmCircuit::<Fr>::configure_with_params(&mut meta, EvmCircuitParams::default());
+
+        // Lookup arguments of the constraint system per table
+        let lookup_counts = ExecutionConfig::<Fr>::lookup_counts(&meta);
+        let baseline = [
+            (Table::U8, 24),
+            (Table::Fixed, 8),
+            (Table::Tx, 4),
+            (Table::Rw, 13),
+            (Table::Bytecode, 4),
+            (Table::Block, 1),
+            (Table::Copy, 1),
+            (Table::Keccak, 1),
+            (Table::Exp, 1),
+            (Table::Sig, 1),
+            (Table::ChunkCtx, 1),
+        ]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+        assert_eq!(lookup_counts, baseline);
+
+        // Lookups of a few gadgets. Every gadget built on `SameContextGadget` looks up its
+        // opcode in the bytecode and fixed tables, and range checks the gas left with 8 bytes.
+        let gadget_lookup_counts = config.execution.instrument().lookup_counts();
+        for (execution_state, expected) in [
+            (
+                ExecutionState::PC,
+                // The program counter is pushed as 8 bytes
+                vec![
+                    (Table::U8, 16),
+                    (Table::Fixed, 1),
+                    (Table::Rw, 1),
+                    (Table::Bytecode, 1),
+                ],
+            ),
+            (
+                ExecutionState::CALLVALUE,
+                vec![
+                    (Table::U8, 8),
+                    (Table::Fixed, 1),
+                    (Table::Rw, 2),
+                    (Table::Bytecode, 1),
+                ],
+            ),
+            (
+                ExecutionState::ORIGIN,
+                // The origin is pushed as 20 bytes
+                vec![
+                    (Table::U8, 28),
+                    (Table::Fixed, 1),
+                    (Table::Tx, 1),
+                    (Table::Rw, 2),
+                    (Table::Bytecode, 1),
+                ],
+            ),
+        ] {
+            assert_eq!(
+                gadget_lookup_counts[&execution_state],
+                expected.into_iter().collect::<BTreeMap<_, _>>(),
+                "lookups of {:?}",
+                execution_state
+            );
+        }
+    }
+
+    #[cfg(feature = "named-lookups")]
+    #[test]
+    fn evm_circuit_named_lookups() {
+        use std::collections::HashSet;
+        use strum::IntoEnumIterator;
+
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (config, _) =
+            EvmCircuit::<Fr>::configure_with_params(&mut meta, EvmCircuitParams::default());
+
+        let annotations = config.execution.lookup_annotations();
+        assert!(!annotations.is_empty());
+        // Every annotated column is a distinct lookup column named after its table
+        let columns = annotations
+            .iter()
+            .map(|(column, _)| column.index())
+            .collect::<HashSet<_>>();
+        assert_eq!(columns.len(), annotations.len());
+        for (_, annotation) in annotations.iter() {
+            assert!(
+                Table::iter().any(|table| annotation.starts_with(&format!("{:?} lookup [", table))),
+                "unexpected annotation {}",
+                annotation
+            );
+        }
+        // The opcode lookup of PC in the bytecode table is named after its execution state
+        assert!(annotations.iter().any(|(_, annotation)| {
+            annotation.starts_with("Bytecode lookup [") && annotation.contains("PC: ")
+        }));
+    }
+
+    /// Assigns only the execution steps of a block into a region of fixed capacity.
+    #[derive(Default)]
+    struct CapacityCircuit {
+        block: Option<Block<Fr>>,
+        chunk: Option<Chunk<Fr>>,
+        rows: usize,
+    }
+
+    impl Circuit<Fr> for CapacityCircuit {
+        type Config = (EvmCircuitConfig<Fr>, Challenges);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            EvmCircuit::<Fr>::configure_with_params(meta, EvmCircuitParams::default())
+        }
+
+        fn synthesize(
+            &self,
+            (config, challenges): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let challenges = challenges.values(&mut layouter);
+            let offset = config.execution.assign_block_with_capacity(
+                &mut layouter,
+                self.block.as_ref().unwrap(),
+                self.chunk.as_ref().unwrap(),
+                &challenges,
+                self.rows,
+            )?;
+            assert_eq!(offset, self.rows);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn assign_block_with_capacity() {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = Chunk::single(&block, builder.circuits_params);
+        let required = EvmCircuit::<Fr>::get_min_num_rows_required(&block, &chunk);
+
+        let run = |rows: usize| {
+            let circuit = CapacityCircuit {
+                block: Some(block.clone()),
+                chunk: Some(chunk.clone()),
+                rows,
+            };
+            MockProver::<Fr>::run(12, &circuit, vec![])
+        };
+        assert!(run(required).is_ok());
+        assert!(run(required + 100).is_ok());
+        assert!(run(required - 1).is_err());
+    }
+
     #[test]
     pub fn empty_evm_circuit_no_padding() {
         CircuitTestBuilder::new_from_test_ctx(
@@ -703,7 +1235,7 @@ mod evm_circuit_stats {
             .handle_block(&block.eth_block, &block.geth_traces)
             .unwrap();
         let block = block_convert::<Fr>(&builder).unwrap();
-        let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+        let chunk = Chunk::single(&block, builder.circuits_params);
         let k = block.get_test_degree(&chunk);
         let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block, chunk);
         let instance = circuit.instance();
@@ -728,7 +1260,7 @@ mod evm_circuit_stats {
             .handle_block(&block.eth_block, &block.geth_traces)
             .unwrap();
         let block = block_convert::<Fr>(&builder).unwrap();
-        let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+        let chunk = Chunk::single(&block, builder.circuits_params);
         let k = block.get_test_degree(&chunk);
 
         let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block, chunk);
@@ -751,7 +1283,7 @@ mod evm_circuit_stats {
             .handle_block(&block.eth_block, &block.geth_traces)
             .unwrap();
         let block = block_convert::<Fr>(&builder).unwrap();
-        let chunk = chunk_convert::<Fr>(&block, &builder).unwrap().remove(0);
+        let chunk = Chunk::single(&block, builder.circuits_params);
         let k = block.get_test_degree(&chunk);
         let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block, chunk);
         let instance = circuit.instance();
@@ -774,4 +1306,136 @@ mod evm_circuit_stats {
         assert_eq!(prover1.fixed(), prover2.fixed());
         assert_eq!(prover1.permutation(), prover2.permutation());
     }
+
+    #[test]
+    fn estimate_pk_rows() {
+        let params = FixedCParams {
+            max_evm_rows: 1 << 16,
+            ..Default::default()
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data_with_params(block.clone(), params)
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = Chunk::single(&block, builder.circuits_params);
+
+        let rows = 1 << block.get_test_degree(&chunk);
+        let estimate = EvmCircuit::<Fr>::estimate_pk_rows(&block, &chunk);
+        assert!(
+            estimate <= rows && rows <= 2 * estimate,
+            "estimate {} for {} rows",
+            estimate,
+            rows
+        );
+
+        let num_advice_columns = EvmCircuit::<Fr>::num_advice_columns();
+        assert!(num_advice_columns > 0);
+        assert!(num_advice_columns <= SuperCircuit::<Fr>::num_advice_columns());
+    }
+}
+
+#[cfg(test)]
+mod enabled_execution_states {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData, Bytecode};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+    use mock::test_ctx::{
+        helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    /// The circuit with only the PUSH, ADD_SUB and STOP gadgets enabled on a
+    /// tx executing `code`, and its degree.
+    fn add_sub_only_circuit(code: Bytecode) -> (EvmCircuit<Fr>, u32) {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |b, _| b,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = Chunk::single(&block, builder.circuits_params);
+        let k = block.get_test_degree(&chunk);
+        let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block, chunk)
+            .with_enabled_execution_states(HashSet::from([
+                ExecutionState::PUSH,
+                ExecutionState::ADD_SUB,
+                ExecutionState::STOP,
+            ]));
+        (circuit, k)
+    }
+
+    #[test]
+    fn enabled_execution_states_ok() {
+        let (circuit, k) = add_sub_only_circuit(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            ADD
+            STOP
+        });
+        assert_eq!(circuit.check_enabled_execution_states(), Ok(()));
+        let instance = circuit.instance();
+        let prover = MockProver::<Fr>::run(k, &circuit, instance).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    #[test]
+    fn disabled_execution_state_rejected() {
+        let (circuit, k) = add_sub_only_circuit(bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x02)
+            MUL
+            STOP
+        });
+        assert_eq!(
+            circuit.check_enabled_execution_states(),
+            Err(DisabledExecutionState {
+                execution_state: ExecutionState::MUL
+            })
+        );
+        let instance = circuit.instance();
+        assert!(matches!(
+            MockProver::<Fr>::run(k, &circuit, instance),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn disabled_execution_states_selector_gates() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        EvmCircuit::<Fr>::configure_with_params(
+            &mut meta,
+            EvmCircuitParams {
+                enabled_execution_states: Some(HashSet::from([ExecutionState::ADD_SUB])),
+                ..Default::default()
+            },
+        );
+        let disabled_gates = meta
+            .gates()
+            .iter()
+            .flat_map(|gate| (0..gate.polynomials().len()).map(move |i| gate.constraint_name(i)))
+            .filter(|name| *name == "execution state is disabled")
+            .count();
+        let num_disabled = ExecutionState::iter()
+            .filter(|state| !state.is_internal() && *state != ExecutionState::ADD_SUB)
+            .count();
+        assert_eq!(disabled_gates, num_disabled);
+    }
 }
@@ -8,11 +8,13 @@ use super::{
     step::HasExecutionState,
     util::{instrumentation::Instrument, CachedRegion, StoredExpression},
 };
+#[cfg(any(test, feature = "test-circuits"))]
+use crate::evm_circuit::util::constraint_builder::StepStateTransitionRecord;
 use crate::{
     evm_circuit::{
         param::{EVM_LOOKUP_COLS, MAX_STEP_HEIGHT, N_PHASE2_COLUMNS, STEP_WIDTH},
         step::{ExecutionState, Step},
-        table::Table,
+        table::{CustomFixedTables, Table},
         util::{
             constraint_builder::{
                 BaseConstraintBuilder, ConstrainBuilderCommon, EVMConstraintBuilder,
@@ -40,11 +42,14 @@ use halo2_proofs::{
     poly::Rotation,
 };
 use std::{
-    collections::{BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     iter,
 };
 use strum::IntoEnumIterator;
 
+#[cfg(test)]
+use halo2_proofs::halo2curves::bn256::Fr;
+
 mod add_sub;
 mod addmod;
 mod address;
@@ -201,7 +206,7 @@ use origin::OriginGadget;
 use padding::PaddingGadget;
 use pc::PcGadget;
 use pop::PopGadget;
-use precompiles::{EcrecoverGadget, IdentityGadget};
+use precompiles::{EcPairingGadget, EcrecoverGadget, IdentityGadget};
 use push::PushGadget;
 use return_revert::ReturnRevertGadget;
 use returndatacopy::ReturnDataCopyGadget;
@@ -239,6 +244,11 @@ pub(crate) trait ExecutionGadget<F: Field> {
     ) -> Result<(), Error>;
 }
 
+/// Step state transitions required by each gadget, checked against the assigned
+/// witness in test builds.
+#[cfg(any(test, feature = "test-circuits"))]
+type StepStateTransitionsMap<F> = HashMap<ExecutionState, Vec<StepStateTransitionRecord<F>>>;
+
 #[derive(Clone, Debug)]
 pub struct ExecutionConfig<F> {
     // EVM Circuit selector, which enables all usable rows.  The rows where this selector is
@@ -261,7 +271,13 @@ pub struct ExecutionConfig<F> {
     pub(crate) height_map: HashMap<ExecutionState, usize>,
     stored_expressions_map: HashMap<ExecutionState, Vec<StoredExpression<F>>>,
     debug_expressions_map: HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
+    #[cfg(any(test, feature = "test-circuits"))]
+    step_state_transitions_map: StepStateTransitionsMap<F>,
     instrument: Instrument,
+    // Fixed table rows registered outside of FixedTableTag
+    pub(crate) custom_fixed_tables: CustomFixedTables<F>,
+    // Execution states whose gadget constraints are configured, all of them if None
+    enabled_execution_states: Option<HashSet<ExecutionState>>,
     // internal state gadgets
     begin_tx_gadget: Box<BeginTxGadget<F>>,
     end_block_gadget: Box<EndBlockGadget<F>>,
@@ -360,6 +376,7 @@ pub struct ExecutionConfig<F> {
     // precompile calls
     precompile_ecrecover_gadget: Box<EcrecoverGadget<F>>,
     precompile_identity_gadget: Box<IdentityGadget<F>>,
+    precompile_ec_pairing_gadget: Box<EcPairingGadget<F>>,
     invalid_tx: Option<Box<InvalidTxGadget<F>>>,
 }
 
@@ -386,7 +403,14 @@ impl<F: Field> ExecutionConfig<F> {
         is_first_chunk: &IsZeroConfig<F>,
         is_last_chunk: &IsZeroConfig<F>,
         feature_config: FeatureConfig,
+        custom_fixed_tables: CustomFixedTables<F>,
+        enabled_execution_states: Option<HashSet<ExecutionState>>,
     ) -> Self {
+        // The internal states are part of every block, so they can't be disabled.
+        let enabled_execution_states = enabled_execution_states.map(|mut states| {
+            states.extend(ExecutionState::iter().filter(ExecutionState::is_internal));
+            states
+        });
         let mut instrument = Instrument::default();
         let q_usable = meta.complex_selector();
         let q_step = meta.advice_column();
@@ -548,6 +572,8 @@ impl<F: Field> ExecutionConfig<F> {
 
         let mut stored_expressions_map = HashMap::new();
         let mut debug_expressions_map = HashMap::new();
+        #[cfg(any(test, feature = "test-circuits"))]
+        let mut step_state_transitions_map = HashMap::new();
 
         macro_rules! configure_gadget {
             () => {
@@ -573,8 +599,11 @@ impl<F: Field> ExecutionConfig<F> {
                         &mut height_map,
                         &mut stored_expressions_map,
                         &mut debug_expressions_map,
+                        #[cfg(any(test, feature = "test-circuits"))]
+                        &mut step_state_transitions_map,
                         &mut instrument,
                         feature_config.clone(),
+                        enabled_execution_states.as_ref(),
                     ))
                 })()
             };
@@ -686,12 +715,17 @@ impl<F: Field> ExecutionConfig<F> {
             // precompile calls
             precompile_identity_gadget: configure_gadget!(),
             precompile_ecrecover_gadget: configure_gadget!(),
+            precompile_ec_pairing_gadget: configure_gadget!(),
             // step and presets
             step: step_curr,
             height_map,
             stored_expressions_map,
             debug_expressions_map,
+            #[cfg(any(test, feature = "test-circuits"))]
+            step_state_transitions_map,
             instrument,
+            custom_fixed_tables,
+            enabled_execution_states,
         };
 
         Self::configure_lookup(
@@ -711,6 +745,10 @@ impl<F: Field> ExecutionConfig<F> {
             &challenges,
             &cell_manager,
         );
+        #[cfg(feature = "named-lookups")]
+        for (column, annotation) in config.lookup_annotations() {
+            meta.annotate_lookup_any_column(column, || annotation);
+        }
         config
     }
 
@@ -718,6 +756,20 @@ impl<F: Field> ExecutionConfig<F> {
         &self.instrument
     }
 
+    /// Number of lookup arguments per table in a constraint system the
+    /// execution config was configured into.  `configure_lookup` names every
+    /// lookup argument it emits after its table.
+    pub fn lookup_counts(meta: &ConstraintSystem<F>) -> BTreeMap<Table, usize> {
+        let mut counts = BTreeMap::new();
+        for lookup in meta.lookups().iter() {
+            if let Some(table) = Table::iter().find(|table| format!("{:?}", table) == lookup.name())
+            {
+                *counts.entry(table).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn configure_gadget<G: ExecutionGadget<F>>(
         meta: &mut ConstraintSystem<F>,
@@ -735,8 +787,11 @@ impl<F: Field> ExecutionConfig<F> {
         height_map: &mut HashMap<ExecutionState, usize>,
         stored_expressions_map: &mut HashMap<ExecutionState, Vec<StoredExpression<F>>>,
         debug_expressions_map: &mut HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
+        #[cfg(any(test, feature = "test-circuits"))]
+        step_state_transitions_map: &mut StepStateTransitionsMap<F>,
         instrument: &mut Instrument,
         feature_config: FeatureConfig,
+        enabled_execution_states: Option<&HashSet<ExecutionState>>,
     ) -> G {
         // Configure the gadget with the max height first so we can find out the actual
         // height
@@ -769,6 +824,23 @@ impl<F: Field> ExecutionConfig<F> {
 
         let gadget = G::configure(&mut cb);
 
+        // A disabled gadget keeps its height, so that the step layout doesn't depend on the
+        // enabled states, but none of its constraints are added.  Its selector is forced to
+        // zero instead, so no step can be in an unconstrained state.
+        if enabled_execution_states.is_some_and(|states| !states.contains(&G::EXECUTION_STATE)) {
+            let (_, _, _, meta) = cb.build();
+            meta.create_gate(G::NAME, |meta| {
+                let q_usable = meta.query_selector(q_usable);
+                let q_step = meta.query_advice(q_step, Rotation::cur());
+                vec![(
+                    "execution state is disabled",
+                    q_usable * q_step * step_curr.execution_state_selector([G::EXECUTION_STATE]),
+                )]
+            });
+            height_map.insert(G::EXECUTION_STATE, height);
+            return gadget;
+        }
+
         Self::configure_gadget_impl(
             q_usable,
             q_step,
@@ -780,6 +852,8 @@ impl<F: Field> ExecutionConfig<F> {
             height_map,
             stored_expressions_map,
             debug_expressions_map,
+            #[cfg(any(test, feature = "test-circuits"))]
+            step_state_transitions_map,
             execute_state_first_step_whitelist,
             execute_state_last_step_whitelist,
             instrument,
@@ -806,6 +880,8 @@ impl<F: Field> ExecutionConfig<F> {
         height_map: &mut HashMap<ExecutionState, usize>,
         stored_expressions_map: &mut HashMap<ExecutionState, Vec<StoredExpression<F>>>,
         debug_expressions_map: &mut HashMap<ExecutionState, Vec<(String, Expression<F>)>>,
+        #[cfg(any(test, feature = "test-circuits"))]
+        step_state_transitions_map: &mut StepStateTransitionsMap<F>,
         execute_state_first_step_whitelist: &HashSet<ExecutionState>,
         execute_state_last_step_whitelist: &HashSet<ExecutionState>,
         instrument: &mut Instrument,
@@ -834,6 +910,8 @@ impl<F: Field> ExecutionConfig<F> {
         }
 
         let debug_expressions = cb.debug_expressions.clone();
+        #[cfg(any(test, feature = "test-circuits"))]
+        let step_state_transitions = cb.step_state_transitions.clone();
 
         // Extract feature config here before cb is built.
         let enable_invalid_tx = cb.feature_config.invalid_tx;
@@ -851,6 +929,8 @@ impl<F: Field> ExecutionConfig<F> {
         );
         stored_expressions_map.insert(execution_state, stored_expressions);
         debug_expressions_map.insert(execution_state, debug_expressions);
+        #[cfg(any(test, feature = "test-circuits"))]
+        step_state_transitions_map.insert(execution_state, step_state_transitions);
 
         // Enforce the logic for this opcode
         let sel_step: &dyn Fn(&mut VirtualCells<F>) -> Expression<F> =
@@ -1038,6 +1118,42 @@ impl<F: Field> ExecutionConfig<F> {
         });
     }
 
+    /// Annotation of each lookup column with the names of the lookups stored in it, so that
+    /// `MockProver` failures point at the gadget lookups rather than only at the table.
+    #[cfg(feature = "named-lookups")]
+    pub(crate) fn lookup_annotations(&self) -> Vec<(Column<Advice>, String)> {
+        let mut names_by_column = HashMap::<usize, BTreeSet<String>>::new();
+        for (execution_state, stored_expressions) in &self.stored_expressions_map {
+            for stored_expression in stored_expressions {
+                if let Some(column_idx) = stored_expression.lookup_column_idx() {
+                    let name = stored_expression
+                        .name
+                        .trim_end_matches(" (stored expression)");
+                    names_by_column
+                        .entry(column_idx)
+                        .or_default()
+                        .insert(format!("{:?}: {}", execution_state, name));
+                }
+            }
+        }
+        self.step
+            .cell_manager
+            .columns()
+            .iter()
+            .filter_map(|column| match column.cell_type {
+                CellType::Lookup(table) => names_by_column.get(&column.idx).map(|names| {
+                    let annotation = format!(
+                        "{:?} lookup [{}]",
+                        table,
+                        names.iter().cloned().collect::<Vec<_>>().join(", ")
+                    );
+                    (column.advice, annotation)
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn configure_lookup(
         meta: &mut ConstraintSystem<F>,
@@ -1133,6 +1249,56 @@ impl<F: Field> ExecutionConfig<F> {
         chunk: &Chunk<F>,
         challenges: &Challenges<Value<F>>,
     ) -> Result<usize, Error> {
+        self.assign_block_padded_to(
+            layouter,
+            block,
+            chunk,
+            challenges,
+            chunk.fixed_param.max_evm_rows,
+        )
+    }
+
+    /// Assign block into a region of `rows` rows, padding the steps up to `rows` regardless of
+    /// the chunk's `max_evm_rows`.  Returns `Error::Synthesis` before assigning anything if the
+    /// steps of the chunk, plus the EndBlock/EndChunk row and at least one padding row, don't fit.
+    pub fn assign_block_with_capacity(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        chunk: &Chunk<F>,
+        challenges: &Challenges<Value<F>>,
+        rows: usize,
+    ) -> Result<usize, Error> {
+        let required = super::EvmCircuit::get_min_num_rows_required(block, chunk);
+        if required > rows {
+            log::error!(
+                "evm circuit requires {} rows but capacity is {}",
+                required,
+                rows
+            );
+            return Err(Error::Synthesis);
+        }
+        self.assign_block_padded_to(layouter, block, chunk, challenges, rows)
+    }
+
+    fn assign_block_padded_to(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        block: &Block<F>,
+        chunk: &Chunk<F>,
+        challenges: &Challenges<Value<F>>,
+        evm_rows: usize,
+    ) -> Result<usize, Error> {
+        // Report inconsistent gas witnesses before they show up as constraint failures.
+        // enable with `RUST_LOG=debug`
+        if log::log_enabled!(log::Level::Debug) {
+            for tx in block.txs.iter() {
+                if let Err(err) = tx.validate_gas_chain() {
+                    log::error!("tx {}: {}", tx.id, err);
+                }
+            }
+        }
+
         // Track number of calls to `layouter.assign_region` as layouter assignment passes.
         let mut assign_pass = 0;
         layouter.assign_region(
@@ -1188,15 +1354,13 @@ impl<F: Field> ExecutionConfig<F> {
                     .chain(std::iter::once((&dummy_tx, &cur_chunk_last_call, padding)))
                     .peekable();
 
-                let evm_rows = chunk.fixed_param.max_evm_rows;
-
                 let mut assign_padding_or_step = |cur_tx_call_step: TxCallStep,
                                                   mut offset: usize,
                                                   next_tx_call_step: Option<TxCallStep>,
                                                   padding_end: Option<usize>|
                  -> Result<usize, Error> {
                     let (_tx, call, step) = cur_tx_call_step;
-                    let height = step.execution_state().get_step_height();
+                    let height = step.execution_state().step_height();
 
                     // If padding, assign padding range with (dummy_tx, call, step)
                     // otherwise, assign one row with cur (tx, call, step), with next (tx, call,
@@ -1282,7 +1446,7 @@ impl<F: Field> ExecutionConfig<F> {
 
                 // part3: assign end chunk or end block
                 if let Some(end_chunk) = &chunk.end_chunk {
-                    debug_assert_eq!(ExecutionState::EndChunk.get_step_height(), 1);
+                    debug_assert_eq!(ExecutionState::EndChunk.step_height(), 1);
                     offset = assign_padding_or_step(
                         (&dummy_tx, &cur_chunk_last_call, end_chunk),
                         offset,
@@ -1297,7 +1461,7 @@ impl<F: Field> ExecutionConfig<F> {
                         chunk.chunk_context.is_last_chunk(),
                         "If not end_chunk, must be end_block at last chunk"
                     );
-                    debug_assert_eq!(ExecutionState::EndBlock.get_step_height(), 1);
+                    debug_assert_eq!(ExecutionState::EndBlock.step_height(), 1);
                     offset = assign_padding_or_step(
                         (&dummy_tx, &cur_chunk_last_call, &block.end_block),
                         offset,
@@ -1484,7 +1648,16 @@ impl<F: Field> ExecutionConfig<F> {
             )?;
         }
 
-        self.assign_exec_step_int(region, offset, block, chunk, cur_step, false, assign_pass)
+        self.assign_exec_step_int(region, offset, block, chunk, cur_step, false, assign_pass)?;
+
+        // The next step is assigned, so the transition to it can be checked.
+        // enable with `RUST_LOG=debug`
+        #[cfg(any(test, feature = "test-circuits"))]
+        if next_step.is_some() && assign_pass == 1 && log::log_enabled!(log::Level::Debug) {
+            self.check_step_state_transitions(region, offset, cur_step.2);
+        }
+
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1503,6 +1676,16 @@ impl<F: Field> ExecutionConfig<F> {
         assign_pass: usize,
     ) -> Result<(), Error> {
         let (transaction, call, step) = tx_call_step;
+        if let Some(states) = &self.enabled_execution_states {
+            if !states.contains(&step.execution_state()) {
+                log::error!(
+                    "ExecutionState {:?} is not enabled in this circuit (step: {:?})",
+                    step.execution_state(),
+                    step
+                );
+                return Err(Error::Synthesis);
+            }
+        }
         self.step
             .assign_exec_step(region, offset, block, call, step)?;
 
@@ -1662,12 +1845,21 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::PrecompileIdentity => {
                 assign_exec_step!(self.precompile_identity_gadget)
             }
+            ExecutionState::PrecompileBn256Pairing => {
+                assign_exec_step!(self.precompile_ec_pairing_gadget)
+            }
 
             unimpl_state => evm_unimplemented!("unimplemented ExecutionState: {:?}", unimpl_state),
         }
 
         // Fill in the witness values for stored expressions
-        let assigned_stored_expressions = self.assign_stored_expressions(region, offset, step)?;
+        // EndBlock and EndChunk are only assigned as the last step of the chunk.
+        let is_last_step = matches!(
+            step.execution_state(),
+            ExecutionState::EndBlock | ExecutionState::EndChunk
+        );
+        let assigned_stored_expressions =
+            self.assign_stored_expressions(region, offset, step, is_last_step)?;
         // Both `SimpleFloorPlanner` and `V1` do two passes; we only enter here once (on the second
         // pass).
         if !is_next && assign_pass == 1 {
@@ -1704,6 +1896,7 @@ impl<F: Field> ExecutionConfig<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         step: &ExecStep,
+        is_last_step: bool,
     ) -> Result<Vec<(String, F)>, Error> {
         let mut assigned_stored_expressions = Vec::new();
         for stored_expression in self
@@ -1711,7 +1904,7 @@ impl<F: Field> ExecutionConfig<F> {
             .get(&step.execution_state())
             .unwrap_or_else(|| panic!("Execution state unknown: {:?}", step.execution_state()))
         {
-            let assigned = stored_expression.assign(region, offset)?;
+            let assigned = stored_expression.assign(region, offset, is_last_step)?;
             assigned.map(|v| {
                 let name = stored_expression.name.clone();
                 assigned_stored_expressions.push((name, v));
@@ -1744,6 +1937,31 @@ impl<F: Field> ExecutionConfig<F> {
         }
     }
 
+    #[cfg(any(test, feature = "test-circuits"))]
+    fn check_step_state_transitions(
+        &self,
+        region: &CachedRegion<'_, '_, F>,
+        offset: usize,
+        step: &ExecStep,
+    ) {
+        for record in self
+            .step_state_transitions_map
+            .get(&step.execution_state())
+            .unwrap_or_else(|| panic!("Execution state unknown: {:?}", step.execution_state()))
+        {
+            if let Some((expected, actual)) = record.mismatch(region, offset) {
+                log::error!(
+                    "incorrect step state transition of {}: expected={:?}, assigned={:?} [offset={}, step={:?}]",
+                    record.field,
+                    expected,
+                    actual,
+                    offset,
+                    step
+                );
+            }
+        }
+    }
+
     fn check_rw_lookup(
         assigned_stored_expressions: &[(String, F)],
         step: &ExecStep,
@@ -1892,3 +2110,84 @@ impl<F: Field> ExecutionConfig<F> {
         }
     }
 }
+
+#[cfg(test)]
+impl ExecutionConfig<Fr> {
+    /// Verify `step` as the only step of the chunk, followed by the padding
+    /// `EndBlock`, in a circuit that only configures the gadget `G` and the
+    /// internal states, and report only the failures of `G` itself: the
+    /// constraints of its gate and the lookups of its rows.  The execution state
+    /// selector sum-to-one, first/last step and state transition constraints
+    /// are skipped, so that a gadget bug isn't hidden behind (or blamed on) the
+    /// interaction with the surrounding steps.
+    pub(crate) fn test_single_state<G: ExecutionGadget<Fr>>(
+        step: &ExecStep,
+        block: &Block<Fr>,
+        chunk: &Chunk<Fr>,
+    ) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+        use crate::{evm_circuit::EvmCircuit, util::SubCircuit};
+        use halo2_proofs::dev::{FailureLocation, MockProver, VerifyFailure};
+
+        assert_eq!(
+            step.execution_state(),
+            G::EXECUTION_STATE,
+            "step doesn't belong to gadget {}",
+            G::NAME
+        );
+
+        // Keep the transactions (and so the tx indexes of the chunk context) but drop
+        // every step other than `step`.
+        let mut block = block.clone();
+        for tx in block.txs.iter_mut() {
+            tx.steps_mut().retain(|s| {
+                s.rwc == step.rwc
+                    && s.pc == step.pc
+                    && s.call_index == step.call_index
+                    && s.execution_state() == step.execution_state()
+            });
+        }
+
+        let step_begin = chunk
+            .begin_chunk
+            .as_ref()
+            .map(|begin_chunk| begin_chunk.execution_state().step_height())
+            .unwrap_or_default();
+        let step_rows = step_begin..step_begin + G::EXECUTION_STATE.step_height();
+        let in_step_rows = |location: &FailureLocation| match location {
+            FailureLocation::InRegion { offset, .. } => step_rows.contains(offset),
+            FailureLocation::OutsideRegion { .. } => false,
+        };
+        let gate_name = format!("('{}')", G::NAME);
+
+        let k = block.get_test_degree(chunk);
+        let circuit = EvmCircuit::get_test_circuit_from_block(block, chunk.clone())
+            .with_enabled_execution_states(HashSet::from([G::EXECUTION_STATE]));
+        let prover = MockProver::run(k, &circuit, circuit.instance()).unwrap();
+        let failures: Vec<_> = prover
+            .verify()
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|failure| match failure {
+                VerifyFailure::ConstraintNotSatisfied {
+                    constraint,
+                    location,
+                    ..
+                } => {
+                    let constraint = format!("{}", constraint);
+                    in_step_rows(location)
+                        && constraint.contains(&gate_name)
+                        && !constraint.contains("State transition")
+                }
+                VerifyFailure::Lookup { location, .. } => in_step_rows(location),
+                _ => false,
+            })
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+}
@@ -109,8 +109,15 @@ impl<F: Field> ExecutionGadget<F> for AddSubGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_word, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode, evm_types::OpcodeId, Word};
+    use super::AddSubGadget;
+    use crate::{
+        evm_circuit::{execution::ExecutionConfig, step::ExecutionState, test::rand_word},
+        test_util::{CircuitTestBuilder, CircuitTestError},
+        witness::{block_convert, Chunk},
+    };
+    use bus_mapping::{circuit_input_builder::FeatureConfig, error::ExecError, mock::BlockData};
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Word};
+    use halo2_proofs::{dev::VerifyFailure, halo2curves::bn256::Fr};
 
     use mock::TestContext;
 
@@ -134,6 +141,55 @@ mod test {
         test_ok(OpcodeId::SUB, 0x090705.into(), 0x060504.into());
     }
 
+    #[test]
+    fn sub_execution_state() {
+        let bytecode = bytecode! {
+            PUSH32(0x090705)
+            PUSH32(0x060504)
+            SUB
+            STOP
+        };
+        let ctx = || TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode.clone()).unwrap();
+
+        // Steps are BeginTx, PUSH32, PUSH32 and SUB.
+        CircuitTestBuilder::new_from_test_ctx(ctx())
+            .expect_execution_state(0, ExecutionState::BeginTx)
+            .expect_execution_state(3, ExecutionState::ADD_SUB)
+            .run();
+
+        let err = CircuitTestBuilder::new_from_test_ctx(ctx())
+            .expect_execution_state(3, ExecutionState::MUL_DIV_MOD)
+            .run_with_result()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CircuitTestError::UnexpectedExecutionState {
+                step_index: 3,
+                expected: ExecutionState::MUL_DIV_MOD,
+                found: Some(ExecutionState::ADD_SUB),
+            }
+        ));
+    }
+
+    #[test]
+    fn add_gadget_without_rw_permutation() {
+        let bytecode = bytecode! {
+            PUSH32(0x030201)
+            PUSH32(0x060504)
+            ADD
+            STOP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .feature(FeatureConfig {
+            disable_rw_permutation: true,
+            ..Default::default()
+        })
+        .run()
+    }
+
     #[test]
     fn add_gadget_rand() {
         let a = rand_word();
@@ -141,4 +197,67 @@ mod test {
         test_ok(OpcodeId::ADD, a, b);
         test_ok(OpcodeId::SUB, a, b);
     }
+
+    #[test]
+    fn add_gadget_single_state() {
+        let bytecode = bytecode! {
+            PUSH32(rand_word())
+            PUSH32(rand_word())
+            ADD
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode)
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = Chunk::single(&block, builder.circuits_params);
+
+        let step = block.txs[0]
+            .steps()
+            .iter()
+            .find(|step| step.opcode() == Some(OpcodeId::ADD))
+            .unwrap();
+        ExecutionConfig::test_single_state::<AddSubGadget<Fr>>(step, &block, &chunk).unwrap();
+    }
+
+    #[test]
+    fn add_opcode_rejected_by_other_gadget() {
+        // Route the ADD step to the ErrorInvalidOpcode gadget: the opcode still matches the
+        // bytecode, but ADD isn't one of the opcodes that gadget is responsible for.
+        let bytecode = bytecode! {
+            PUSH32(rand_word())
+            PUSH32(rand_word())
+            ADD
+            STOP
+        };
+        let err = CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        .block_modifier(Box::new(|block, _chunks| {
+            let step = block.txs[0]
+                .steps_mut()
+                .iter_mut()
+                .find(|step| step.opcode() == Some(OpcodeId::ADD))
+                .unwrap();
+            step.error = Some(ExecError::InvalidOpcode);
+        }))
+        .run_with_result()
+        .unwrap_err();
+
+        err.assert_evm_failure();
+        let CircuitTestError::VerificationFailed { reasons, .. } = err else {
+            unreachable!()
+        };
+        assert!(
+            reasons.iter().any(
+                |reason| matches!(reason, VerifyFailure::Lookup { name, .. } if name == "Fixed")
+            ),
+            "{:?}",
+            reasons
+        );
+    }
 }
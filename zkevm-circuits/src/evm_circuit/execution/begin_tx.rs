@@ -1,7 +1,7 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS},
+        param::N_BYTES_ACCOUNT_ADDRESS,
         step::ExecutionState,
         util::{
             and,
@@ -13,14 +13,14 @@ use crate::{
             is_precompiled,
             math_gadget::{
                 ContractCreateGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget,
-                RangeCheckGadget,
             },
             not, rlc,
-            tx::{BeginTxHelperGadget, TxDataGadget},
+            tx::{BeginTxHelperGadget, IntrinsicGasGadget, TxDataGadget},
             AccountAddress, CachedRegion, Cell, StepRws,
         },
         witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
+    mpt_circuit::{rlp_encode_item, rlp_encode_list, rlp_encode_word},
     table::{AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxContextFieldTag},
     util::{
         word::{Word32Cell, WordExpr, WordLoHi, WordLoHiCell},
@@ -41,7 +41,7 @@ pub(crate) struct BeginTxGadget<F> {
     tx_caller_address_is_zero: IsZeroWordGadget<F, WordLoHiCell<F>>,
     call_callee_address: AccountAddress<F>,
     reversion_info: ReversionInfo<F>,
-    sufficient_gas_left: RangeCheckGadget<F, N_BYTES_GAS>,
+    intrinsic_gas: IntrinsicGasGadget<F>,
     transfer_with_gas_fee: TransferGadget<F, true>,
     code_hash: WordLoHiCell<F>,
     is_empty_code_hash: IsEqualWordGadget<F, WordLoHi<Expression<F>>, WordLoHi<Expression<F>>>,
@@ -58,6 +58,9 @@ pub(crate) struct BeginTxGadget<F> {
     // coinbase, and may be duplicate.
     // <https://github.com/ethereum/go-ethereum/blob/604e215d1bb070dff98fb76aa965064c74e3633f/core/state/statedb.go#LL1119C9-L1119C9>
     is_coinbase_warm: Cell<F>,
+    // Gas left at the start of the tx when taken from the witness, see
+    // `FeatureConfig::override_gas_left`
+    gas_left_override: Option<Cell<F>>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
@@ -82,8 +85,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         ); // rwc_delta += 1
 
         // Check gas_left is sufficient
-        let gas_left = tx.gas.expr() - tx.intrinsic_gas();
-        let sufficient_gas_left = RangeCheckGadget::construct(cb, gas_left.clone());
+        let intrinsic_gas = IntrinsicGasGadget::construct(cb, &tx);
+        let gas_left_override = cb.feature_config.override_gas_left.then(|| cb.query_cell());
+        let gas_left = gas_left_override
+            .as_ref()
+            .map_or_else(|| intrinsic_gas.gas_left(), |cell| cell.expr());
 
         let tx_caller_address_is_zero = cb.is_zero_word(&tx.caller_address);
         cb.require_equal(
@@ -149,13 +155,19 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
             coinbase.to_word(),
         );
-        cb.account_access_list_write_unchecked(
-            tx_id.expr(),
-            coinbase.to_word(),
-            1.expr(),
-            is_coinbase_warm.expr(),
-            None,
-        ); // rwc_delta += 1
+        let warm_coinbase = cb.feature_config.warm_coinbase;
+        if warm_coinbase {
+            cb.account_access_list_write_unchecked(
+                tx_id.expr(),
+                coinbase.to_word(),
+                1.expr(),
+                is_coinbase_warm.expr(),
+                None,
+            ); // rwc_delta += 1
+        } else {
+            // The coinbase isn't added to the access list
+            cb.require_zero("is_coinbase_warm is 0", is_coinbase_warm.expr());
+        }
 
         // Read code_hash of callee
         let code_hash = cb.query_word_unchecked();
@@ -308,7 +320,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
-                //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                //   - Write TxAccessListAccount (Coinbase) for EIP-3651 if enabled
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
                 //   - Write CallContext Depth
@@ -325,7 +337,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
                 rw_counter: Delta(
-                    23.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                    22.expr()
+                        + warm_coinbase.expr()
+                        + transfer_with_gas_fee.rw_delta()
+                        + PRECOMPILE_COUNT.expr(),
                 ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
@@ -368,11 +383,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
-                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651 if enabled
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     rw_counter: Delta(
-                        9.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        8.expr()
+                            + warm_coinbase.expr()
+                            + transfer_with_gas_fee.rw_delta()
+                            + PRECOMPILE_COUNT.expr(),
                     ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
@@ -431,7 +449,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write TxAccessListAccount (Precompile) x PRECOMPILE_COUNT
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
-                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651 if enabled
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     //   - Write CallContext Depth
@@ -448,7 +466,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
                     rw_counter: Delta(
-                        22.expr() + transfer_with_gas_fee.rw_delta() + PRECOMPILE_COUNT.expr(),
+                        21.expr()
+                            + warm_coinbase.expr()
+                            + transfer_with_gas_fee.rw_delta()
+                            + PRECOMPILE_COUNT.expr(),
                     ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
@@ -468,7 +489,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_caller_address_is_zero,
             call_callee_address,
             reversion_info,
-            sufficient_gas_left,
+            intrinsic_gas,
             transfer_with_gas_fee,
             code_hash,
             is_empty_code_hash,
@@ -481,6 +502,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             is_caller_callee_equal,
             coinbase,
             is_coinbase_warm,
+            gas_left_override,
         }
     }
 
@@ -502,7 +524,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 
         rws.offset_add(PRECOMPILE_COUNT as usize);
 
-        let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
+        let is_coinbase_warm = if block.feature_config.warm_coinbase {
+            rws.next().tx_access_list_value_pair().1
+        } else {
+            false
+        };
         let mut callee_code_hash = zero;
         if !is_precompiled(&tx.to_or_contract_addr()) {
             callee_code_hash = rws.next().account_codehash_pair().1;
@@ -539,8 +565,21 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             call.rw_counter_end_of_reversion,
             call.is_persistent,
         )?;
-        self.sufficient_gas_left
-            .assign(region, offset, F::from(tx.gas() - step.gas_cost))?;
+        let intrinsic_gas = self.intrinsic_gas.assign(
+            region,
+            offset,
+            tx,
+            block.feature_config.init_code_word_gas(),
+        )?;
+        debug_assert_eq!(intrinsic_gas, step.gas_cost, "intrinsic gas mismatch");
+        if let Some(gas_left_override) = &self.gas_left_override {
+            // The gas left is the one the execution of the tx starts with
+            let gas_left = tx
+                .steps()
+                .get(1)
+                .map_or(tx.gas() - intrinsic_gas, |next_step| next_step.gas_left);
+            gas_left_override.assign(region, offset, Value::known(F::from(gas_left)))?;
+        }
         self.code_hash
             .assign_u256(region, offset, callee_code_hash)?;
         self.is_empty_code_hash.assign_u256(
@@ -552,14 +591,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         self.callee_not_exists
             .assign_u256(region, offset, callee_code_hash)?;
 
-        let untrimmed_contract_addr = {
-            let mut stream = ethers_core::utils::rlp::RlpStream::new();
-            stream.begin_list(2);
-            stream.append(&tx.from);
-            stream.append(&tx.nonce.to_word());
-            let rlp_encoding = stream.out().to_vec();
-            keccak256(&rlp_encoding)
-        };
+        let untrimmed_contract_addr = keccak256(&rlp_encode_list(&[
+            rlp_encode_item(tx.from.as_bytes()),
+            rlp_encode_word(tx.nonce.to_word()),
+        ]));
         self.caller_nonce_hash_bytes.assign_u256(
             region,
             offset,
@@ -600,11 +635,17 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use bus_mapping::evm::OpcodeId;
+    use crate::{
+        evm_circuit::{step::ExecutionState, test::rand_bytes},
+        test_util::{CircuitTestBuilder, CircuitTestError},
+    };
+    use bus_mapping::{circuit_input_builder::FeatureConfig, evm::OpcodeId};
     use eth_types::{self, bytecode, evm_types::GasCost, word, Address, Bytecode, Word};
     use ethers_core::utils::get_contract_address;
-    use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
+    use mock::{
+        eth, gwei, test_ctx::helpers::account_0_code_account_1_no_code, MockTransaction,
+        TestContext, MOCK_ACCOUNTS,
+    };
     use std::vec;
 
     fn gas(call_data: &[u8]) -> Word {
@@ -661,6 +702,97 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    fn mstore_code() -> Bytecode {
+        bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0)
+            MSTORE
+            STOP
+        }
+    }
+
+    fn mstore_gas() -> u64 {
+        2 * OpcodeId::PUSH1.constant_gas_cost()
+            + OpcodeId::MSTORE.constant_gas_cost()
+            + GasCost::MEMORY_EXPANSION_LINEAR_COEFF
+    }
+
+    // Run PUSH1, PUSH1 and an MSTORE expanding the memory to one word, in a
+    // tx traced with `traced_gas_left` after the intrinsic gas, and with the
+    // witness of the tx starting with `gas_left` instead.
+    fn test_mstore_with_gas_left(
+        feature_config: FeatureConfig,
+        traced_gas_left: u64,
+        gas_left: u64,
+        mstore_state: ExecutionState,
+    ) -> Result<(), CircuitTestError> {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(mstore_code()),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(Word::from(GasCost::TX + traced_gas_left));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .feature(feature_config)
+            .block_modifier(Box::new(move |block, _chunk| {
+                // Shift the gas left of the opcode steps, between BeginTx and
+                // EndTx.  EndTx keeps the gas left of the traced tx, as STOP
+                // and the out of gas error don't constrain it.
+                let steps = block.txs[0].steps_mut();
+                let n_steps = steps.len();
+                for step in steps[1..n_steps - 1].iter_mut() {
+                    step.gas_left = step.gas_left + gas_left - traced_gas_left;
+                }
+            }))
+            .expect_execution_state(3, mstore_state)
+            .run_with_result()
+    }
+
+    #[test]
+    fn begin_tx_override_gas_left() {
+        let feature_config = FeatureConfig {
+            override_gas_left: true,
+            ..Default::default()
+        };
+        assert!(!feature_config.is_mainnet());
+
+        // The tx has more gas than the witness starts with, and the MSTORE
+        // has exactly enough of it.
+        let success = (mstore_gas() + 1, mstore_gas(), ExecutionState::MEMORY);
+        // The tx has less gas than the witness starts with, and the MSTORE is
+        // still one gas short.
+        let out_of_gas = (
+            mstore_gas() - 2,
+            mstore_gas() - 1,
+            ExecutionState::ErrorOutOfGasStaticMemoryExpansion,
+        );
+
+        for (traced_gas_left, gas_left, mstore_state) in [success, out_of_gas] {
+            // The override lets the witness start the execution with another
+            // gas than the one the tx has left after the intrinsic gas.
+            test_mstore_with_gas_left(feature_config, traced_gas_left, gas_left, mstore_state)
+                .unwrap();
+
+            // Without the override, the gas left must be the one left by the
+            // tx.
+            test_mstore_with_gas_left(
+                FeatureConfig::default(),
+                traced_gas_left,
+                gas_left,
+                mstore_state,
+            )
+            .unwrap_err()
+            .assert_evm_failure();
+        }
+    }
+
     fn mock_tx(value: Word, gas_price: Word, calldata: Vec<u8>) -> eth_types::Transaction {
         let from = MOCK_ACCOUNTS[1];
         let to = MOCK_ACCOUNTS[0];
@@ -695,6 +827,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn begin_tx_mixed_calldata() {
+        // Zero bytes cost 4 and non-zero bytes 16 in the intrinsic gas, as
+        // precomputed by the tx circuit.
+        let calldata = vec![0, 1, 0, 0, 0xff, 0, 0x7f, 0];
+        assert_eq!(
+            gas(&calldata),
+            Word::from(GasCost::TX + 2 * OpcodeId::PUSH32.constant_gas_cost() + 5 * 4 + 3 * 16)
+        );
+        test_ok(mock_tx(eth(1), gwei(2), calldata), Some(code_with_return()));
+    }
+
     #[test]
     fn begin_tx_large_nonce() {
         // This test checks that the rw table assignment and evm circuit are consistent
@@ -796,6 +940,13 @@ mod test {
     }
 
     fn begin_tx_deploy(nonce: u64) {
+        begin_tx_deploy_with_feature(nonce, FeatureConfig::default()).unwrap();
+    }
+
+    fn begin_tx_deploy_with_feature(
+        nonce: u64,
+        feature_config: FeatureConfig,
+    ) -> Result<(), CircuitTestError> {
         let code = bytecode! {
             // [ADDRESS, STOP]
             PUSH32(word!("3000000000000000000000000000000000000000000000000000000000000000"))
@@ -826,13 +977,41 @@ mod test {
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .feature(feature_config)
+            .run_with_result()
     }
 
     #[test]
     fn begin_tx_deploy_nonce_zero() {
         begin_tx_deploy(0);
     }
+
+    #[test]
+    fn begin_tx_deploy_without_init_code_limit() {
+        let feature_config = FeatureConfig {
+            limit_init_code_size: false,
+            ..Default::default()
+        };
+        assert!(!feature_config.is_mainnet());
+
+        // The trace charges the init code word gas of EIP-3860, which the
+        // intrinsic gas leaves out without the limit.
+        begin_tx_deploy_with_feature(0, feature_config)
+            .unwrap_err()
+            .assert_evm_failure();
+
+        // The execution starting with the traced gas left is proved once the
+        // gas left is overridden.
+        begin_tx_deploy_with_feature(
+            0,
+            FeatureConfig {
+                override_gas_left: true,
+                ..feature_config
+            },
+        )
+        .unwrap();
+    }
     #[test]
     fn begin_tx_deploy_nonce_small_1byte() {
         begin_tx_deploy(1);
@@ -83,9 +83,16 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
+    use bus_mapping::circuit_input_builder::FeatureConfig;
+    use eth_types::{bytecode, ToWord, Word};
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_DIFFICULTY, MOCK_MIX_HASH,
+    };
 
     fn test_ok(bytecode: bytecode::Bytecode) {
         CircuitTestBuilder::new_from_test_ctx(
@@ -125,4 +132,59 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    /// Run DIFFICULTY on a block with the `difficulty` header, pre-merge (`is_merged = false`)
+    /// or post-merge (`is_merged = true`, with a mix hash), and check the pushed value.
+    fn test_difficulty(is_merged: bool, difficulty: Word, expected: Word) {
+        let bytecode = bytecode! {
+            DIFFICULTY
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| {
+                block.difficulty(difficulty);
+                if is_merged {
+                    block.mix_hash(*MOCK_MIX_HASH);
+                }
+                block
+            },
+        )
+        .unwrap();
+        let feature_config = FeatureConfig {
+            is_merged,
+            ..Default::default()
+        };
+
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .feature(feature_config)
+            .build_block(None)
+            .unwrap();
+        assert_eq!(block.context.difficulty, expected);
+        let step = block.txs[0]
+            .steps()
+            .iter()
+            .find(|step| step.execution_state() == ExecutionState::BLOCKCTX)
+            .unwrap();
+        assert_eq!(block.get_rws(step, 0).stack_value(), expected);
+
+        CircuitTestBuilder::new_from_block(block, chunks).run();
+    }
+
+    #[test]
+    fn blockcxt_difficulty_pre_merge() {
+        test_difficulty(false, *MOCK_DIFFICULTY, *MOCK_DIFFICULTY);
+    }
+
+    #[test]
+    fn blockcxt_zero_difficulty_pre_merge() {
+        test_difficulty(false, Word::zero(), Word::zero());
+    }
+
+    #[test]
+    fn blockcxt_prevrandao_post_merge() {
+        test_difficulty(true, Word::zero(), MOCK_MIX_HASH.to_word());
+    }
 }
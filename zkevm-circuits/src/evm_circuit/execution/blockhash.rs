@@ -185,6 +185,33 @@ mod test {
         test_ok((0xcafe + 1).into(), 0xcafeu64);
     }
 
+    #[test]
+    fn blockhash_gadget_last_history_hash() {
+        let current_block_number = 0xcafeu64;
+        let code = bytecode! {
+            PUSH32(current_block_number - 1)
+            BLOCKHASH
+            STOP
+        };
+        // More hashes than BLOCKHASH can reach, only the latest 256 are kept.
+        let history_hashes = (0..300)
+            .map(|i| U256::from(0xbeefcafeu64 + i))
+            .collect::<Vec<_>>();
+        let ctx = TestContext::<2, 1>::new(
+            Some(history_hashes.clone()),
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(current_block_number),
+        )
+        .unwrap();
+        assert_eq!(ctx.history_hashes, history_hashes[300 - 256..]);
+
+        let stop = ctx.geth_traces[0].struct_logs.last().unwrap();
+        assert_eq!(stop.stack.last().unwrap(), *history_hashes.last().unwrap());
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run()
+    }
+
     #[test]
     fn blockhash_gadget_block_number_overflow() {
         test_ok(U256::MAX, 0xcafeu64);
@@ -267,7 +267,10 @@ impl<F: Field> ExecutionGadget<F> for CallDataCopyGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use crate::{
+        evm_circuit::{test::rand_bytes, witness::Rw},
+        test_util::CircuitTestBuilder,
+    };
     use bus_mapping::circuit_input_builder::FixedCParams;
     use eth_types::{bytecode, Word};
     use mock::{
@@ -374,6 +377,60 @@ mod test {
         test_internal_ok(0x40, 0x20, 10, 0x28.into(), 0xA0.into());
     }
 
+    #[test]
+    fn calldatacopy_gadget_zero_fills_past_calldata() {
+        let call_data_length = 0x40;
+        let bytecode = bytecode! {
+            PUSH32(32) // length
+            PUSH32(call_data_length - 10) // data_offset
+            PUSH32(0) // memory_offset
+            #[start]
+            CALLDATACOPY
+            STOP
+        };
+        let call_data = vec![0xff; call_data_length];
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(call_data.into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block(None)
+            .unwrap();
+
+        // The first 10 bytes come from the calldata, the trailing 22 bytes
+        // past its end are zero-filled.
+        let memory = block
+            .rws
+            .table_assignments(true)
+            .into_iter()
+            .filter_map(|rw| match rw {
+                Rw::Memory {
+                    is_write: true,
+                    memory_address,
+                    byte,
+                    ..
+                } => Some((memory_address, byte)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let expected = (0..32)
+            .map(|addr| (addr, if addr < 10 { 0xff } else { 0 }))
+            .collect::<Vec<_>>();
+        assert_eq!(memory, expected);
+
+        CircuitTestBuilder::new_from_block(block, chunks).run();
+    }
+
     #[test]
     fn calldatacopy_gadget_zero_length() {
         test_root_ok(0x40, 0, 0x00.into(), 0x40.into());
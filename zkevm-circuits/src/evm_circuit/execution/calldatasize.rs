@@ -5,23 +5,26 @@ use crate::{
         util::{
             common_gadget::SameContextGadget,
             constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
-            CachedRegion,
+            not, CachedRegion, Cell,
         },
         witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
-    table::CallContextFieldTag,
+    table::{CallContextFieldTag, TxContextFieldTag},
     util::{
-        word::{WordExpr, WordLoHiCell},
+        word::{WordExpr, WordLoHi, WordLoHiCell},
         Expr,
     },
 };
 use bus_mapping::evm::OpcodeId;
 use eth_types::Field;
-use halo2_proofs::plonk::Error;
+use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
 pub(crate) struct CallDataSizeGadget<F> {
     same_context: SameContextGadget<F>,
+    /// Transaction ID, only used to look up the tx calldata length for a root
+    /// call.
+    tx_id: Cell<F>,
     call_data_size: WordLoHiCell<F>,
 }
 
@@ -33,19 +36,39 @@ impl<F: Field> ExecutionGadget<F> for CallDataSizeGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
 
-        // Add lookup constraint in the call context for the calldatasize field.
+        let tx_id = cb.query_cell();
         let call_data_size = cb.query_word_unchecked();
-        cb.call_context_lookup_read(
-            None,
-            CallContextFieldTag::CallDataLength,
-            call_data_size.to_word(),
-        );
+
+        // For a root call, the calldatasize is the length of the tx input.
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.call_context_lookup_read(
+                None,
+                CallContextFieldTag::TxId,
+                WordLoHi::from_lo_unchecked(tx_id.expr()),
+            );
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::CallDataLength,
+                None,
+                call_data_size.to_word(),
+            );
+        });
+
+        // For an internal call, the calldatasize is the length set by the
+        // caller in the call context.
+        cb.condition(not::expr(cb.curr.state.is_root.expr()), |cb| {
+            cb.call_context_lookup_read(
+                None,
+                CallContextFieldTag::CallDataLength,
+                call_data_size.to_word(),
+            );
+        });
 
         // The calldatasize should be pushed to the top of the stack.
         cb.stack_push(call_data_size.to_word());
 
         let step_state_transition = StepStateTransition {
-            rw_counter: Delta(2.expr()),
+            rw_counter: Delta(cb.rw_counter_offset()),
             program_counter: Delta(1.expr()),
             stack_pointer: Delta((-1).expr()),
             gas_left: Delta(-OpcodeId::CALLDATASIZE.constant_gas_cost().expr()),
@@ -56,6 +79,7 @@ impl<F: Field> ExecutionGadget<F> for CallDataSizeGadget<F> {
 
         Self {
             same_context,
+            tx_id,
             call_data_size,
         }
     }
@@ -66,12 +90,15 @@ impl<F: Field> ExecutionGadget<F> for CallDataSizeGadget<F> {
         offset: usize,
         block: &Block<F>,
         _chunk: &Chunk<F>,
-        _tx: &Transaction,
+        tx: &Transaction,
         _call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         self.same_context.assign_exec_step(region, offset, step)?;
 
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id)))?;
+
         let call_data_size = block.get_rws(step, 1).stack_value();
 
         self.call_data_size
@@ -179,4 +206,53 @@ mod test {
             test_ok(call_data_size, is_root);
         }
     }
+
+    #[test]
+    fn calldatasize_gadget_internal_differs_from_tx() {
+        let bytecode = bytecode! {
+            CALLDATASIZE
+            STOP
+        };
+        // The tx input and the sub-call calldata have different lengths, so the
+        // internal call must not read the tx calldata length.
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000123"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode! {
+                        CALLDATASIZE
+                        POP
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0x40)
+                        PUSH1(0)
+                        PUSH1(0)
+                        PUSH1(0x20)
+                        GAS
+                        CALL
+                        STOP
+                    });
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(bytecode);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .input(rand_bytes(7).into())
+                    .gas(Word::from(40000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
 }
@@ -100,6 +100,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
         cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.responsible_opcode_lookup(opcode.expr());
         let is_call = cb.is_eq(opcode.expr(), OpcodeId::CALL.expr());
         let is_callcode = cb.is_eq(opcode.expr(), OpcodeId::CALLCODE.expr());
         let is_delegatecall = cb.is_eq(opcode.expr(), OpcodeId::DELEGATECALL.expr());
@@ -240,7 +241,9 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         // Verify transfer only for CALL opcode in the successful case.  If value == 0,
         // skip the transfer (this is necessary for non-existing accounts, which
         // will not be created when value is 0 and so the callee balance lookup
-        // would be invalid).
+        // would be invalid).  The transfer is only enabled when `value <= caller_balance`, which
+        // keeps a CALL to self sound: the caller balance is decreased and then increased back
+        // by the same value.
         let transfer = cb.condition(is_call.expr() * is_precheck_ok.expr(), |cb| {
             TransferGadget::construct(
                 cb,
@@ -1114,9 +1117,12 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 mod test {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use bus_mapping::circuit_input_builder::FixedCParams;
+    use bus_mapping::circuit_input_builder::{ExecState, FixedCParams};
     use eth_types::{
-        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, ToWord, Word,
+        address, bytecode,
+        evm_types::{GasCost, OpcodeId},
+        geth_types::Account,
+        word, Address, ToWord, Word,
     };
 
     use itertools::Itertools;
@@ -1152,6 +1158,57 @@ mod test {
         }
     }
 
+    #[test]
+    fn callop_value_transfer_to_self() {
+        // (value is the whole balance, value is more than the balance)
+        for extra_value in [0u64, 1] {
+            // The inner call (CALLER == ADDRESS) stops right away.
+            let code = bytecode! {
+                ADDRESS
+                CALLER
+                EQ
+                PUSH1(22)
+                JUMPI
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(0)
+                PUSH1(extra_value)
+                SELFBALANCE
+                ADD
+                ADDRESS
+                GAS
+                CALL
+                STOP
+                JUMPDEST // 22
+                STOP
+            };
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x000000000000000000000000000000000000cafe"))
+                        .balance(Word::from(10u64.pow(19)));
+                    accs[1]
+                        .address(Address::repeat_byte(0xfe))
+                        .balance(Word::from(10u64.pow(18)))
+                        .code(code);
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .to(accs[1].address)
+                        .gas(100000.into())
+                        .value(1000.into());
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
     #[test]
     fn callop_nested() {
         for opcode in TEST_CALL_OPCODES {
@@ -1366,8 +1423,101 @@ mod test {
         );
     }
 
-    fn test_ok(caller: Account, callee: Account) {
-        let ctx = TestContext::<3, 1>::new(
+    #[test]
+    fn callop_gas_capped_to_all_but_one_64th() {
+        // Request far more gas than the tx has left, so EIP-150 caps it.
+        let requested = 1_000_000u64;
+        let stack = Stack {
+            gas: requested,
+            ..Default::default()
+        };
+        for opcode in TEST_CALL_OPCODES {
+            let builder = CircuitTestBuilder::new_from_test_ctx(test_ctx(
+                caller(opcode, stack, true),
+                callee(bytecode! { STOP }),
+            ))
+            .params(FixedCParams {
+                max_rws: 1 << 12,
+                ..Default::default()
+            });
+            let (block, chunks) = builder.build_block(None).unwrap();
+
+            let steps = block.txs[0].steps();
+            let mut calls = 0;
+            for (step, next_step) in steps.iter().tuple_windows() {
+                if step.exec_state != ExecState::Op(*opcode) {
+                    continue;
+                }
+                // The first call accesses the callee cold, the second one warm.
+                let access_cost = if calls == 0 {
+                    GasCost::COLD_ACCOUNT_ACCESS
+                } else {
+                    GasCost::WARM_ACCESS
+                };
+                let available = step.gas_left - access_cost;
+                let capped = available - available / 64;
+                assert!(capped < requested);
+                assert_ne!(next_step.call_index, step.call_index);
+                assert_eq!(next_step.gas_left, capped, "{:?} callee gas", opcode);
+                calls += 1;
+            }
+            assert_eq!(calls, 2);
+
+            CircuitTestBuilder::new_from_block(block, chunks).run();
+        }
+    }
+
+    #[test]
+    fn callop_value_stipend() {
+        // The callee holds no balance and only gets the stipend to run STOP.
+        let callee = Account {
+            balance: Word::zero(),
+            ..callee(bytecode! { STOP })
+        };
+        for (opcode, value) in [OpcodeId::CALL, OpcodeId::CALLCODE]
+            .iter()
+            .cartesian_product([Word::zero(), Word::from(10).pow(18.into())])
+        {
+            let stack = Stack {
+                value,
+                ..Default::default()
+            };
+            let builder = CircuitTestBuilder::new_from_test_ctx(test_ctx(
+                caller(opcode, stack, true),
+                callee.clone(),
+            ))
+            .params(FixedCParams {
+                max_rws: 1 << 12,
+                ..Default::default()
+            });
+            let (block, chunks) = builder.build_block(None).unwrap();
+
+            let stipend = if value.is_zero() {
+                0
+            } else {
+                GAS_STIPEND_CALL_WITH_VALUE
+            };
+            let callee_gas = block.txs[0]
+                .steps()
+                .iter()
+                .tuple_windows()
+                .filter(|(step, _)| step.exec_state == ExecState::Op(*opcode))
+                .map(|(_, next_step)| next_step.gas_left)
+                .collect_vec();
+            assert_eq!(
+                callee_gas,
+                vec![stipend; 2],
+                "{:?} with value {}",
+                opcode,
+                value
+            );
+
+            CircuitTestBuilder::new_from_block(block, chunks).run();
+        }
+    }
+
+    fn test_ctx(caller: Account, callee: Account) -> TestContext<3, 1> {
+        TestContext::<3, 1>::new(
             None,
             |accs| {
                 accs[0]
@@ -1386,9 +1536,11 @@ mod test {
             },
             |block, _tx| block.number(0xcafeu64),
         )
-        .unwrap();
+        .unwrap()
+    }
 
-        CircuitTestBuilder::new_from_test_ctx(ctx)
+    fn test_ok(caller: Account, callee: Account) {
+        CircuitTestBuilder::new_from_test_ctx(test_ctx(caller, callee))
             .params(FixedCParams {
                 max_rws: 1 << 12,
                 ..Default::default()
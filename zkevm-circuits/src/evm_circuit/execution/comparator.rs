@@ -11,7 +11,7 @@ use crate::{
         witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
     util::{
-        word::{WordExpr, WordLoHi, WordLoHiCell},
+        word::{WordLoHi, WordLoHiCell},
         Expr,
     },
 };
@@ -62,8 +62,10 @@ impl<F: Field> ExecutionGadget<F> for ComparatorGadget<F> {
         // When swap is enabled we swap stack places between a and b.
         // We can push result here directly because
         // it only uses the LSB of a word.
-        cb.stack_pop(WordLoHi::select(is_gt.expr(), b.to_word(), a.to_word()));
-        cb.stack_pop(WordLoHi::select(is_gt.expr(), a.to_word(), b.to_word()));
+        let lhs = cb.select_word(is_gt.expr(), &b, &a);
+        let rhs = cb.select_word(is_gt.expr(), &a, &b);
+        cb.stack_pop(lhs);
+        cb.stack_pop(rhs);
         cb.stack_push(WordLoHi::from_lo_unchecked(result.expr()));
 
         // State transition
@@ -2,24 +2,23 @@ use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
         param::{
-            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE,
-            N_BYTES_U64, N_BYTES_WORD,
+            N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
         },
         step::ExecutionState,
         util::{
-            common_gadget::TransferGadget,
+            common_gadget::{CreateAddressGadget, TransferGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
             },
             math_gadget::{
-                ConstantDivisionGadget, ContractCreateGadget, IsEqualGadget, IsZeroWordGadget,
-                LtGadget, LtWordGadget,
+                ConstantDivisionGadget, IsEqualGadget, IsZeroWordGadget, LtGadget, LtWordGadget,
             },
             memory_gadget::{
                 CommonMemoryAddressGadget, MemoryAddressGadget, MemoryExpansionGadget,
             },
-            not, AccountAddress, CachedRegion, Cell, StepRws,
+            not, CachedRegion, Cell, StepRws,
         },
         witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
@@ -32,19 +31,13 @@ use crate::{
 use bus_mapping::{
     circuit_input_builder::CopyDataType, evm::OpcodeId, operation::Target, state_db::CodeDB,
 };
-use eth_types::{
-    evm_types::{GasCost, INIT_CODE_WORD_GAS},
-    Field, OpsIdentity, ToBigEndian, ToScalar, ToWord, U256,
-};
-use ethers_core::utils::keccak256;
+use eth_types::{evm_types::GasCost, Field, OpsIdentity, ToScalar, ToWord, U256};
 use gadgets::util::{and, select};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
 };
 
-use std::iter::once;
-
 /// Gadget for CREATE and CREATE2 opcodes
 #[derive(Clone, Debug)]
 pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionState> {
@@ -58,18 +51,19 @@ pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionStat
     was_warm: Cell<F>,
     value: Word32Cell<F>,
 
+    caller_address: WordLoHiCell<F>,
+    caller_nonce: Cell<F>,
     caller_balance: WordLoHiCell<F>,
     callee_reversion_info: ReversionInfo<F>,
     callee_nonce: Cell<F>,
     prev_code_hash: WordLoHiCell<F>,
     prev_code_hash_is_zero: IsZeroWordGadget<F, WordLoHi<Expression<F>>>,
     transfer: TransferGadget<F, false>,
-    create: ContractCreateGadget<F, IS_CREATE2>,
+    create_address: CreateAddressGadget<F, IS_CREATE2>,
 
     init_code: MemoryAddressGadget<F>,
     init_code_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_ADDRESS>,
     init_code_rlc: Cell<F>,
-    keccak_output: Word32Cell<F>,
 
     is_depth_in_range: LtGadget<F, N_BYTES_U64>,
     is_insufficient_balance: LtWordGadget<F>,
@@ -95,6 +89,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             opcode.expr(),
             vec![OpcodeId::CREATE2.expr(), OpcodeId::CREATE.expr()],
         );
+        // CREATE for the CREATE state and CREATE2 for the CREATE2 state.
+        cb.responsible_opcode_lookup(opcode.expr());
         let is_create2 = cb.is_eq(opcode.expr(), OpcodeId::CREATE2.expr());
 
         // Use rw_counter of the step which triggers next call as its call_id.
@@ -107,14 +103,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let depth = cb.call_context(None, CallContextFieldTag::Depth);
         let mut reversion_info = cb.reversion_info_read(None);
 
-        let keccak_output = cb.query_word32();
-        let create = ContractCreateGadget::construct(cb);
-        let contract_addr = AccountAddress::new(
-            keccak_output.limbs[..N_BYTES_ACCOUNT_ADDRESS]
-                .to_vec()
-                .try_into()
-                .unwrap(),
-        );
+        let caller_address = cb.query_word_unchecked();
+        let caller_nonce = cb.query_cell();
+        let create_address =
+            CreateAddressGadget::construct(cb, caller_address.to_word(), caller_nonce.expr());
+        let create = create_address.create();
+        let contract_addr = create_address.address();
 
         // stack operations
         let value = cb.query_word32();
@@ -126,18 +120,17 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         cb.condition(is_create2.expr(), |cb| {
             cb.stack_pop(create.salt());
         });
-        cb.stack_push(contract_addr.to_word().mul_selector(is_success.expr()));
+        cb.stack_push(contract_addr.clone().mul_selector(is_success.expr()));
 
         // read caller's balance and nonce
-        let caller_nonce = create.caller_nonce();
         let caller_balance = cb.query_word_unchecked();
         cb.account_read(
-            create.caller_address(),
+            caller_address.to_word(),
             AccountFieldTag::Balance,
             caller_balance.to_word(),
         );
         cb.account_read(
-            create.caller_address(),
+            caller_address.to_word(),
             AccountFieldTag::Nonce,
             WordLoHi::from_lo_unchecked(caller_nonce.expr()),
         );
@@ -155,16 +148,18 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         // verify gas cost
         let init_code = MemoryAddressGadget::construct(cb, offset, length);
         let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.address()]);
-        let init_code_word_size = ConstantDivisionGadget::construct(
+        let init_code_word_size = ConstantDivisionGadget::construct_for_max(
             cb,
             init_code.length() + (N_BYTES_WORD - 1).expr(),
             N_BYTES_WORD as u64,
+            (1 << (8 * N_BYTES_MEMORY_ADDRESS)) - 1 + (N_BYTES_WORD as u64 - 1),
         );
+        let init_code_word_gas = cb.feature_config.init_code_word_gas();
         let keccak_gas_cost = init_code_word_size.quotient()
             * select::expr(
                 is_create2.expr(),
-                (INIT_CODE_WORD_GAS + GasCost::COPY_SHA3).expr(),
-                INIT_CODE_WORD_GAS.expr(),
+                (init_code_word_gas + GasCost::COPY_SHA3).expr(),
+                init_code_word_gas.expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + keccak_gas_cost;
         let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
@@ -179,7 +174,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             cb.condition(is_precheck_ok.expr(), |cb| {
                 // increase caller's nonce
                 cb.account_write(
-                    create.caller_address(),
+                    caller_address.to_word(),
                     AccountFieldTag::Nonce,
                     WordLoHi::from_lo_unchecked(caller_nonce.expr() + 1.expr()),
                     WordLoHi::from_lo_unchecked(caller_nonce.expr()),
@@ -189,7 +184,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                 // add callee to access list
                 cb.account_access_list_write_unchecked(
                     tx_id.expr(),
-                    contract_addr.to_word(),
+                    contract_addr.clone(),
                     1.expr(),
                     was_warm.expr(),
                     Some(&mut reversion_info),
@@ -197,7 +192,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
                 // read contract's previous hash
                 cb.account_read(
-                    contract_addr.to_word(),
+                    contract_addr.clone(),
                     AccountFieldTag::CodeHash,
                     prev_code_hash.to_word(),
                 );
@@ -211,7 +206,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                 let prev_code_hash_is_zero = cb.is_zero_word(&prev_code_hash_word);
                 cb.condition(not::expr(prev_code_hash_is_zero.expr()), |cb| {
                     cb.account_read(
-                        contract_addr.to_word(),
+                        contract_addr.clone(),
                         AccountFieldTag::Nonce,
                         WordLoHi::from_lo_unchecked(callee_nonce.expr()),
                     );
@@ -315,11 +310,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             and::expr([is_precheck_ok.clone(), not_address_collision.expr()]),
             |cb| {
                 // keccak table lookup to verify contract address.
-                cb.keccak_table_lookup(
-                    create.input_rlc(cb),
-                    create.input_length(),
-                    keccak_output.to_word(),
-                );
+                create_address.keccak_lookup(cb);
 
                 // propagate is_persistent
                 cb.require_equal(
@@ -331,8 +322,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                 // transfer
                 let transfer = TransferGadget::construct(
                     cb,
-                    create.caller_address(),
-                    contract_addr.to_word(),
+                    caller_address.to_word(),
+                    contract_addr.clone(),
                     false.expr(),
                     true.expr(),
                     value.clone(),
@@ -342,7 +333,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
                 // EIP 161, the nonce of a newly created contract is 1
                 cb.account_write(
-                    contract_addr.to_word(),
+                    contract_addr.clone(),
                     AccountFieldTag::Nonce,
                     WordLoHi::one(),
                     WordLoHi::zero(),
@@ -367,8 +358,8 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
                             CallContextFieldTag::TxId,
                             WordLoHi::from_lo_unchecked(tx_id.expr()),
                         ),
-                        (CallContextFieldTag::CallerAddress, create.caller_address()),
-                        (CallContextFieldTag::CalleeAddress, contract_addr.to_word()),
+                        (CallContextFieldTag::CallerAddress, caller_address.to_word()),
+                        (CallContextFieldTag::CalleeAddress, contract_addr.clone()),
                         (
                             CallContextFieldTag::RwCounterEndOfReversion,
                             WordLoHi::from_lo_unchecked(
@@ -494,12 +485,13 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             memory_expansion,
             gas_left,
             init_code_word_size,
-            create,
+            create_address,
+            caller_address,
+            caller_nonce,
             caller_balance,
             is_depth_in_range,
             is_insufficient_balance,
             is_nonce_in_range,
-            keccak_output,
             not_address_collision,
             is_success,
             prev_code_hash,
@@ -561,6 +553,10 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
         let is_precheck_ok =
             call.depth < 1025 && caller_balance >= value && caller_nonce < u64::MAX;
 
+        self.caller_address
+            .assign_h160(region, offset, call.address)?;
+        self.caller_nonce
+            .assign(region, offset, Value::known(F::from(caller_nonce)))?;
         self.caller_balance
             .assign_u256(region, offset, caller_balance)?;
         let (was_warm, callee_prev_code_hash, callee_nonce) = if is_precheck_ok {
@@ -620,11 +616,12 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             offset,
             (31u64 + init_code_length.as_u64()).into(),
         )?;
+        let init_code_word_gas = block.feature_config.init_code_word_gas();
         let initcode_gas_cost = u64::try_from(init_code_word_size).unwrap()
             * if is_create2 {
-                INIT_CODE_WORD_GAS + GasCost::COPY_SHA3
+                init_code_word_gas + GasCost::COPY_SHA3
             } else {
-                INIT_CODE_WORD_GAS
+                init_code_word_gas
             };
         let gas_left =
             step.gas_left - GasCost::CREATE - memory_expansion_gas_cost - initcode_gas_cost;
@@ -652,27 +649,6 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             }
 
             let code_hash = CodeDB::hash(&values);
-            let keccak_input: Vec<u8> = if is_create2 {
-                once(0xffu8)
-                    .chain(call.address.to_fixed_bytes())
-                    .chain(salt.to_be_bytes())
-                    .chain(code_hash.to_fixed_bytes())
-                    .collect()
-            } else {
-                let mut stream = ethers_core::utils::rlp::RlpStream::new();
-                stream.begin_list(2);
-                stream.append(&call.address);
-                stream.append(&caller_nonce);
-                stream.out().to_vec()
-            };
-            let mut keccak_output = keccak256(keccak_input);
-            keccak_output.reverse();
-
-            self.keccak_output.assign_u256(
-                region,
-                offset,
-                U256::from_little_endian(&keccak_output),
-            )?;
             self.init_code_rlc.assign(
                 region,
                 offset,
@@ -688,7 +664,7 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
             CodeDB::empty_code_hash()
         };
 
-        self.create.assign(
+        self.create_address.assign(
             region,
             offset,
             call.address,
@@ -730,7 +706,10 @@ impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        mpt_circuit::{rlp_encode_item, rlp_encode_list, rlp_encode_word},
+        test_util::CircuitTestBuilder,
+    };
     use eth_types::{
         address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode, Word,
     };
@@ -892,6 +871,40 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_create_tampered_preimage() {
+        let nonce = 0x80u64;
+        let caller = Account {
+            address: *CALLER_ADDRESS,
+            code: creator_bytecode(initialization_bytecode(true), 23414.into(), false, true).into(),
+            nonce: nonce.into(),
+            balance: eth(10),
+            ..Default::default()
+        };
+        let preimage = |nonce: u64| {
+            rlp_encode_list(&[
+                rlp_encode_item(CALLER_ADDRESS.as_bytes()),
+                rlp_encode_word(nonce.into()),
+            ])
+        };
+
+        // The keccak table holds the hash of rlp([caller, nonce + 1]) instead
+        // of the one of rlp([caller, nonce]), which the CREATE address must be
+        // derived from.
+        CircuitTestBuilder::new_from_test_ctx(test_context(caller))
+            .block_modifier(Box::new(move |block, _chunks| {
+                let input = block
+                    .sha3_inputs
+                    .iter_mut()
+                    .find(|input| **input == preimage(nonce))
+                    .expect("CREATE preimage in the keccak inputs");
+                *input = preimage(nonce + 1);
+            }))
+            .run_with_result()
+            .unwrap_err()
+            .assert_evm_failure();
+    }
+
     #[test]
     fn test_create_empty_init_code() {
         for is_create2 in [true, false] {
@@ -84,10 +84,13 @@ impl<F: Field> ExecutionGadget<F> for EndChunkGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{
-        test_util::CircuitTestBuilder,
-        witness::{block_convert, chunk_convert},
+        test_util::{Circuit, CircuitTestBuilder, CircuitTestError},
+        witness::{block_convert, chunk_convert, Block, ExecStep, Rw},
+    };
+    use bus_mapping::{
+        circuit_input_builder::{FeatureConfig, FixedCParams, N_EXEC_STATE},
+        mock::BlockData,
     };
-    use bus_mapping::{circuit_input_builder::FixedCParams, mock::BlockData};
     use eth_types::{address, bytecode, geth_types::GethData, Word};
     use halo2_proofs::halo2curves::bn256::Fr;
     use mock::TestContext;
@@ -200,6 +203,104 @@ mod test {
             .unwrap();
     }
 
+    /// Return the StepState rows of a BeginChunk or EndChunk step.
+    fn step_state_rows(block: &Block<Fr>, step: &ExecStep) -> Vec<Rw> {
+        (0..N_EXEC_STATE)
+            .map(|idx| block.get_rws(step, idx))
+            .inspect(|rw| assert!(matches!(rw, Rw::StepState { .. })))
+            .collect()
+    }
+
+    #[test]
+    fn test_end_chunk_step_state_at_boundary() {
+        let mut bytecode = bytecode! {};
+        for _ in 0..50 {
+            bytecode.op_push1(0x01).op_pop();
+        }
+        bytecode.op_stop();
+        let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+        let params = FixedCParams {
+            total_chunks: 2,
+            max_evm_rows: 1 << 12,
+            max_rws: 128,
+            max_txs: 1,
+            ..Default::default()
+        };
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(test_ctx)
+            .params(params)
+            .build_block(Some(2))
+            .unwrap();
+
+        let end_chunk = chunks[0].end_chunk.as_ref().unwrap();
+        let begin_chunk = chunks[1].begin_chunk.as_ref().unwrap();
+        // The chunk boundary falls in the middle of the transaction.
+        let steps = block.txs[0].steps();
+        assert!(steps.iter().any(|step| step.rwc.0 < end_chunk.rwc.0));
+        assert!(steps.iter().any(|step| step.rwc.0 > begin_chunk.rwc.0));
+
+        let writes = step_state_rows(&block, end_chunk);
+        let reads = step_state_rows(&block, begin_chunk);
+        for (write, read) in writes.iter().zip(reads.iter()) {
+            let (
+                Rw::StepState {
+                    rw_counter: write_rwc,
+                    is_write: true,
+                    field_tag: write_tag,
+                    value: write_value,
+                },
+                Rw::StepState {
+                    rw_counter: read_rwc,
+                    is_write: false,
+                    field_tag: read_tag,
+                    value: read_value,
+                },
+            ) = (write, read)
+            else {
+                panic!("unexpected StepState rows {:?} and {:?}", write, read);
+            };
+            assert_eq!(write_tag, read_tag);
+            assert_eq!(write_value, read_value);
+            // The next chunk continues right after the EndChunk writes.
+            assert_eq!(write_rwc + N_EXEC_STATE, *read_rwc);
+        }
+        assert_eq!(block.rws.validate_step_state_bracketing(), Ok(()));
+
+        CircuitTestBuilder::new_from_block(block, chunks).run();
+    }
+
+    #[test]
+    fn test_disable_rw_permutation_rejects_multiple_chunks() {
+        let mut bytecode = bytecode! {};
+        for _ in 0..50 {
+            bytecode.op_push1(0x01).op_pop();
+        }
+        bytecode.op_stop();
+        let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+        let params = FixedCParams {
+            total_chunks: 2,
+            max_evm_rows: 1 << 12,
+            max_rws: 128,
+            max_txs: 1,
+            ..Default::default()
+        };
+
+        let err = CircuitTestBuilder::new_from_test_ctx(test_ctx)
+            .params(params)
+            .feature(FeatureConfig {
+                disable_rw_permutation: true,
+                ..Default::default()
+            })
+            .run_multiple_chunks_with_result(Some(2))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CircuitTestError::SynthesisFailure {
+                circuit: Circuit::EVM,
+                ..
+            }
+        ));
+    }
+
     test_2_txs_with_various_chunk_size! {
         test_2_txs_with_1_400: (1, 400),
         test_2_txs_with_2_400: (2, 400),
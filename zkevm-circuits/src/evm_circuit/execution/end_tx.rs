@@ -21,7 +21,7 @@ use crate::{
         Expr,
     },
 };
-use eth_types::{evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, Field};
+use eth_types::Field;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -60,11 +60,9 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
         // Calculate effective gas to refund
         let gas_used = tx_gas.expr() - cb.curr.state.gas_left.expr();
-        let max_refund = ConstantDivisionGadget::construct(
-            cb,
-            gas_used.clone(),
-            MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
-        );
+        let max_refund_quotient = cb.feature_config.hardfork.max_refund_quotient();
+        let max_refund =
+            ConstantDivisionGadget::construct(cb, gas_used.clone(), max_refund_quotient);
         let refund = cb.query_cell();
         cb.tx_refund_read(tx_id.expr(), WordLoHi::from_lo_unchecked(refund.expr()));
         let effective_refund = cb.min_max(max_refund.quotient(), refund.expr());
@@ -17,14 +17,12 @@ use crate::{
 };
 
 use eth_types::{
-    evm_types::{GasCost, OpcodeId},
+    evm_types::{GasCost, OpcodeId, MAX_CODE_SIZE},
     Field,
 };
 
 use halo2_proofs::{circuit::Value, plonk::Error};
 
-const MAXCODESIZE: u64 = 0x6000u64;
-
 /// Gadget for code store oog and max code size exceed
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorCodeStoreGadget<F> {
@@ -71,8 +69,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorCodeStoreGadget<F> {
             GasCost::CODE_DEPOSIT_BYTE_COST.expr() * memory_address.length(),
         );
 
-        // constrain code size > MAXCODESIZE
-        let max_code_size_exceed = cb.is_lt(MAXCODESIZE.expr(), memory_address.length());
+        // constrain code size > MAX_CODE_SIZE
+        let max_code_size_exceed = cb.is_lt(MAX_CODE_SIZE.expr(), memory_address.length());
 
         // check must be one of CodeStoreOutOfGas or MaxCodeSizeExceeded
         cb.require_in_set(
@@ -123,7 +121,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorCodeStoreGadget<F> {
         self.max_code_size_exceed.assign(
             region,
             offset,
-            F::from(MAXCODESIZE),
+            F::from(MAX_CODE_SIZE),
             F::from(length.as_u64()),
         )?;
 
@@ -137,7 +135,10 @@ impl<F: Field> ExecutionGadget<F> for ErrorCodeStoreGadget<F> {
 mod test {
     use bus_mapping::circuit_input_builder::FixedCParams;
     use eth_types::{
-        address, bytecode, evm_types::OpcodeId, geth_types::Account, Address, Bytecode, Word,
+        address, bytecode,
+        evm_types::{OpcodeId, MAX_CODE_SIZE},
+        geth_types::Account,
+        Address, Bytecode, Word,
     };
 
     use lazy_static::lazy_static;
@@ -149,8 +150,6 @@ mod test {
         static ref CALLER_ADDRESS: Address = address!("0x00bbccddee000000000000000000000000002400");
     }
 
-    const MAXCODESIZE: u64 = 0x6000u64;
-
     fn run_test_circuits(ctx: TestContext<2, 1>) {
         CircuitTestBuilder::new_from_test_ctx(ctx)
             .params(FixedCParams {
@@ -163,7 +162,7 @@ mod test {
     fn initialization_bytecode(is_oog: bool) -> Bytecode {
         let memory_bytes = [0x60; 10];
         let memory_value = Word::from_big_endian(&memory_bytes);
-        let code_len = if is_oog { 5 } else { MAXCODESIZE + 1 };
+        let code_len = if is_oog { 5 } else { MAX_CODE_SIZE + 1 };
 
         let mut code = bytecode! {
             PUSH10(memory_value)
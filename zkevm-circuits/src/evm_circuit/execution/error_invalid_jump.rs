@@ -183,7 +183,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidJumpGadget<F> {
 #[cfg(test)]
 mod test {
 
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{evm_circuit::step::ExecutionState, test_util::CircuitTestBuilder};
     use eth_types::{
         address, bytecode, bytecode::Bytecode, evm_types::OpcodeId, geth_types::Account, Address,
         ToWord, Word,
@@ -222,6 +222,24 @@ mod test {
         test_invalid_jump(40, true);
     }
 
+    #[test]
+    fn invalid_jump_into_push_data() {
+        // Every immediate byte of the PUSH32 is 0x5b (JUMPDEST) but isn't code
+        let bytecode = bytecode! {
+            PUSH32(Word::from_big_endian(&[OpcodeId::JUMPDEST.as_u8(); 32]))
+            POP
+            PUSH1(5)
+            JUMP
+        };
+
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        )
+        // BeginTx, PUSH32, POP, PUSH1, JUMP
+        .expect_execution_state(4, ExecutionState::ErrorInvalidJump)
+        .run();
+    }
+
     #[test]
     fn invalid_jump_internal() {
         // test jump error in internal call
@@ -1,12 +1,9 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::N_BYTES_GAS,
         step::ExecutionState,
         util::{
-            common_gadget::CommonErrorGadget,
-            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::LtGadget,
+            common_gadget::CommonErrorOOGGadget, constraint_builder::EVMConstraintBuilder,
             CachedRegion, Cell,
         },
         witness::{Block, Call, Chunk, ExecStep, Transaction},
@@ -21,8 +18,7 @@ pub(crate) struct ErrorOOGConstantGadget<F> {
     opcode: Cell<F>,
     // constrain gas left is less than required
     gas_required: Cell<F>,
-    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
-    common_error_gadget: CommonErrorGadget<F>,
+    common_error_oog_gadget: CommonErrorOOGGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for ErrorOOGConstantGadget<F> {
@@ -38,20 +34,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGConstantGadget<F> {
         cb.constant_gas_lookup(opcode.expr(), gas_required.expr());
         // Check if the amount of gas available is less than the amount of gas
         // required
-        let insufficient_gas = cb.is_lt(cb.curr.state.gas_left.expr(), gas_required.expr());
-        cb.require_equal(
-            "constant gas left is less than gas required ",
-            insufficient_gas.expr(),
-            1.expr(),
-        );
-
-        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 0.expr());
+        let common_error_oog_gadget =
+            CommonErrorOOGGadget::construct(cb, opcode.expr(), gas_required.expr(), 0.expr());
 
         Self {
             opcode,
             gas_required,
-            insufficient_gas,
-            common_error_gadget,
+            common_error_oog_gadget,
         }
     }
 
@@ -73,16 +62,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGConstantGadget<F> {
         self.gas_required
             .assign(region, offset, Value::known(F::from(step.gas_cost)))?;
         // Gas insufficient check
-        // Get `gas_available` variable here once it's available
-        self.insufficient_gas.assign(
-            region,
-            offset,
-            F::from(step.gas_left),
-            F::from(step.gas_cost),
-        )?;
-
-        self.common_error_gadget
-            .assign(region, offset, block, call, step, 2)?;
+        self.common_error_oog_gadget
+            .assign(region, offset, block, call, step, step.gas_cost, 2)?;
         Ok(())
     }
 }
@@ -18,9 +18,7 @@ use crate::{
     witness::{Block, Call, Chunk, ExecStep, Transaction},
 };
 use eth_types::{
-    evm_types::{
-        GasCost, OpcodeId, CREATE2_GAS_PER_CODE_WORD, CREATE_GAS_PER_CODE_WORD, MAX_INIT_CODE_SIZE,
-    },
+    evm_types::{GasCost, OpcodeId},
     Field, U256,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
@@ -35,8 +33,8 @@ pub(crate) struct ErrorOOGCreateGadget<F> {
     memory_address: MemoryExpandedAddressGadget<F>,
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
     // Init code size is overflow when it is greater than 49152
-    // (maximum init code size) if Shanghai, otherwise when it is greater than
-    // 0x1FFFFFFFE0 (maximum value of offset + size).
+    // (maximum init code size) with the EIP-3860 limit, otherwise when it is
+    // greater than 0x1FFFFFFFE0 (maximum value of offset + size).
     // Uint64 overflow is checked in `memory_address` (offset + length).
     init_code_size_overflow: LtGadget<F, { N_BYTES_MEMORY_ADDRESS }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
@@ -68,16 +66,20 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         cb.stack_pop(memory_address.length_word());
         cb.condition(is_create2.expr().0, |cb| cb.stack_pop(salt.to_word()));
 
-        let init_code_size_overflow = cb.is_lt(MAX_INIT_CODE_SIZE.expr(), memory_address.length());
+        let init_code_size_overflow = cb.is_lt(
+            cb.feature_config.max_init_code_size().expr(),
+            memory_address.length(),
+        );
 
         let minimum_word_size = MemoryWordSizeGadget::construct(cb, memory_address.length());
         let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
 
+        let init_code_word_gas = cb.feature_config.init_code_word_gas();
         let code_store_gas_cost = minimum_word_size.expr()
             * select::expr(
                 is_create2.expr().0,
-                CREATE2_GAS_PER_CODE_WORD.expr(),
-                CREATE_GAS_PER_CODE_WORD.expr(),
+                (init_code_word_gas + GasCost::COPY_SHA3).expr(),
+                init_code_word_gas.expr(),
             );
         let gas_cost = GasCost::CREATE.expr() + memory_expansion.gas_cost() + code_store_gas_cost;
         let insufficient_gas = cb.is_lt(cb.curr.state.gas_left.expr(), gas_cost);
@@ -168,15 +170,16 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
         self.init_code_size_overflow.assign(
             region,
             offset,
-            F::from(MAX_INIT_CODE_SIZE),
+            F::from(block.feature_config.max_init_code_size()),
             F::from(init_code_size),
         )?;
 
+        let init_code_word_gas = block.feature_config.init_code_word_gas();
         let code_store_gas_cost = minimum_word_size
             * if is_create2 {
-                CREATE2_GAS_PER_CODE_WORD
+                init_code_word_gas + GasCost::COPY_SHA3
             } else {
-                CREATE_GAS_PER_CODE_WORD
+                init_code_word_gas
             };
         self.insufficient_gas.assign(
             region,
@@ -202,7 +205,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGCreateGadget<F> {
 mod tests {
     use super::*;
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, word, Bytecode, ToWord};
+    use eth_types::{bytecode, evm_types::MAX_INIT_CODE_SIZE, word, Bytecode, ToWord};
     use mock::{
         eth,
         test_ctx::{helpers::account_0_code_account_1_no_code, LoggerConfig},
@@ -317,6 +320,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_create_init_code_size_limit() {
+        for is_create2 in [true, false] {
+            // One byte over the EIP-3860 limit of 49152, well below the maximum
+            // expanded memory address, so only `init_code_size_overflow` is set.
+            let case = TestCase::new(is_create2, U256::zero(), 49153.into(), MOCK_BLOCK_GAS_LIMIT);
+
+            test_root(&case);
+            test_internal(&case);
+        }
+    }
+
     fn test_root(case: &TestCase) {
         let ctx = TestContext::<2, 1>::new_with_logger_config(
             None,
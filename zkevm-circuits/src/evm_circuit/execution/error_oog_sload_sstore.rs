@@ -6,8 +6,8 @@ use crate::{
         util::{
             and,
             common_gadget::{
-                cal_sload_gas_cost_for_assignment, cal_sstore_gas_cost_for_assignment,
-                CommonErrorGadget, SloadGasGadget, SstoreGasGadget,
+                cal_sload_gas_cost_for_assignment, CommonErrorGadget, SloadGasGadget,
+                SstoreGasGadget,
             },
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{LtGadget, PairSelectGadget},
@@ -174,7 +174,10 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
             let value = rws.next().stack_value();
             let (_, value_prev, _, original_value) = rws.next().storage_value_aux();
             let gas_cost =
-                cal_sstore_gas_cost_for_assignment(value, value_prev, original_value, is_warm);
+                block
+                    .feature_config
+                    .hardfork
+                    .gas_cost(value, value_prev, original_value, is_warm);
             (value, value_prev, original_value, gas_cost)
         } else {
             let gas_cost = cal_sload_gas_cost_for_assignment(is_warm);
@@ -244,10 +247,8 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGSloadSstoreGadget<F> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{
-        evm_circuit::{test::rand_bytes, util::common_gadget::cal_sstore_gas_cost_for_assignment},
-        test_util::CircuitTestBuilder,
-    };
+    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use bus_mapping::circuit_input_builder::Hardfork;
     use eth_types::{
         bytecode,
         evm_types::{GasCost, OpcodeId},
@@ -411,12 +412,8 @@ mod test {
                 PUSH32(key)
                 SSTORE
             };
-            let sstore_gas_cost = cal_sstore_gas_cost_for_assignment(
-                value_prev,
-                original_value,
-                original_value,
-                false,
-            );
+            let sstore_gas_cost =
+                Hardfork::default().gas_cost(value_prev, original_value, original_value, false);
             let mut gas_cost = 2 * OpcodeId::PUSH32.constant_gas_cost()
                 + max(
                     sstore_gas_cost,
@@ -428,12 +425,8 @@ mod test {
                     PUSH32(key)
                     SSTORE
                 });
-                let sstore_gas_cost = cal_sstore_gas_cost_for_assignment(
-                    value_prev,
-                    original_value,
-                    original_value,
-                    true,
-                );
+                let sstore_gas_cost =
+                    Hardfork::default().gas_cost(value_prev, original_value, original_value, true);
                 gas_cost += 2 * OpcodeId::PUSH32.constant_gas_cost()
                     + max(
                         sstore_gas_cost,
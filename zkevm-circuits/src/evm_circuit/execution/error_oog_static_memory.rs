@@ -1,12 +1,12 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS},
+        param::N_BYTES_MEMORY_ADDRESS,
         step::ExecutionState,
         util::{
-            common_gadget::CommonErrorGadget,
+            common_gadget::CommonErrorOOGGadget,
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-            math_gadget::{IsEqualGadget, LtGadget},
+            math_gadget::IsEqualGadget,
             memory_gadget::{
                 CommonMemoryAddressGadget, MemoryExpandedAddressGadget, MemoryExpansionGadget,
             },
@@ -17,7 +17,6 @@ use crate::{
     util::{word::WordLoHi, Expr},
 };
 use eth_types::{evm_types::OpcodeId, Field, ToWord};
-use gadgets::util::or;
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -32,8 +31,7 @@ pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     // number up to 2^35 - 1.
     memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_ADDRESS>,
     memory_address: MemoryExpandedAddressGadget<F>,
-    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
-    common_error_gadget: CommonErrorGadget<F>,
+    common_error_oog_gadget: CommonErrorOOGGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
@@ -72,28 +70,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 
         // Check if the amount of gas available is less than the amount of gas required
         // constant gas of MSTORE, MSTORE8 and MLOAD are the same
-        let insufficient_gas = LtGadget::construct(
+        let common_error_oog_gadget = CommonErrorOOGGadget::construct(
             cb,
-            cb.curr.state.gas_left.expr(),
+            opcode.expr(),
             OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost(),
+            memory_address.overflow(),
         );
 
-        cb.require_equal(
-            "Memory address is overflow or gas left is less than required",
-            or::expr([memory_address.overflow(), insufficient_gas.expr()]),
-            1.expr(),
-        );
-
-        let common_error_gadget =
-            CommonErrorGadget::construct(cb, opcode.expr(), cb.rw_counter_offset());
-
         Self {
             opcode,
             memory_address,
             memory_expansion,
-            insufficient_gas,
             is_mstore8,
-            common_error_gadget,
+            common_error_oog_gadget,
         }
     }
 
@@ -139,16 +128,16 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 
         // Gas insufficient check
         let const_gas = opcode.constant_gas_cost();
-        self.insufficient_gas.assign(
+        self.common_error_oog_gadget.assign(
             region,
             offset,
-            F::from(step.gas_left),
-            F::from(memory_expansion_gas + const_gas),
+            block,
+            call,
+            step,
+            memory_expansion_gas + const_gas,
+            3,
         )?;
 
-        self.common_error_gadget
-            .assign(region, offset, block, call, step, 3)?;
-
         Ok(())
     }
 }
@@ -156,7 +145,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 #[cfg(test)]
 mod tests {
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, word, Bytecode, ToWord, U256};
+    use eth_types::{bytecode, evm_types::GasCost, word, Bytecode, ToWord, U256};
     use mock::{
         eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
     };
@@ -194,6 +183,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_static_memory_expansion_only() {
+        // The constant gas of MSTORE is covered but not the memory expansion
+        // to 0x1020 bytes.
+        let code = bytecode! {
+            PUSH8(0x14)
+            PUSH32(0x1000)
+            MSTORE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX + 2 * GasCost::FASTEST + GasCost::FASTEST + 10).into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     fn testing_bytecodes(offset: U256) -> Vec<Bytecode> {
         vec![
             bytecode! {
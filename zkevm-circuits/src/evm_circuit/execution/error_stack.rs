@@ -1,11 +1,13 @@
 use crate::{
     evm_circuit::{
         execution::ExecutionGadget,
+        param::STACK_CAPACITY,
         step::ExecutionState,
-        table::{FixedTableTag, Lookup},
         util::{
-            common_gadget::CommonErrorGadget, constraint_builder::EVMConstraintBuilder,
-            CachedRegion, Cell,
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            or, CachedRegion, Cell,
         },
         witness::{Block, Call, Chunk, ExecStep, Transaction},
     },
@@ -17,6 +19,12 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorStackGadget<F> {
     opcode: Cell<F>,
+    min_stack: Cell<F>,
+    max_stack: Cell<F>,
+    // `STACK_CAPACITY < stack_pointer + min_stack`
+    is_underflow: LtGadget<F, 2>,
+    // `stack_pointer + min_stack < max_stack`
+    is_overflow: LtGadget<F, 2>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -27,23 +35,30 @@ impl<F: Field> ExecutionGadget<F> for ErrorStackGadget<F> {
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
+        let min_stack = cb.query_cell();
+        let max_stack = cb.query_cell();
+        cb.stack_pointer_lookup(opcode.expr(), min_stack.expr(), max_stack.expr());
 
-        cb.add_lookup(
-            "Responsible opcode lookup for invalid stack pointer",
-            Lookup::Fixed {
-                tag: FixedTableTag::ResponsibleOpcode.expr(),
-                values: [
-                    Self::EXECUTION_STATE.as_u64().expr(),
-                    opcode.expr(),
-                    cb.curr.state.stack_pointer.expr(),
-                ],
-            },
+        // The stack holds `STACK_CAPACITY - stack_pointer` items.  The opcode
+        // underflows when there are less than `min_stack` of them, and
+        // overflows when it would grow the stack beyond `STACK_CAPACITY`.
+        let stack_pointer_plus_min_stack = cb.curr.state.stack_pointer.expr() + min_stack.expr();
+        let is_underflow = cb.is_lt(STACK_CAPACITY.expr(), stack_pointer_plus_min_stack.clone());
+        let is_overflow = cb.is_lt(stack_pointer_plus_min_stack, max_stack.expr());
+        cb.require_equal(
+            "stack underflow or overflow",
+            or::expr([is_underflow.expr(), is_overflow.expr()]),
+            1.expr(),
         );
 
         let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 0.expr());
 
         Self {
             opcode,
+            min_stack,
+            max_stack,
+            is_underflow,
+            is_overflow,
             common_error_gadget,
         }
     }
@@ -62,6 +77,26 @@ impl<F: Field> ExecutionGadget<F> for ErrorStackGadget<F> {
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
 
+        let (min_stack, max_stack) = opcode.stack_requirements();
+        let (min_stack, max_stack) = (u64::from(min_stack), u64::from(max_stack));
+        self.min_stack
+            .assign(region, offset, Value::known(F::from(min_stack)))?;
+        self.max_stack
+            .assign(region, offset, Value::known(F::from(max_stack)))?;
+        let stack_pointer_plus_min_stack = F::from(step.stack_pointer() + min_stack);
+        self.is_underflow.assign(
+            region,
+            offset,
+            F::from(STACK_CAPACITY as u64),
+            stack_pointer_plus_min_stack,
+        )?;
+        self.is_overflow.assign(
+            region,
+            offset,
+            stack_pointer_plus_min_stack,
+            F::from(max_stack),
+        )?;
+
         self.common_error_gadget
             .assign(region, offset, block, call, step, 2)?;
 
@@ -280,4 +280,15 @@ mod tests {
         test_ok(Word::MAX, 2.into());
         test_ok(Word::MAX, 3.into());
     }
+
+    #[test]
+    fn exp_gadget_wraps_around() {
+        // 2^256 mod 2^256 == 0
+        test_ok(2.into(), 256.into());
+        test_ok(2.into(), 257.into());
+        test_ok(0x10.into(), 64.into());
+        test_ok(0x100.into(), 32.into());
+        // 0^0 == 1, not 0
+        test_ok(Word::zero(), Word::zero());
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
         util::{
             constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
             math_gadget::{IsEqualGadget, LtGadget, LtWordGadget},
-            tx::{BeginTxHelperGadget, EndTxHelperGadget, TxDataGadget},
+            tx::{BeginTxHelperGadget, EndTxHelperGadget, IntrinsicGasGadget, TxDataGadget},
             CachedRegion, Cell, StepRws,
         },
         witness::{Block, Call, ExecStep, Transaction},
@@ -51,8 +51,11 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
         let is_nonce_match = cb.is_eq(account_nonce.expr(), tx.nonce.expr());
 
         // Check if the gas limit is larger or equal to the intrinsic gas cost
-        let insufficient_gas_limit =
-            LtGadget::<F, N_BYTES_GAS>::construct(cb, tx.gas.expr(), tx.intrinsic_gas());
+        let insufficient_gas_limit = LtGadget::<F, N_BYTES_GAS>::construct(
+            cb,
+            tx.gas.expr(),
+            tx.intrinsic_gas(cb.feature_config.init_code_word_gas()),
+        );
 
         // Check if the balance is sufficient to pay for the total tx cost (intrinsic gas + value)
         let balance = cb.query_word32();
@@ -121,7 +124,8 @@ impl<F: Field> ExecutionGadget<F> for InvalidTxGadget<F> {
             region,
             offset,
             tx.gas().scalar(),
-            tx.intrinsic_gas_cost().scalar(),
+            IntrinsicGasGadget::<F>::intrinsic_gas(tx, block.feature_config.init_code_word_gas())
+                .scalar(),
         )?;
         self.balance.assign_u256(region, offset, balance)?;
         self.insufficient_balance.assign(
@@ -141,7 +145,7 @@ mod test {
     use crate::test_util::CircuitTestBuilder;
     use bus_mapping::circuit_input_builder::FeatureConfig;
     use eth_types::{self, bytecode, Word};
-    use mock::{eth, gwei, TestContext, MOCK_ACCOUNTS};
+    use mock::{eth, gwei, InvalidTxReason, TestContext, MOCK_ACCOUNTS};
 
     #[test]
     fn invalid_tx_invalid_nonce() {
@@ -239,6 +243,41 @@ mod test {
             .run();
     }
 
+    #[test]
+    fn invalid_tx_make_invalid() {
+        fn test_ok(reason: InvalidTxReason) {
+            let to = MOCK_ACCOUNTS[0];
+            let from = MOCK_ACCOUNTS[1];
+            let ctx = TestContext::<2, 2>::new(
+                None,
+                |accs| {
+                    accs[0].address(to).balance(eth(1));
+                    accs[1].address(from).balance(eth(1)).nonce(1);
+                },
+                |mut txs, _| {
+                    txs[0].to(to).from(from).make_invalid(reason);
+                    // The valid tx is processed with the nonce the invalid tx skipped.
+                    txs[1].to(to).from(from);
+                },
+                |block, _| block,
+            )
+            .unwrap();
+            assert!(ctx.geth_traces[0].invalid);
+            assert!(!ctx.geth_traces[1].invalid);
+            assert_eq!(ctx.eth_block.transactions[1].nonce, Word::from(1));
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .feature(FeatureConfig {
+                    invalid_tx: true,
+                    ..Default::default()
+                })
+                .run();
+        }
+
+        test_ok(InvalidTxReason::InvalidNonce);
+        test_ok(InvalidTxReason::InsufficientBalance);
+        test_ok(InvalidTxReason::IntrinsicGasTooLow);
+    }
+
     #[test]
     fn invalid_tx_state_transitions() {
         fn test_ok<const NTX: usize>(tx_states: [bool; NTX]) {
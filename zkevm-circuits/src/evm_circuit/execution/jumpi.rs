@@ -114,10 +114,14 @@ impl<F: Field> ExecutionGadget<F> for JumpiGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{
-        evm_circuit::test::{rand_range, rand_word},
+        evm_circuit::{
+            step::{ExecutionState, HasExecutionState},
+            test::{rand_range, rand_word},
+        },
         test_util::CircuitTestBuilder,
     };
-    use eth_types::{bytecode, Word};
+    use bus_mapping::error::ExecError;
+    use eth_types::{bytecode, evm_types::OpcodeId, Word};
     use mock::TestContext;
 
     fn test_ok(destination: usize, condition: Word) {
@@ -173,6 +177,61 @@ mod test {
         test_ok(rand_range(1 << 11..0x5fff), rand_word());
     }
 
+    // JUMPI is at pc 4, followed by a STOP at pc 5 and a JUMPDEST at pc 6.
+    fn ctx_short_jump(destination: u64) -> TestContext<2, 1> {
+        let bytecode = bytecode! {
+            PUSH1(1) // condition
+            PUSH1(destination)
+            JUMPI
+            STOP
+            JUMPDEST
+            STOP
+        };
+        TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap()
+    }
+
+    #[test]
+    fn jumpi_gadget_taken_to_jumpdest() {
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx_short_jump(6))
+            .build_block(None)
+            .unwrap();
+        let steps = block.txs[0].steps();
+        let jumpi = steps
+            .iter()
+            .position(|step| step.execution_state() == ExecutionState::JUMPI)
+            .unwrap();
+        assert_eq!(steps[jumpi + 1].pc, 6);
+
+        CircuitTestBuilder::new_from_block(block, chunks).run();
+    }
+
+    #[test]
+    fn jumpi_gadget_taken_to_non_jumpdest() {
+        let (mut block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx_short_jump(5))
+            .build_block(None)
+            .unwrap();
+        // The taken jump to a STOP byte is routed to ErrorInvalidJump
+        assert!(block.txs[0]
+            .steps()
+            .iter()
+            .any(
+                |step| step.execution_state() == ExecutionState::ErrorInvalidJump
+                    && step.opcode() == Some(OpcodeId::JUMPI)
+            ));
+        CircuitTestBuilder::new_from_block(block.clone(), chunks.clone()).run();
+
+        // and can't be proven as a successful JUMPI
+        for step in block.txs[0].steps_mut() {
+            if step.error == Some(ExecError::InvalidJump) {
+                step.error = None;
+            }
+        }
+        CircuitTestBuilder::new_from_block(block, chunks)
+            .run_with_result()
+            .unwrap_err()
+            .assert_evm_failure();
+    }
+
     #[test]
     fn jumpi_gadget_with_zero_cond_and_overflow_dest() {
         let bytecode = bytecode! {
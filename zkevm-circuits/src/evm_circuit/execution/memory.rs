@@ -195,7 +195,9 @@ mod test {
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .validate_memory_growth()
+            .run();
     }
 
     #[test]
@@ -84,7 +84,10 @@ impl<F: Field> ExecutionGadget<F> for MsizeGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
     use eth_types::{bytecode, Word};
     use mock::TestContext;
 
@@ -105,4 +108,35 @@ mod test {
         )
         .run();
     }
+
+    #[test]
+    fn msize_gadget_word_aligned() {
+        // MSIZE is the memory size in bytes rounded up to a multiple of 32.
+        let bytecode = bytecode! {
+            PUSH1(0xff)
+            PUSH1(0)
+            MSTORE
+            MSIZE
+            POP
+            PUSH1(0xff)
+            PUSH1(33)
+            MSTORE
+            MSIZE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block(None)
+            .unwrap();
+        let msizes = block.txs[0]
+            .steps()
+            .iter()
+            .filter(|step| step.execution_state() == ExecutionState::MSIZE)
+            .map(|step| block.get_rws(step, 0).stack_value())
+            .collect::<Vec<_>>();
+        assert_eq!(msizes, vec![Word::from(32), Word::from(96)]);
+
+        CircuitTestBuilder::new_from_block(block, chunks).run();
+    }
 }
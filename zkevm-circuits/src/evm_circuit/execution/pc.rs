@@ -80,8 +80,11 @@ impl<F: Field> ExecutionGadget<F> for PcGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{bytecode, Word};
     use mock::TestContext;
 
     fn test_ok() {
@@ -101,4 +104,31 @@ mod test {
     fn pc_gadget_simple() {
         test_ok();
     }
+
+    #[test]
+    fn pc_gadget_after_push_immediates() {
+        // The PUSH immediates occupy 1 + 2 + 32 bytes, so PC is at offset 39.
+        let bytecode = bytecode! {
+            PUSH1(1)
+            PUSH2(2)
+            PUSH32(3)
+            JUMPDEST
+            PC
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap();
+
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block(None)
+            .unwrap();
+        let step = block.txs[0]
+            .steps()
+            .iter()
+            .find(|step| step.execution_state() == ExecutionState::PC)
+            .unwrap();
+        assert_eq!(step.pc, 39);
+        assert_eq!(block.get_rws(step, 0).stack_value(), Word::from(39));
+
+        CircuitTestBuilder::new_from_block(block, chunks).run();
+    }
 }
@@ -1,3 +1,6 @@
+mod ec_pairing;
+pub use ec_pairing::EcPairingGadget;
+
 mod ecrecover;
 pub use ecrecover::EcrecoverGadget;
 
@@ -0,0 +1,319 @@
+use bus_mapping::{circuit_input_builder::Call, precompile::PrecompileCalls};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use gadgets::util::{select, Expr};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_EC_PAIR, N_BYTES_MEMORY_ADDRESS},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, RangeCheckGadget},
+            CachedRegion, Cell,
+        },
+    },
+    table::CallContextFieldTag,
+    witness::{Block, Chunk, ExecStep, Transaction},
+};
+
+#[derive(Clone, Debug)]
+pub struct EcPairingGadget<F> {
+    input_len: EcPairingInputLenGadget<F>,
+    is_success: Cell<F>,
+    callee_address: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for EcPairingGadget<F> {
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileBn256Pairing;
+
+    const NAME: &'static str = "EC_PAIRING";
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let [is_success, callee_address, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::CalleeAddress,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|tag| cb.call_context(None, tag));
+
+        // The input length is checked before any arithmetic: a malformed input
+        // fails the call, which then consumes all of its gas. Failures due to
+        // insufficient gas are diverted to the ErrorOogPrecompile gadget.
+        let input_len = EcPairingInputLenGadget::construct(cb, call_data_length.expr());
+        cb.require_equal(
+            "EcPairing: the call succeeds iff the input length is a multiple of 192 bytes",
+            is_success.expr(),
+            input_len.is_valid(),
+        );
+
+        let hardfork = cb.feature_config.hardfork;
+        let gas_cost = select::expr(
+            is_success.expr(),
+            hardfork.base_gas_cost(PrecompileCalls::Bn128Pairing).expr()
+                + input_len.n_pairs() * GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        cb.precompile_info_lookup(
+            cb.execution_state().as_u64().expr(),
+            callee_address.expr(),
+            cb.execution_state()
+                .precompile_base_gas_cost(hardfork)
+                .expr(),
+        );
+
+        // The output of the pairing check is not constrained yet, it is only
+        // required to be the 32 bytes returned by a successful call.
+        let restore_context = RestoreContextGadget::construct2(
+            cb,
+            is_success.expr(),
+            gas_cost.expr(),
+            0.expr(),
+            0x00.expr(),                   // ReturnDataOffset
+            is_success.expr() * 32.expr(), // ReturnDataLength
+            0.expr(),
+            0.expr(),
+        );
+
+        Self {
+            input_len,
+            is_success,
+            callee_address,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _chunk: &Chunk<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.input_len
+            .assign(region, offset, call.call_data_length)?;
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(u64::from(call.is_success))),
+        )?;
+        self.callee_address.assign(
+            region,
+            offset,
+            Value::known(call.code_address().unwrap().to_scalar().unwrap()),
+        )?;
+        self.caller_id.assign(
+            region,
+            offset,
+            Value::known(F::from(call.caller_id.try_into().unwrap())),
+        )?;
+        self.call_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_offset)),
+        )?;
+        self.call_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.call_data_length)),
+        )?;
+        self.return_data_offset.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_offset)),
+        )?;
+        self.return_data_length.assign(
+            region,
+            offset,
+            Value::known(F::from(call.return_data_length)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, 7)?;
+
+        Ok(())
+    }
+}
+
+/// Gadget to split the input length of the ecPairing precompile into a number
+/// of point pairs and a remainder, which is zero for a well-formed input.
+#[derive(Clone, Debug)]
+struct EcPairingInputLenGadget<F> {
+    n_pairs: Cell<F>,
+    // remainder = remainder_hi * 64 + remainder_lo with remainder_hi < 3, which
+    // bounds the remainder by 192 without a dedicated range table.
+    remainder_hi: Cell<F>,
+    remainder_lo: Cell<F>,
+    n_pairs_range_check: RangeCheckGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    remainder_is_zero: IsZeroGadget<F>,
+}
+
+impl<F: Field> EcPairingInputLenGadget<F> {
+    fn construct(cb: &mut EVMConstraintBuilder<F>, input_len: Expression<F>) -> Self {
+        let n_pairs = cb.query_cell();
+        let remainder_hi = cb.query_cell();
+        let remainder_lo = cb.query_cell();
+
+        cb.require_in_set(
+            "remainder_hi < 3",
+            remainder_hi.expr(),
+            vec![0.expr(), 1.expr(), 2.expr()],
+        );
+        cb.range_lookup(remainder_lo.expr(), 64);
+        let n_pairs_range_check = RangeCheckGadget::construct(cb, n_pairs.expr());
+
+        let remainder = remainder_hi.expr() * 64.expr() + remainder_lo.expr();
+        cb.require_equal(
+            "input_len == n_pairs * 192 + remainder",
+            input_len,
+            n_pairs.expr() * N_BYTES_EC_PAIR.expr() + remainder.clone(),
+        );
+        let remainder_is_zero = IsZeroGadget::construct(cb, remainder);
+
+        Self {
+            n_pairs,
+            remainder_hi,
+            remainder_lo,
+            n_pairs_range_check,
+            remainder_is_zero,
+        }
+    }
+
+    /// Number of point pairs in the input
+    fn n_pairs(&self) -> Expression<F> {
+        self.n_pairs.expr()
+    }
+
+    /// Whether the input length is a multiple of 192 bytes
+    fn is_valid(&self) -> Expression<F> {
+        self.remainder_is_zero.expr()
+    }
+
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input_len: u64,
+    ) -> Result<(), Error> {
+        let n_pairs = input_len / N_BYTES_EC_PAIR as u64;
+        let remainder = input_len % N_BYTES_EC_PAIR as u64;
+        self.n_pairs
+            .assign(region, offset, Value::known(F::from(n_pairs)))?;
+        self.remainder_hi
+            .assign(region, offset, Value::known(F::from(remainder / 64)))?;
+        self.remainder_lo
+            .assign(region, offset, Value::known(F::from(remainder % 64)))?;
+        self.n_pairs_range_check
+            .assign(region, offset, F::from(n_pairs))?;
+        self.remainder_is_zero
+            .assign(region, offset, F::from(remainder))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        evm_circuit::step::{ExecutionState, HasExecutionState},
+        test_util::CircuitTestBuilder,
+    };
+    use bus_mapping::{
+        evm::OpcodeId,
+        precompile::{PrecompileCallArgs, PrecompileCalls, N_BYTES_EC_PAIR},
+    };
+    use eth_types::ToWord;
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    fn ec_pairing_call(call_data_length: usize, gas: u64) -> PrecompileCallArgs {
+        PrecompileCallArgs {
+            name: "ecPairing",
+            // zero bytes encode points at infinity, which pass the pairing check
+            call_data_offset: 0x00.into(),
+            call_data_length: call_data_length.into(),
+            ret_offset: 0x00.into(),
+            ret_size: 0x20.into(),
+            gas: gas.into(),
+            address: PrecompileCalls::Bn128Pairing.address().to_word(),
+            ..Default::default()
+        }
+    }
+
+    /// Runs the call with each call opcode, checking the execution state of
+    /// the step following the call
+    fn run_ec_pairing_call(call: PrecompileCallArgs, expected: ExecutionState) {
+        let call_kinds = [
+            OpcodeId::CALL,
+            OpcodeId::STATICCALL,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::CALLCODE,
+        ];
+        for call_kind in call_kinds {
+            let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(call.with_call_op(call_kind))
+                .unwrap();
+
+            let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx)
+                .build_block(None)
+                .unwrap();
+            let (_, precompile_step) = block.txs[0]
+                .steps()
+                .iter()
+                .tuple_windows()
+                .find(|(step, _)| step.opcode() == Some(call_kind))
+                .unwrap();
+            assert_eq!(precompile_step.execution_state(), expected, "{call_kind:?}");
+
+            CircuitTestBuilder::new_from_block(block, chunks).run();
+        }
+    }
+
+    #[test]
+    fn ec_pairing_input_len_multiple_of_192() {
+        for n_pairs in [0, 1, 2] {
+            run_ec_pairing_call(
+                ec_pairing_call(n_pairs * N_BYTES_EC_PAIR, 0x30000),
+                ExecutionState::PrecompileBn256Pairing,
+            );
+        }
+    }
+
+    #[test]
+    fn ec_pairing_input_len_not_multiple_of_192() {
+        run_ec_pairing_call(
+            ec_pairing_call(100, 0x30000),
+            ExecutionState::PrecompileBn256Pairing,
+        );
+    }
+
+    #[test]
+    fn ec_pairing_input_len_not_multiple_of_192_oog() {
+        run_ec_pairing_call(
+            ec_pairing_call(100, 0x100),
+            ExecutionState::ErrorOutOfGasPrecompile,
+        );
+    }
+}
@@ -160,7 +160,9 @@ impl<F: Field> ExecutionGadget<F> for EcrecoverGadget<F> {
         cb.precompile_info_lookup(
             cb.execution_state().as_u64().expr(),
             callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
+            cb.execution_state()
+                .precompile_base_gas_cost(cb.feature_config.hardfork)
+                .expr(),
         );
 
         let restore_context = RestoreContextGadget::construct2(
@@ -64,7 +64,9 @@ impl<F: Field> ExecutionGadget<F> for IdentityGadget<F> {
         cb.precompile_info_lookup(
             cb.execution_state().as_u64().expr(),
             callee_address.expr(),
-            cb.execution_state().precompile_base_gas_cost().expr(),
+            cb.execution_state()
+                .precompile_base_gas_cost(cb.feature_config.hardfork)
+                .expr(),
         );
 
         // In the case of Identity precompile, the only failure is in the case of insufficient gas
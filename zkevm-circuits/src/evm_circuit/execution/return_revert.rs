@@ -26,7 +26,7 @@ use crate::{
 use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
 use eth_types::{
     evm_types::{GasCost, INVALID_INIT_CODE_FIRST_BYTE},
-    Field, ToScalar, U256,
+    Field, ToScalar,
 };
 use halo2_proofs::{circuit::Value, plonk::Error};
 
@@ -67,6 +67,7 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
         cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.responsible_opcode_lookup(opcode.expr());
 
         let offset = cb.query_word_unchecked();
         let length = cb.query_memory_address();
@@ -333,10 +334,8 @@ impl<F: Field> ExecutionGadget<F> for ReturnRevertGadget<F> {
                 offset,
                 region.keccak_rlc(&values.iter().rev().cloned().collect::<Vec<u8>>()),
             )?;
-            let mut code_hash = CodeDB::hash(&values).to_fixed_bytes();
-            code_hash.reverse();
             self.code_hash
-                .assign_u256(region, offset, U256::from_little_endian(&code_hash))?;
+                .assign_be(region, offset, CodeDB::hash(&values).as_bytes())?;
         }
 
         let copy_rw_increase = if call.is_create() && call.is_success {
@@ -99,7 +99,8 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         let p_lo = cb.query_cell();
         let p_hi = cb.query_cell();
         let p_top = cb.query_cell();
-        let is_neg = cb.is_lt(127.expr(), a.limbs[31].expr());
+        let is_neg =
+            LtGadget::construct_bounded::<{ u8::MAX as u64 }>(cb, 127.expr(), a.limbs[31].expr());
         let shf_lt256 = cb.is_zero(sum::expr(&shift.limbs[1..32]));
 
         for idx in 0..4 {
@@ -179,9 +180,11 @@ impl<F: Field> ExecutionGadget<F> for SarGadget<F> {
         );
 
         // Shift constraint
-        let shf_div64_lt_4 = cb.is_lt(shf_div64.expr(), 4.expr());
+        let shf_div64_lt_4 =
+            LtGadget::construct_bounded::<{ u8::MAX as u64 }>(cb, shf_div64.expr(), 4.expr());
         cb.require_equal("shf_div64 < 4", shf_div64_lt_4.expr(), 1.expr());
-        let shf_mod64_lt_64 = cb.is_lt(shf_mod64.expr(), 64.expr());
+        let shf_mod64_lt_64 =
+            LtGadget::construct_bounded::<{ u8::MAX as u64 }>(cb, shf_mod64.expr(), 64.expr());
         cb.require_equal("shf_mod64 < 64", shf_mod64_lt_64.expr(), 1.expr());
         cb.require_equal(
             "shift[0] == shf_mod64 + shf_div64 * 64",
@@ -108,8 +108,11 @@ impl<F: Field> ExecutionGadget<F> for SignedDivModGadget<F> {
         // `(1 << 255) - 1`. So constraint
         // `sign(dividend) == sign(divisor) ^ sign(quotient)` cannot be applied
         // for this case.
-        let dividend_is_signed_overflow =
-            cb.is_lt(127.expr(), dividend_abs.x_abs().limbs[31].expr());
+        let dividend_is_signed_overflow = LtGadget::construct_bounded::<{ u8::MAX as u64 }>(
+            cb,
+            127.expr(),
+            dividend_abs.x_abs().limbs[31].expr(),
+        );
 
         // Constrain sign(dividend) == sign(divisor) ^ sign(quotient) when both
         // quotient and divisor are non-zero and dividend is not signed overflow.
@@ -205,6 +205,19 @@ mod tests {
         test_ok(Sha3CodeGen::mem_gt_size(0x404, 0x505));
     }
 
+    #[test]
+    fn sha3_gadget_multi_randomness() {
+        let (code, _) = Sha3CodeGen::mem_lt_size(0x10, 0x10).gen_sha3_code();
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .params(FixedCParams {
+            max_rws: 5500,
+            ..Default::default()
+        })
+        .run_multi_randomness(&[0x100, 0x12345]);
+    }
+
     #[test]
     fn sha3_gadget_overflow_offset_and_zero_size() {
         let bytecode = bytecode! {
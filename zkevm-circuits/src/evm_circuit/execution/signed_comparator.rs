@@ -59,8 +59,10 @@ impl<F: Field> ExecutionGadget<F> for SignedComparatorGadget<F> {
         // number is negative if the most significant cell >= 128
         // (0b10000000). a and b being in the little-endian notation, the
         // most-significant byte is the last byte.
-        let sign_check_a = cb.is_lt(a.limbs[31].expr(), 128.expr());
-        let sign_check_b = cb.is_lt(b.limbs[31].expr(), 128.expr());
+        let sign_check_a =
+            LtGadget::construct_bounded::<{ u8::MAX as u64 }>(cb, a.limbs[31].expr(), 128.expr());
+        let sign_check_b =
+            LtGadget::construct_bounded::<{ u8::MAX as u64 }>(cb, b.limbs[31].expr(), 128.expr());
 
         // sign_check_a_lt expression implies a is positive since its MSB < 2**7
         // sign_check_b_lt expression implies b is positive since its MSB < 2**7
@@ -21,6 +21,7 @@ use crate::{
     },
 };
 
+use bus_mapping::circuit_input_builder::Hardfork;
 use eth_types::{evm_types::GasCost, Field};
 use halo2_proofs::{
     circuit::Value,
@@ -224,6 +225,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
         self.tx_refund.assign(
             region,
             offset,
+            block.feature_config.hardfork,
             tx_refund,
             tx_refund_prev,
             value,
@@ -255,6 +257,7 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         value_prev: T,
         original_value: T,
     ) -> Self {
+        let hardfork = cb.feature_config.hardfork;
         let value_prev_is_zero_gadget = cb.is_zero_word(&value_prev.to_word());
         let value_is_zero_gadget = cb.is_zero_word(&value.to_word());
         let original_is_zero_gadget = cb.is_zero_word(&original_value.to_word());
@@ -291,10 +294,10 @@ impl<F: Field> SstoreTxRefundGadget<F> {
             not::expr(prev_eq_value) * not::expr(original_eq_prev) * (value_prev_is_zero);
 
         let tx_refund_new = tx_refund_old.expr()
-            + delete_slot * GasCost::SSTORE_CLEARS_SCHEDULE.expr()
-            + reset_existing * (GasCost::SSTORE_RESET.expr() - GasCost::WARM_ACCESS.expr())
-            + reset_inexistent * (GasCost::SSTORE_SET.expr() - GasCost::WARM_ACCESS.expr())
-            - recreate_slot * (GasCost::SSTORE_CLEARS_SCHEDULE.expr());
+            + delete_slot * hardfork.sstore_clears_schedule().expr()
+            + reset_existing * (hardfork.sstore_reset() - hardfork.sload_gas()).expr()
+            + reset_inexistent * (GasCost::SSTORE_SET - hardfork.sload_gas()).expr()
+            - recreate_slot * hardfork.sstore_clears_schedule().expr();
 
         Self {
             tx_refund_old,
@@ -318,6 +321,7 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
+        hardfork: Hardfork,
         tx_refund: u64,
         tx_refund_old: u64,
         value: eth_types::Word,
@@ -351,72 +355,22 @@ impl<F: Field> SstoreTxRefundGadget<F> {
             WordLoHi::from(value_prev),
         )?;
         debug_assert_eq!(
-            calc_expected_tx_refund(tx_refund_old, value, value_prev, original_value),
+            hardfork.tx_refund(tx_refund_old, value, value_prev, original_value),
             tx_refund
         );
         Ok(())
     }
 }
 
-fn calc_expected_tx_refund(
-    tx_refund_old: u64,
-    value: eth_types::Word,
-    value_prev: eth_types::Word,
-    original_value: eth_types::Word,
-) -> u64 {
-    // Same clause tags(like "delete slot (2.1.2b)") used as [`makeGasSStoreFunc` in go-ethereum](https://github.com/ethereum/go-ethereum/blob/9fd8825d5a196edde6d8ef81382979875145b346/core/vm/operations_acl.go#L27)
-    // Control flow of this function try to follow `makeGasSStoreFunc` for better
-    // understanding and comparison.
-
-    let mut tx_refund_new = tx_refund_old;
-
-    // The "clearing slot refund" and "resetting value refund" are ADDED together,
-    // they are NOT MUTUALLY EXCLUSIVE.
-    // Search "Apply both of the following clauses" in EIP-2200 for more details.
-    // There can be five total kinds of refund:
-    // 1. -SSTORE_CLEARS_SCHEDULE
-    // 2. SSTORE_CLEARS_SCHEDULE
-    // 3. SSTORE_SET - WARM_ACCESS
-    // 4. SSTORE_RESET - WARM_ACCESS
-    // 5. -SSTORE_CLEARS_SCHEDULE + SSTORE_RESET - WARM_ACCESS
-    // The last case can happen if (original_value, prev_value, value) be (v,0,v)
-    // where v != 0,
-    // then both "clearing slot refund" and "resetting value refund" are non zero.
-
-    if value_prev != value {
-        // refund related to clearing slot
-        // "delete slot (2.1.2b)" can be safely merged in "delete slot (2.2.1.2)"
-        if !original_value.is_zero() {
-            if value_prev.is_zero() {
-                // recreate slot (2.2.1.1)
-                tx_refund_new -= GasCost::SSTORE_CLEARS_SCHEDULE
-            }
-            if value.is_zero() {
-                // delete slot (2.2.1.2)
-                tx_refund_new += GasCost::SSTORE_CLEARS_SCHEDULE
-            }
-        }
-
-        // refund related to resetting value
-        if original_value == value {
-            if original_value.is_zero() {
-                // reset to original inexistent slot (2.2.2.1)
-                tx_refund_new += GasCost::SSTORE_SET - GasCost::WARM_ACCESS;
-            } else {
-                // reset to original existing slot (2.2.2.2)
-                tx_refund_new += GasCost::SSTORE_RESET - GasCost::WARM_ACCESS;
-            }
-        }
-    }
-
-    tx_refund_new
-}
-
 #[cfg(test)]
 mod test {
 
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
+    use bus_mapping::{
+        circuit_input_builder::{FeatureConfig, Hardfork},
+        operation::Target,
+    };
+    use eth_types::{bytecode, evm_types::GasCost, Word};
     use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     #[test]
@@ -480,6 +434,67 @@ mod test {
         );
     }
 
+    #[test]
+    fn sstore_gadget_refund_per_hardfork() {
+        // Deleting an existing cold slot costs 5000 gas under every hardfork, so the same trace
+        // proves under all of them. It is refunded SSTORE_CLEARS_SCHEDULE, which EIP-3529
+        // lowered.
+        let key = Word::from(0x030201);
+        let refund = |hardfork| {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(Word::from(10u64.pow(19)))
+                        .code(bytecode! {
+                            PUSH32(0)
+                            PUSH32(key)
+                            SSTORE
+                            STOP
+                        })
+                        .storage(vec![(key, Word::from(0x060504))].into_iter());
+                    accs[1]
+                        .address(MOCK_ACCOUNTS[1])
+                        .balance(Word::from(10u64.pow(19)));
+                },
+                tx_from_1_to_0,
+                |block, _txs| block,
+            )
+            .unwrap();
+            let builder = CircuitTestBuilder::new_from_test_ctx(ctx).feature(FeatureConfig {
+                hardfork,
+                ..Default::default()
+            });
+            let (block, chunks) = builder.build_block(None).unwrap();
+            CircuitTestBuilder::new_from_block(block.clone(), chunks).run();
+
+            // The TxRefund rows are the SSTORE write and the EndTx read.
+            let tx_refund = block.rws.0[&Target::TxRefund]
+                .iter()
+                .map(|rw| rw.tx_refund_value_pair().0)
+                .max()
+                .unwrap();
+            let caller_balance = block.rws.final_accounts()[&MOCK_ACCOUNTS[1]].balance;
+            (tx_refund, caller_balance, block.txs[0].tx.gas_price)
+        };
+
+        let (istanbul, istanbul_balance, gas_price) = refund(Hardfork::Istanbul);
+        let (berlin, berlin_balance, _) = refund(Hardfork::Berlin);
+        let (london, london_balance, _) = refund(Hardfork::London);
+        assert_eq!(istanbul, GasCost::SSTORE_CLEARS_SCHEDULE_EIP2200);
+        assert_eq!(berlin, GasCost::SSTORE_CLEARS_SCHEDULE_EIP2200);
+        assert_eq!(london, GasCost::SSTORE_CLEARS_SCHEDULE);
+
+        // The tx uses 21000 + 2 * 3 + 5000 = 26006 gas. EndTx caps the 15000 EIP-2200 refund at
+        // half of it, 13003, while the 4800 EIP-3529 refund is below a fifth of it, 5201.
+        assert_eq!(istanbul_balance, berlin_balance);
+        assert_eq!(
+            berlin_balance - london_balance,
+            gas_price * Word::from(13003 - 4800)
+        );
+    }
+
     fn test_ok(key: Word, value: Word, value_prev: Word, original_value: Word) {
         // Here we use two bytecodes to test both is_persistent(STOP) or not(REVERT)
         // Besides, in bytecode we use two SSTOREs,
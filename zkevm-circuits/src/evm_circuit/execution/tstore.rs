@@ -135,9 +135,10 @@ impl<F: Field> ExecutionGadget<F> for TstoreGadget<F> {
 #[cfg(test)]
 mod test {
 
-    use crate::test_util::CircuitTestBuilder;
-    use eth_types::{bytecode, Word};
-    use mock::{test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
+    use crate::{test_util::CircuitTestBuilder, witness::Rw};
+    use bus_mapping::operation::Target;
+    use eth_types::{bytecode, ToWord, Word};
+    use mock::{eth, test_ctx::helpers::tx_from_1_to_0, TestContext, MOCK_ACCOUNTS};
 
     #[test]
     fn tstore_gadget_no_refund() {
@@ -210,4 +211,84 @@ mod test {
 
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
+
+    /// The caller delegates to a callee which runs TSTORE in the storage
+    /// context of the caller and then reverts or stops. The caller then reads
+    /// the slot back with TLOAD, whose result is returned.
+    fn test_delegatecall_boundary(callee_reverts: bool) -> Word {
+        let key = Word::from(34);
+        let value = Word::from(100);
+
+        let mut code_b = bytecode! {
+            PUSH32(value)
+            PUSH32(key)
+            TSTORE
+        };
+        if callee_reverts {
+            code_b.op_revert(0, 0);
+        } else {
+            code_b.op_stop();
+        }
+        let code_a = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH20(MOCK_ACCOUNTS[1].to_word()) // addr
+            PUSH2(0xffff) // gas
+            DELEGATECALL
+            POP
+            PUSH32(key)
+            TLOAD
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code_a);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_b);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        let (block, chunks) = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .build_block(None)
+            .unwrap();
+        CircuitTestBuilder::new_from_block(block.clone(), chunks).run();
+
+        // The TLOAD of the caller is the last read of the transient storage
+        block.rws.0[&Target::TransientStorage]
+            .iter()
+            .filter(|rw| !rw.is_write())
+            .max_by_key(|rw| rw.rw_counter())
+            .map(|rw| match rw {
+                Rw::AccountTransientStorage {
+                    account_address,
+                    storage_key,
+                    value,
+                    ..
+                } => {
+                    assert_eq!(*account_address, MOCK_ACCOUNTS[0]);
+                    assert_eq!(*storage_key, key);
+                    *value
+                }
+                _ => unreachable!(),
+            })
+            .expect("TLOAD reads the transient storage")
+    }
+
+    #[test]
+    fn tstore_reverted_in_failed_call() {
+        assert_eq!(test_delegatecall_boundary(true), Word::zero());
+    }
+
+    #[test]
+    fn tstore_visible_after_committed_call() {
+        assert_eq!(test_delegatecall_boundary(false), Word::from(100));
+    }
 }
@@ -1,6 +1,6 @@
 //! Constants and parameters for the EVM circuit
 use super::table::Table;
-use crate::evm_circuit::{step::ExecutionState, EvmCircuit};
+use crate::evm_circuit::{step::ExecutionState, EvmCircuit, EvmCircuitParams};
 use bus_mapping::circuit_input_builder::FeatureConfig;
 use halo2_proofs::{
     halo2curves::bn256::Fr,
@@ -111,6 +111,10 @@ pub(crate) const N_BYTES_ACCOUNT_ADDRESS: usize = 20;
 pub(crate) const N_BYTES_MEMORY_ADDRESS: usize = 5;
 pub(crate) const N_BYTES_MEMORY_WORD_SIZE: usize = 4;
 
+// Number of bytes of a (G1, G2) point pair in the input of the ecPairing
+// precompile.
+pub(crate) const N_BYTES_EC_PAIR: usize = bus_mapping::precompile::N_BYTES_EC_PAIR;
+
 pub(crate) const STACK_CAPACITY: usize = 1024;
 
 // Number of bytes that will be used of prorgam counter. Although the maximum
@@ -190,7 +194,13 @@ lazy_static::lazy_static! {
 }
 fn get_step_height_map(feature_config: FeatureConfig) -> HashMap<ExecutionState, usize> {
     let mut meta = ConstraintSystem::<Fr>::default();
-    let circuit = EvmCircuit::configure_with_params(&mut meta, feature_config);
+    let circuit = EvmCircuit::configure_with_params(
+        &mut meta,
+        EvmCircuitParams {
+            feature_config,
+            ..Default::default()
+        },
+    );
     circuit.0.execution.height_map
 }
 #[cfg(test)]
@@ -210,4 +220,14 @@ mod tests {
         // We show that the invalid tx feature affects none of the other execution state heights
         assert_eq!(map_invalid_tx, map_mainnet);
     }
+
+    #[test]
+    fn end_block_step_height() {
+        // EndBlock is assigned to a single row, repeated until the last usable row.
+        assert_eq!(ExecutionState::EndBlock.step_height(), 1);
+        assert_eq!(
+            ExecutionState::step_heights().get(&ExecutionState::EndBlock),
+            Some(&1)
+        );
+    }
 }
@@ -19,7 +19,7 @@ use crate::{
     },
 };
 use bus_mapping::{
-    circuit_input_builder::ExecState,
+    circuit_input_builder::{ExecState, Hardfork},
     error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
     evm::OpcodeId,
     precompile::PrecompileCalls,
@@ -29,9 +29,9 @@ use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
 };
-use std::{fmt::Display, iter, marker::ConstParamTy};
+use std::{collections::HashMap, fmt::Display, iter, marker::ConstParamTy};
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use strum_macros::{EnumIter, IntoStaticStr};
 
 impl From<PrecompileCalls> for ExecutionState {
     fn from(value: PrecompileCalls) -> Self {
@@ -50,7 +50,7 @@ impl From<PrecompileCalls> for ExecutionState {
 }
 
 #[allow(non_camel_case_types, missing_docs)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, ConstParamTy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, IntoStaticStr, ConstParamTy)]
 /// All the possible execution states that the computation of EVM can arrive.
 /// Some states are shared by multiple opcodes.
 pub enum ExecutionState {
@@ -182,7 +182,7 @@ impl Default for ExecutionState {
 
 impl Display for ExecutionState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        f.write_str(self.as_str())
     }
 }
 impl From<&ExecError> for ExecutionState {
@@ -245,96 +245,11 @@ impl From<&ExecStep> for ExecutionState {
         }
         match step.exec_state {
             ExecState::Op(op) => {
-                if op.is_dup() {
-                    return ExecutionState::DUP;
-                }
-                if op.is_push() {
-                    return ExecutionState::PUSH;
-                }
-                if op.is_swap() {
-                    return ExecutionState::SWAP;
-                }
-                if op.is_log() {
-                    return ExecutionState::LOG;
-                }
-
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
-                match op {
-                    OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
-                    OpcodeId::ADDMOD => ExecutionState::ADDMOD,
-                    OpcodeId::ADDRESS => ExecutionState::ADDRESS,
-                    OpcodeId::BALANCE => ExecutionState::BALANCE,
-                    OpcodeId::MUL | OpcodeId::DIV | OpcodeId::MOD => ExecutionState::MUL_DIV_MOD,
-                    OpcodeId::MULMOD => ExecutionState::MULMOD,
-                    OpcodeId::SDIV | OpcodeId::SMOD => ExecutionState::SDIV_SMOD,
-                    OpcodeId::EQ | OpcodeId::LT | OpcodeId::GT => ExecutionState::CMP,
-                    OpcodeId::SLT | OpcodeId::SGT => ExecutionState::SCMP,
-                    OpcodeId::SIGNEXTEND => ExecutionState::SIGNEXTEND,
-                    OpcodeId::STOP => ExecutionState::STOP,
-                    OpcodeId::AND => ExecutionState::BITWISE,
-                    OpcodeId::XOR => ExecutionState::BITWISE,
-                    OpcodeId::OR => ExecutionState::BITWISE,
-                    OpcodeId::NOT => ExecutionState::NOT,
-                    OpcodeId::EXP => ExecutionState::EXP,
-                    OpcodeId::POP => ExecutionState::POP,
-                    OpcodeId::BYTE => ExecutionState::BYTE,
-                    OpcodeId::MLOAD => ExecutionState::MEMORY,
-                    OpcodeId::MSTORE => ExecutionState::MEMORY,
-                    OpcodeId::MSTORE8 => ExecutionState::MEMORY,
-                    OpcodeId::JUMPDEST => ExecutionState::JUMPDEST,
-                    OpcodeId::JUMP => ExecutionState::JUMP,
-                    OpcodeId::JUMPI => ExecutionState::JUMPI,
-                    OpcodeId::GASPRICE => ExecutionState::GASPRICE,
-                    OpcodeId::PC => ExecutionState::PC,
-                    OpcodeId::MSIZE => ExecutionState::MSIZE,
-                    OpcodeId::CALLER => ExecutionState::CALLER,
-                    OpcodeId::CALLVALUE => ExecutionState::CALLVALUE,
-                    OpcodeId::EXTCODEHASH => ExecutionState::EXTCODEHASH,
-                    OpcodeId::EXTCODESIZE => ExecutionState::EXTCODESIZE,
-                    OpcodeId::BLOCKHASH => ExecutionState::BLOCKHASH,
-                    OpcodeId::TIMESTAMP
-                    | OpcodeId::NUMBER
-                    | OpcodeId::GASLIMIT
-                    | OpcodeId::COINBASE
-                    | OpcodeId::DIFFICULTY
-                    | OpcodeId::BASEFEE => ExecutionState::BLOCKCTX,
-                    OpcodeId::GAS => ExecutionState::GAS,
-                    OpcodeId::SAR => ExecutionState::SAR,
-                    OpcodeId::SELFBALANCE => ExecutionState::SELFBALANCE,
-                    OpcodeId::SHA3 => ExecutionState::SHA3,
-                    OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
-                    OpcodeId::SLOAD => ExecutionState::SLOAD,
-                    OpcodeId::SSTORE => ExecutionState::SSTORE,
-                    OpcodeId::TLOAD => ExecutionState::TLOAD,
-                    OpcodeId::TSTORE => ExecutionState::TSTORE,
-                    OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
-                    OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
-                    OpcodeId::CHAINID => ExecutionState::CHAINID,
-                    OpcodeId::ISZERO => ExecutionState::ISZERO,
-                    OpcodeId::CALL
-                    | OpcodeId::CALLCODE
-                    | OpcodeId::DELEGATECALL
-                    | OpcodeId::STATICCALL => ExecutionState::CALL_OP,
-                    OpcodeId::ORIGIN => ExecutionState::ORIGIN,
-                    OpcodeId::CODECOPY => ExecutionState::CODECOPY,
-                    OpcodeId::CALLDATALOAD => ExecutionState::CALLDATALOAD,
-                    OpcodeId::CODESIZE => ExecutionState::CODESIZE,
-                    OpcodeId::EXTCODECOPY => ExecutionState::EXTCODECOPY,
-                    OpcodeId::RETURN | OpcodeId::REVERT => ExecutionState::RETURN_REVERT,
-                    OpcodeId::RETURNDATASIZE => ExecutionState::RETURNDATASIZE,
-                    OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
-                    OpcodeId::CREATE => ExecutionState::CREATE,
-                    OpcodeId::CREATE2 => ExecutionState::CREATE2,
-                    // dummy ops
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
-                    _ => unimplemented!("unimplemented opcode {:?}", op),
+                if op == OpcodeId::SELFDESTRUCT {
+                    evm_unimplemented!("{:?} is implemented with DummyGadget", op);
                 }
+                ExecutionState::from_opcode(op)
+                    .unwrap_or_else(|| unimplemented!("unimplemented opcode {:?}", op))
             }
             ExecState::Precompile(precompile) => match precompile {
                 PrecompileCalls::Ecrecover => ExecutionState::PrecompileEcrecover,
@@ -377,6 +292,108 @@ impl ExecutionState {
         Self::iter().count()
     }
 
+    /// Returns the execution state responsible for a successful execution of
+    /// `opcode`, or `None` if the opcode isn't implemented.
+    pub(crate) fn from_opcode(opcode: OpcodeId) -> Option<Self> {
+        if opcode.is_dup() {
+            return Some(ExecutionState::DUP);
+        }
+        if opcode.is_push() {
+            return Some(ExecutionState::PUSH);
+        }
+        if opcode.is_swap() {
+            return Some(ExecutionState::SWAP);
+        }
+        if opcode.is_log() {
+            return Some(ExecutionState::LOG);
+        }
+
+        Some(match opcode {
+            OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
+            OpcodeId::ADDMOD => ExecutionState::ADDMOD,
+            OpcodeId::ADDRESS => ExecutionState::ADDRESS,
+            OpcodeId::BALANCE => ExecutionState::BALANCE,
+            OpcodeId::MUL | OpcodeId::DIV | OpcodeId::MOD => ExecutionState::MUL_DIV_MOD,
+            OpcodeId::MULMOD => ExecutionState::MULMOD,
+            OpcodeId::SDIV | OpcodeId::SMOD => ExecutionState::SDIV_SMOD,
+            OpcodeId::EQ | OpcodeId::LT | OpcodeId::GT => ExecutionState::CMP,
+            OpcodeId::SLT | OpcodeId::SGT => ExecutionState::SCMP,
+            OpcodeId::SIGNEXTEND => ExecutionState::SIGNEXTEND,
+            OpcodeId::STOP => ExecutionState::STOP,
+            OpcodeId::AND => ExecutionState::BITWISE,
+            OpcodeId::XOR => ExecutionState::BITWISE,
+            OpcodeId::OR => ExecutionState::BITWISE,
+            OpcodeId::NOT => ExecutionState::NOT,
+            OpcodeId::EXP => ExecutionState::EXP,
+            OpcodeId::POP => ExecutionState::POP,
+            OpcodeId::BYTE => ExecutionState::BYTE,
+            OpcodeId::MLOAD => ExecutionState::MEMORY,
+            OpcodeId::MSTORE => ExecutionState::MEMORY,
+            OpcodeId::MSTORE8 => ExecutionState::MEMORY,
+            OpcodeId::JUMPDEST => ExecutionState::JUMPDEST,
+            OpcodeId::JUMP => ExecutionState::JUMP,
+            OpcodeId::JUMPI => ExecutionState::JUMPI,
+            OpcodeId::GASPRICE => ExecutionState::GASPRICE,
+            OpcodeId::PC => ExecutionState::PC,
+            OpcodeId::MSIZE => ExecutionState::MSIZE,
+            OpcodeId::CALLER => ExecutionState::CALLER,
+            OpcodeId::CALLVALUE => ExecutionState::CALLVALUE,
+            OpcodeId::EXTCODEHASH => ExecutionState::EXTCODEHASH,
+            OpcodeId::EXTCODESIZE => ExecutionState::EXTCODESIZE,
+            OpcodeId::BLOCKHASH => ExecutionState::BLOCKHASH,
+            OpcodeId::TIMESTAMP
+            | OpcodeId::NUMBER
+            | OpcodeId::GASLIMIT
+            | OpcodeId::COINBASE
+            | OpcodeId::DIFFICULTY
+            | OpcodeId::BASEFEE => ExecutionState::BLOCKCTX,
+            OpcodeId::GAS => ExecutionState::GAS,
+            OpcodeId::SAR => ExecutionState::SAR,
+            OpcodeId::SELFBALANCE => ExecutionState::SELFBALANCE,
+            OpcodeId::SHA3 => ExecutionState::SHA3,
+            OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
+            OpcodeId::SLOAD => ExecutionState::SLOAD,
+            OpcodeId::SSTORE => ExecutionState::SSTORE,
+            OpcodeId::TLOAD => ExecutionState::TLOAD,
+            OpcodeId::TSTORE => ExecutionState::TSTORE,
+            OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
+            OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
+            OpcodeId::CHAINID => ExecutionState::CHAINID,
+            OpcodeId::ISZERO => ExecutionState::ISZERO,
+            OpcodeId::CALL | OpcodeId::CALLCODE | OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => {
+                ExecutionState::CALL_OP
+            }
+            OpcodeId::ORIGIN => ExecutionState::ORIGIN,
+            OpcodeId::CODECOPY => ExecutionState::CODECOPY,
+            OpcodeId::CALLDATALOAD => ExecutionState::CALLDATALOAD,
+            OpcodeId::CODESIZE => ExecutionState::CODESIZE,
+            OpcodeId::EXTCODECOPY => ExecutionState::EXTCODECOPY,
+            OpcodeId::RETURN | OpcodeId::REVERT => ExecutionState::RETURN_REVERT,
+            OpcodeId::RETURNDATASIZE => ExecutionState::RETURNDATASIZE,
+            OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
+            OpcodeId::CREATE => ExecutionState::CREATE,
+            OpcodeId::CREATE2 => ExecutionState::CREATE2,
+            // dummy ops
+            OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
+            _ => return None,
+        })
+    }
+
+    /// Whether the state is one of the internal states, which every block
+    /// goes through and so can't be disabled in the circuit.
+    pub(crate) fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            Self::BeginTx
+                | Self::EndTx
+                | Self::EndBlock
+                | Self::Padding
+                | Self::BeginChunk
+                | Self::EndChunk
+                | Self::InvalidTx
+        )
+    }
+
     pub(crate) fn is_precompiled(&self) -> bool {
         matches!(
             self,
@@ -392,8 +409,8 @@ impl ExecutionState {
         )
     }
 
-    pub(crate) fn precompile_base_gas_cost(&self) -> u64 {
-        (match self {
+    pub(crate) fn precompile_base_gas_cost(&self, hardfork: Hardfork) -> u64 {
+        hardfork.base_gas_cost(match self {
             Self::PrecompileEcrecover => PrecompileCalls::Ecrecover,
             Self::PrecompileSha256 => PrecompileCalls::Sha256,
             Self::PrecompileRipemd160 => PrecompileCalls::Ripemd160,
@@ -405,7 +422,6 @@ impl ExecutionState {
             Self::PrecompileBlake2f => PrecompileCalls::Blake2F,
             _ => return 0,
         })
-        .base_gas_cost()
     }
 
     pub(crate) fn halts_in_exception(&self) -> bool {
@@ -442,53 +458,60 @@ impl ExecutionState {
 
     /// Get the opocdes that are related to the execution state
     pub fn responsible_opcodes(&self) -> Vec<ResponsibleOp> {
-        if matches!(self, Self::ErrorStack) {
-            return OpcodeId::valid_opcodes()
+        if matches!(self, Self::ErrorInvalidOpcode) {
+            return OpcodeId::invalid_opcodes()
                 .into_iter()
-                .flat_map(|op| {
-                    op.invalid_stack_ptrs()
-                        .into_iter()
-                        .map(move |stack_ptr| ResponsibleOp::InvalidStackPtr(op, stack_ptr))
-                })
+                .map(Into::into)
                 .collect();
         }
 
+        self.associated_opcodes()
+            .iter()
+            .copied()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// Get the opcodes whose successful execution is handled by the execution
+    /// state, e.g. ADD and SUB for [`ExecutionState::ADD_SUB`].  Internal,
+    /// error and precompile states have no associated opcode.
+    pub fn associated_opcodes(&self) -> &'static [OpcodeId] {
         match self {
-            Self::STOP => vec![OpcodeId::STOP],
-            Self::ADD_SUB => vec![OpcodeId::ADD, OpcodeId::SUB],
-            Self::MUL_DIV_MOD => vec![OpcodeId::MUL, OpcodeId::DIV, OpcodeId::MOD],
-            Self::SDIV_SMOD => vec![OpcodeId::SDIV, OpcodeId::SMOD],
-            Self::SHL_SHR => vec![OpcodeId::SHL, OpcodeId::SHR],
-            Self::ADDMOD => vec![OpcodeId::ADDMOD],
-            Self::MULMOD => vec![OpcodeId::MULMOD],
-            Self::EXP => vec![OpcodeId::EXP],
-            Self::SIGNEXTEND => vec![OpcodeId::SIGNEXTEND],
-            Self::CMP => vec![OpcodeId::LT, OpcodeId::GT, OpcodeId::EQ],
-            Self::SCMP => vec![OpcodeId::SLT, OpcodeId::SGT],
-            Self::ISZERO => vec![OpcodeId::ISZERO],
-            Self::BITWISE => vec![OpcodeId::AND, OpcodeId::OR, OpcodeId::XOR],
-            Self::NOT => vec![OpcodeId::NOT],
-            Self::BYTE => vec![OpcodeId::BYTE],
-            Self::SAR => vec![OpcodeId::SAR],
-            Self::SHA3 => vec![OpcodeId::SHA3],
-            Self::ADDRESS => vec![OpcodeId::ADDRESS],
-            Self::BALANCE => vec![OpcodeId::BALANCE],
-            Self::ORIGIN => vec![OpcodeId::ORIGIN],
-            Self::CALLER => vec![OpcodeId::CALLER],
-            Self::CALLVALUE => vec![OpcodeId::CALLVALUE],
-            Self::CALLDATALOAD => vec![OpcodeId::CALLDATALOAD],
-            Self::CALLDATASIZE => vec![OpcodeId::CALLDATASIZE],
-            Self::CALLDATACOPY => vec![OpcodeId::CALLDATACOPY],
-            Self::CODESIZE => vec![OpcodeId::CODESIZE],
-            Self::CODECOPY => vec![OpcodeId::CODECOPY],
-            Self::GASPRICE => vec![OpcodeId::GASPRICE],
-            Self::EXTCODESIZE => vec![OpcodeId::EXTCODESIZE],
-            Self::EXTCODECOPY => vec![OpcodeId::EXTCODECOPY],
-            Self::RETURNDATASIZE => vec![OpcodeId::RETURNDATASIZE],
-            Self::RETURNDATACOPY => vec![OpcodeId::RETURNDATACOPY],
-            Self::EXTCODEHASH => vec![OpcodeId::EXTCODEHASH],
-            Self::BLOCKHASH => vec![OpcodeId::BLOCKHASH],
-            Self::BLOCKCTX => vec![
+            Self::STOP => &[OpcodeId::STOP],
+            Self::ADD_SUB => &[OpcodeId::ADD, OpcodeId::SUB],
+            Self::MUL_DIV_MOD => &[OpcodeId::MUL, OpcodeId::DIV, OpcodeId::MOD],
+            Self::SDIV_SMOD => &[OpcodeId::SDIV, OpcodeId::SMOD],
+            Self::SHL_SHR => &[OpcodeId::SHL, OpcodeId::SHR],
+            Self::ADDMOD => &[OpcodeId::ADDMOD],
+            Self::MULMOD => &[OpcodeId::MULMOD],
+            Self::EXP => &[OpcodeId::EXP],
+            Self::SIGNEXTEND => &[OpcodeId::SIGNEXTEND],
+            Self::CMP => &[OpcodeId::LT, OpcodeId::GT, OpcodeId::EQ],
+            Self::SCMP => &[OpcodeId::SLT, OpcodeId::SGT],
+            Self::ISZERO => &[OpcodeId::ISZERO],
+            Self::BITWISE => &[OpcodeId::AND, OpcodeId::OR, OpcodeId::XOR],
+            Self::NOT => &[OpcodeId::NOT],
+            Self::BYTE => &[OpcodeId::BYTE],
+            Self::SAR => &[OpcodeId::SAR],
+            Self::SHA3 => &[OpcodeId::SHA3],
+            Self::ADDRESS => &[OpcodeId::ADDRESS],
+            Self::BALANCE => &[OpcodeId::BALANCE],
+            Self::ORIGIN => &[OpcodeId::ORIGIN],
+            Self::CALLER => &[OpcodeId::CALLER],
+            Self::CALLVALUE => &[OpcodeId::CALLVALUE],
+            Self::CALLDATALOAD => &[OpcodeId::CALLDATALOAD],
+            Self::CALLDATASIZE => &[OpcodeId::CALLDATASIZE],
+            Self::CALLDATACOPY => &[OpcodeId::CALLDATACOPY],
+            Self::CODESIZE => &[OpcodeId::CODESIZE],
+            Self::CODECOPY => &[OpcodeId::CODECOPY],
+            Self::GASPRICE => &[OpcodeId::GASPRICE],
+            Self::EXTCODESIZE => &[OpcodeId::EXTCODESIZE],
+            Self::EXTCODECOPY => &[OpcodeId::EXTCODECOPY],
+            Self::RETURNDATASIZE => &[OpcodeId::RETURNDATASIZE],
+            Self::RETURNDATACOPY => &[OpcodeId::RETURNDATACOPY],
+            Self::EXTCODEHASH => &[OpcodeId::EXTCODEHASH],
+            Self::BLOCKHASH => &[OpcodeId::BLOCKHASH],
+            Self::BLOCKCTX => &[
                 OpcodeId::TIMESTAMP,
                 OpcodeId::NUMBER,
                 OpcodeId::GASLIMIT,
@@ -496,23 +519,21 @@ impl ExecutionState {
                 OpcodeId::DIFFICULTY,
                 OpcodeId::BASEFEE,
             ],
-            Self::CHAINID => vec![OpcodeId::CHAINID],
-            Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
-            Self::POP => vec![OpcodeId::POP],
-            Self::MEMORY => {
-                vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
-            }
-            Self::SLOAD => vec![OpcodeId::SLOAD],
-            Self::SSTORE => vec![OpcodeId::SSTORE],
-            Self::JUMP => vec![OpcodeId::JUMP],
-            Self::JUMPI => vec![OpcodeId::JUMPI],
-            Self::PC => vec![OpcodeId::PC],
-            Self::MSIZE => vec![OpcodeId::MSIZE],
-            Self::GAS => vec![OpcodeId::GAS],
-            Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
-            Self::TLOAD => vec![OpcodeId::TLOAD],
-            Self::TSTORE => vec![OpcodeId::TSTORE],
-            Self::PUSH => vec![
+            Self::CHAINID => &[OpcodeId::CHAINID],
+            Self::SELFBALANCE => &[OpcodeId::SELFBALANCE],
+            Self::POP => &[OpcodeId::POP],
+            Self::MEMORY => &[OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8],
+            Self::SLOAD => &[OpcodeId::SLOAD],
+            Self::SSTORE => &[OpcodeId::SSTORE],
+            Self::JUMP => &[OpcodeId::JUMP],
+            Self::JUMPI => &[OpcodeId::JUMPI],
+            Self::PC => &[OpcodeId::PC],
+            Self::MSIZE => &[OpcodeId::MSIZE],
+            Self::GAS => &[OpcodeId::GAS],
+            Self::JUMPDEST => &[OpcodeId::JUMPDEST],
+            Self::TLOAD => &[OpcodeId::TLOAD],
+            Self::TSTORE => &[OpcodeId::TSTORE],
+            Self::PUSH => &[
                 OpcodeId::PUSH0,
                 OpcodeId::PUSH1,
                 OpcodeId::PUSH2,
@@ -547,7 +568,7 @@ impl ExecutionState {
                 OpcodeId::PUSH31,
                 OpcodeId::PUSH32,
             ],
-            Self::DUP => vec![
+            Self::DUP => &[
                 OpcodeId::DUP1,
                 OpcodeId::DUP2,
                 OpcodeId::DUP3,
@@ -565,7 +586,7 @@ impl ExecutionState {
                 OpcodeId::DUP15,
                 OpcodeId::DUP16,
             ],
-            Self::SWAP => vec![
+            Self::SWAP => &[
                 OpcodeId::SWAP1,
                 OpcodeId::SWAP2,
                 OpcodeId::SWAP3,
@@ -583,29 +604,30 @@ impl ExecutionState {
                 OpcodeId::SWAP15,
                 OpcodeId::SWAP16,
             ],
-            Self::LOG => vec![
+            Self::LOG => &[
                 OpcodeId::LOG0,
                 OpcodeId::LOG1,
                 OpcodeId::LOG2,
                 OpcodeId::LOG3,
                 OpcodeId::LOG4,
             ],
-            Self::CREATE => vec![OpcodeId::CREATE],
-            Self::CALL_OP => vec![
+            Self::CREATE => &[OpcodeId::CREATE],
+            Self::CALL_OP => &[
                 OpcodeId::CALL,
                 OpcodeId::CALLCODE,
                 OpcodeId::DELEGATECALL,
                 OpcodeId::STATICCALL,
             ],
-            Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
-            Self::CREATE2 => vec![OpcodeId::CREATE2],
-            Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
-            _ => vec![],
+            Self::RETURN_REVERT => &[OpcodeId::RETURN, OpcodeId::REVERT],
+            Self::CREATE2 => &[OpcodeId::CREATE2],
+            Self::SELFDESTRUCT => &[OpcodeId::SELFDESTRUCT],
+            _ => &[],
         }
-        .into_iter()
-        .map(Into::into)
-        .collect()
+    }
+
+    /// Get the name of the execution state, as spelled in the enum
+    pub fn as_str(&self) -> &'static str {
+        self.into()
     }
 
     /// Get the state height
@@ -613,10 +635,20 @@ impl ExecutionState {
         EXECUTION_STATE_HEIGHT_MAP.get(self).copied()
     }
 
-    pub(crate) fn get_step_height(&self) -> usize {
+    /// Height in rows of the step of this execution state in the EVM circuit.
+    /// Panics if the execution state has no gadget.
+    pub fn step_height(&self) -> usize {
         self.get_step_height_option()
             .unwrap_or_else(|| panic!("Execution state unknown: {:?}", self))
     }
+
+    /// Height in rows of the step of every execution state with a gadget in the
+    /// EVM circuit, as measured by the cell manager when the gadget is
+    /// configured.  Every row of a step spans `STEP_WIDTH` cells, so this is
+    /// also the cell usage of each gadget.
+    pub fn step_heights() -> HashMap<ExecutionState, usize> {
+        EXECUTION_STATE_HEIGHT_MAP.clone()
+    }
 }
 
 /// Enum of Responsible opcode mapping to execution state.
@@ -624,8 +656,6 @@ impl ExecutionState {
 pub enum ResponsibleOp {
     /// Raw opcode
     Op(OpcodeId),
-    /// Corresponding to ExecutionState::ErrorStack
-    InvalidStackPtr(OpcodeId, u32),
 }
 
 /// Helper for easy transform from a raw OpcodeId to ResponsibleOp.
@@ -638,9 +668,8 @@ impl From<OpcodeId> for ResponsibleOp {
 impl ResponsibleOp {
     /// Get the opcode
     pub fn opcode(&self) -> OpcodeId {
-        *match self {
-            ResponsibleOp::Op(opcode) => opcode,
-            ResponsibleOp::InvalidStackPtr(opcode, _) => opcode,
+        match self {
+            ResponsibleOp::Op(opcode) => *opcode,
         }
     }
 }
@@ -897,3 +926,38 @@ impl<F: Field> Step<F> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_state_names_and_opcodes() {
+        assert_eq!(ExecutionState::CMP.as_str(), "CMP");
+        assert_eq!(
+            ExecutionState::ErrorOutOfGasSHA3.to_string(),
+            "ErrorOutOfGasSHA3"
+        );
+
+        let cmp_opcodes = ExecutionState::CMP.associated_opcodes();
+        for opcode in [OpcodeId::EQ, OpcodeId::LT, OpcodeId::GT] {
+            assert!(cmp_opcodes.contains(&opcode));
+        }
+        assert_eq!(
+            ExecutionState::ADD_SUB.associated_opcodes(),
+            &[OpcodeId::ADD, OpcodeId::SUB]
+        );
+        assert!(ExecutionState::BeginTx.associated_opcodes().is_empty());
+
+        // Every associated opcode is executed in its execution state
+        for execution_state in ExecutionState::iter() {
+            for opcode in execution_state.associated_opcodes() {
+                assert_eq!(
+                    ExecutionState::from_opcode(*opcode),
+                    Some(execution_state),
+                    "{opcode:?}"
+                );
+            }
+        }
+    }
+}
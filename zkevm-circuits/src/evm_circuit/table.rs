@@ -1,11 +1,7 @@
 //! Fixed lookup tables and dynamic lookup tables for the EVM circuit
 
-use crate::{
-    evm_circuit::step::{ExecutionState, ResponsibleOp},
-    impl_expr,
-    util::word::WordLoHi,
-};
-use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
+use crate::{evm_circuit::step::ExecutionState, impl_expr, util::word::WordLoHi};
+use bus_mapping::{circuit_input_builder::Hardfork, evm::OpcodeId, precompile::PrecompileCalls};
 use eth_types::Field;
 use gadgets::util::Expr;
 use halo2_proofs::plonk::Expression;
@@ -49,12 +45,24 @@ pub enum FixedTableTag {
     ConstantGasCost,
     /// Precompile information
     PrecompileInfo,
+    /// Lookup min/max stack of opcodes
+    OpcodeStackPointers,
 }
 impl_expr!(FixedTableTag);
 
 impl FixedTableTag {
-    /// build up the fixed table row values
+    /// build up the fixed table row values with the precompile gas costs of
+    /// the default hardfork
     pub(crate) fn build<F: Field>(&self) -> Box<dyn Iterator<Item = [F; 4]>> {
+        self.build_with_hardfork(Hardfork::default())
+    }
+
+    /// build up the fixed table row values, with the precompile base gas costs
+    /// of `hardfork`
+    pub(crate) fn build_with_hardfork<F: Field>(
+        &self,
+        hardfork: Hardfork,
+    ) -> Box<dyn Iterator<Item = [F; 4]>> {
         let tag = F::from(*self as u64);
         match self {
             Self::Zero => Box::new((0..1).map(move |_| [tag, F::ZERO, F::ZERO, F::ZERO])),
@@ -103,17 +111,11 @@ impl FixedTableTag {
                 Box::new(ExecutionState::iter().flat_map(move |execution_state| {
                     execution_state.responsible_opcodes().into_iter().map(
                         move |responsible_opcode| {
-                            let (op, aux) = match responsible_opcode {
-                                ResponsibleOp::Op(op) => (op, F::ZERO),
-                                ResponsibleOp::InvalidStackPtr(op, stack_ptr) => {
-                                    (op, F::from(u64::from(stack_ptr)))
-                                }
-                            };
                             [
                                 tag,
                                 F::from(execution_state.as_u64()),
-                                F::from(op.as_u64()),
-                                aux,
+                                F::from(responsible_opcode.opcode().as_u64()),
+                                F::ZERO,
                             ]
                         },
                     )
@@ -160,14 +162,74 @@ impl FixedTableTag {
                             state.as_u64()
                         }),
                         F::from(u64::from(precompile)),
-                        F::from(precompile.base_gas_cost()),
+                        F::from(hardfork.base_gas_cost(precompile)),
                     ]
                 }),
             ),
+            Self::OpcodeStackPointers => Box::new(
+                OpcodeId::iter()
+                    .filter(move |opcode| !matches!(opcode, OpcodeId::INVALID(_)))
+                    .map(move |opcode| {
+                        let (min_stack, max_stack) = opcode.stack_requirements();
+                        [
+                            tag,
+                            F::from(opcode.as_u64()),
+                            F::from(u64::from(min_stack)),
+                            F::from(u64::from(max_stack)),
+                        ]
+                    }),
+            ),
         }
     }
 }
 
+/// Fixed table rows registered under tag ids outside of [`FixedTableTag`], so
+/// that experimental gadgets can add lookup tables without editing the enum.
+/// The rows are loaded after the standard tags, by an EVM circuit configured
+/// with them in its `EvmCircuitParams`.
+#[derive(Clone, Debug)]
+pub struct CustomFixedTables<F> {
+    tables: Vec<(u64, Vec<[F; 3]>)>,
+}
+
+impl<F> Default for CustomFixedTables<F> {
+    fn default() -> Self {
+        Self { tables: vec![] }
+    }
+}
+
+impl<F: Field> CustomFixedTables<F> {
+    /// Registers `rows` under `tag_id`. Panics if `tag_id` is already used by
+    /// a [`FixedTableTag`] or by a previously registered table.
+    pub fn register(mut self, tag_id: u64, rows: Vec<[F; 3]>) -> Self {
+        assert!(
+            FixedTableTag::iter().all(|tag| tag as u64 != tag_id),
+            "fixed table tag {} is already used by FixedTableTag",
+            tag_id
+        );
+        assert!(
+            self.tables.iter().all(|(id, _)| *id != tag_id),
+            "fixed table tag {} is registered twice",
+            tag_id
+        );
+        self.tables.push((tag_id, rows));
+        self
+    }
+
+    /// Number of rows of all the registered tables
+    pub(crate) fn num_rows(&self) -> usize {
+        self.tables.iter().map(|(_, rows)| rows.len()).sum()
+    }
+
+    /// build up the registered fixed table row values
+    pub(crate) fn build(&self) -> impl Iterator<Item = [F; 4]> + '_ {
+        self.tables.iter().flat_map(|(tag_id, rows)| {
+            let tag = F::from(*tag_id);
+            rows.iter().map(move |[a, b, c]| [tag, *a, *b, *c])
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, EnumIter)]
 /// Each item represents the lookup table to query
 pub enum Table {
@@ -546,3 +608,201 @@ impl<F: Field> Lookup<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        evm_circuit::EvmCircuit,
+        util::SubCircuit,
+        witness::{block_convert, Chunk},
+    };
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed},
+        poly::Rotation,
+    };
+    use mock::TestContext;
+
+    const TOY_TAG: u64 = 100;
+
+    /// A toy table of squares, registered as a custom fixed table
+    fn toy_tables() -> CustomFixedTables<Fr> {
+        CustomFixedTables::default().register(
+            TOY_TAG,
+            (0..16u64)
+                .map(|x| [Fr::from(x), Fr::from(x * x), Fr::from(0)])
+                .collect(),
+        )
+    }
+
+    /// Looks up `(TOY_TAG, value, value^2, 0)` in the fixed table.
+    #[derive(Default)]
+    struct ToyCircuit {
+        value: u64,
+    }
+
+    impl Circuit<Fr> for ToyCircuit {
+        type Config = ([Column<Fixed>; 4], Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let fixed_table = [(); 4].map(|_| meta.fixed_column());
+            let value = meta.advice_column();
+            meta.lookup_any("toy square lookup", |meta| {
+                let value = meta.query_advice(value, Rotation::cur());
+                let input = [
+                    TOY_TAG.expr(),
+                    value.clone(),
+                    value.clone() * value,
+                    0.expr(),
+                ];
+                input
+                    .into_iter()
+                    .zip(fixed_table.map(|col| meta.query_fixed(col, Rotation::cur())))
+                    .collect()
+            });
+            (fixed_table, value)
+        }
+
+        fn synthesize(
+            &self,
+            (fixed_table, value): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let tables = toy_tables();
+            layouter.assign_region(
+                || "fixed table",
+                |mut region| {
+                    for (offset, row) in std::iter::once([Fr::from(0); 4])
+                        .chain(FixedTableTag::Range5.build())
+                        .chain(tables.build())
+                        .enumerate()
+                    {
+                        for (column, value) in fixed_table.iter().zip(row) {
+                            region.assign_fixed(|| "", *column, offset, || Value::known(value))?;
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+            layouter.assign_region(
+                || "value",
+                |mut region| {
+                    region.assign_advice(|| "", value, 0, || Value::known(Fr::from(self.value)))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn custom_fixed_table_lookup() {
+        let prover = MockProver::run(6, &ToyCircuit { value: 3 }, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        let prover = MockProver::run(6, &ToyCircuit { value: 17 }, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn custom_fixed_table_in_evm_circuit() {
+        // A table larger than the rows needed by the block, and the fixed table tags
+        let rows = 1 << 17;
+        let tables = CustomFixedTables::default().register(
+            TOY_TAG,
+            (0..rows as u64)
+                .map(|x| [Fr::from(x), Fr::from(x) * Fr::from(x), Fr::from(0)])
+                .collect(),
+        );
+
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP }).unwrap();
+        let block: GethData = ctx.into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = Chunk::single(&block, builder.circuits_params);
+        let block_degree = block.get_test_degree(&chunk);
+
+        let circuit = EvmCircuit::<Fr>::get_test_circuit_from_block(block, chunk);
+        let fixed_table_rows = circuit.num_rows_required_for_fixed_table();
+        let circuit = circuit.with_custom_fixed_tables(tables);
+        assert_eq!(
+            circuit.num_rows_required_for_fixed_table(),
+            fixed_table_rows + rows
+        );
+
+        // The custom rows are counted in the degree of the circuit
+        let k = circuit.get_test_degree();
+        assert!(k > block_degree);
+        let prover = MockProver::<Fr>::run(k, &circuit, circuit.instance()).unwrap();
+        prover.assert_satisfied_par();
+    }
+
+    #[test]
+    #[should_panic(expected = "is already used by FixedTableTag")]
+    fn custom_fixed_table_collides_with_tag() {
+        CustomFixedTables::<Fr>::default().register(FixedTableTag::Pow2 as u64, vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "is registered twice")]
+    fn custom_fixed_table_registered_twice() {
+        toy_tables().register(TOY_TAG, vec![]);
+    }
+
+    #[test]
+    fn opcode_stack_pointers() {
+        let rows = FixedTableTag::OpcodeStackPointers
+            .build::<Fr>()
+            .collect::<Vec<_>>();
+        let lookup = |opcode: OpcodeId| {
+            rows.iter()
+                .find(|row| row[1] == Fr::from(opcode.as_u64()))
+                .map(|row| (row[2], row[3]))
+                .unwrap()
+        };
+        assert_eq!(lookup(OpcodeId::PUSH1), (Fr::from(0), Fr::from(1)));
+        assert_eq!(lookup(OpcodeId::DUP16), (Fr::from(16), Fr::from(17)));
+        assert_eq!(lookup(OpcodeId::ADD), (Fr::from(2), Fr::from(2)));
+    }
+
+    #[test]
+    fn responsible_opcode_without_stack_errors() {
+        // Stack errors are checked against the opcode stack pointers table
+        let rows = FixedTableTag::ResponsibleOpcode
+            .build::<Fr>()
+            .collect::<Vec<_>>();
+        assert!(rows.iter().all(|row| row[3] == Fr::from(0)));
+        assert!(!rows
+            .iter()
+            .any(|row| row[1] == Fr::from(ExecutionState::ErrorStack.as_u64())));
+    }
+
+    #[test]
+    fn precompile_info_modexp_base_gas_per_hardfork() {
+        let modexp_base_gas = |hardfork| {
+            FixedTableTag::PrecompileInfo
+                .build_with_hardfork::<Fr>(hardfork)
+                .find(|row| row[2] == Fr::from(PrecompileCalls::Modexp.address()))
+                .map(|row| row[3])
+                .unwrap()
+        };
+        let istanbul = modexp_base_gas(Hardfork::Istanbul);
+        let berlin = modexp_base_gas(Hardfork::Berlin);
+
+        assert_ne!(istanbul, berlin);
+        assert_eq!(istanbul, Fr::from(0));
+        assert_eq!(berlin, Fr::from(200));
+    }
+}
@@ -172,6 +172,8 @@ pub struct StoredExpression<F> {
     cell_type: CellType,
     expr: Expression<F>,
     expr_id: String,
+    /// Only enabled on steps other than the last one
+    not_step_last: bool,
 }
 
 impl<F> Hash for StoredExpression<F> {
@@ -214,12 +216,28 @@ pub(crate) fn evaluate_expression<F: Field>(
 }
 
 impl<F: Field> StoredExpression<F> {
+    /// Index of the cell manager column the expression is stored in, if it's a lookup
+    #[cfg(feature = "named-lookups")]
+    pub(crate) fn lookup_column_idx(&self) -> Option<usize> {
+        matches!(self.cell_type, CellType::Lookup(_)).then(|| self.cell.get_column_idx())
+    }
+
     pub fn assign(
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
+        is_last_step: bool,
     ) -> Result<Value<F>, Error> {
-        let value = evaluate_expression(&self.expr, region, offset);
+        // A lookup only enabled on non-last steps is unconstrained on the last step, where its
+        // input may not be in the table, so it's disabled with 0 instead.
+        let value = if is_last_step
+            && self.not_step_last
+            && matches!(self.cell_type, CellType::Lookup(_))
+        {
+            Value::known(F::ZERO)
+        } else {
+            evaluate_expression(&self.expr, region, offset)
+        };
         self.cell.assign(region, offset, value)?;
         Ok(value)
     }
@@ -1,15 +1,16 @@
 use super::{
     constraint_builder::ConstrainBuilderCommon,
     from_bytes,
-    math_gadget::{IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget},
+    math_gadget::{
+        ContractCreateGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget, LtGadget,
+    },
     memory_gadget::{CommonMemoryAddressGadget, MemoryExpansionGadget},
     AccountAddress, CachedRegion, StepRws,
 };
 use crate::{
     evm_circuit::{
-        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
         step::ExecutionState,
-        table::{FixedTableTag, Lookup},
         util::{
             and,
             constraint_builder::{
@@ -20,6 +21,7 @@ use crate::{
             not, Cell,
         },
     },
+    mpt_circuit::{rlp_encode_item, rlp_encode_list, rlp_encode_word},
     table::{chunk_ctx_table::ChunkCtxFieldTag, AccountFieldTag, CallContextFieldTag},
     util::{
         word::{Word32, Word32Cell, WordExpr, WordLoHi, WordLoHiCell},
@@ -27,15 +29,17 @@ use crate::{
     },
     witness::{Block, Call, Chunk, ExecStep},
 };
-use bus_mapping::state_db::CodeDB;
 use eth_types::{
-    evm_types::GasCost, Field, OpsIdentity, ToAddress, ToLittleEndian, ToScalar, ToWord, U256,
+    evm_types::GasCost, Address, Field, OpsIdentity, ToAddress, ToBigEndian, ToLittleEndian,
+    ToScalar, U256,
 };
+use ethers_core::utils::keccak256;
 use gadgets::util::{or, select, sum};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
 };
+use std::iter::once;
 
 /// Construction of execution state that stays in the same call context, which
 /// lookups the opcode and verifies the execution state is responsible for it,
@@ -53,17 +57,7 @@ impl<F: Field> SameContextGadget<F> {
         step_state_transition: StepStateTransition<F>,
     ) -> Self {
         cb.opcode_lookup(opcode.expr(), 1.expr());
-        cb.add_lookup(
-            "Responsible opcode lookup",
-            Lookup::Fixed {
-                tag: FixedTableTag::ResponsibleOpcode.expr(),
-                values: [
-                    cb.execution_state().as_u64().expr(),
-                    opcode.expr(),
-                    0.expr(),
-                ],
-            },
-        );
+        cb.responsible_opcode_lookup(opcode.expr());
 
         // Check gas_left is sufficient
         let sufficient_gas_left = RangeCheckGadget::construct(cb, cb.next.state.gas_left.expr());
@@ -87,8 +81,11 @@ impl<F: Field> SameContextGadget<F> {
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
 
-        self.sufficient_gas_left
-            .assign(region, offset, F::from(step.gas_left - step.gas_cost))?;
+        self.sufficient_gas_left.assign(
+            region,
+            offset,
+            F::from(step.gas_left) - F::from(step.gas_cost),
+        )?;
 
         Ok(())
     }
@@ -465,6 +462,10 @@ impl<F: Field> TransferToGadget<F> {
 /// The value transfer is only performed if the value is not zero.
 /// It also create the receiver account when the conditions in [`TransferToGadget`] is met.
 /// This gadget is used in BeginTx, Call ops, and Create.
+///
+/// The sender balance is always written before the receiver one. For a transfer to self, the
+/// balance goes from `b` to `b - value` and then back to `b`, so the caller must only enable the
+/// gadget when `value <= b` (the sender decrease can't underflow).
 #[derive(Clone, Debug)]
 pub(crate) struct TransferGadget<F, const WITH_FEE: bool> {
     sender_sub_fee: Option<UpdateBalanceGadget<F, 2, false>>,
@@ -762,7 +763,7 @@ impl<F: Field, MemAddrGadget: CommonMemoryAddressGadget<F>, const IS_SUCCESS_CAL
             region,
             offset,
             Value::known(WordLoHi::from(callee_code_hash)),
-            Value::known(WordLoHi::from(CodeDB::empty_code_hash().to_word())),
+            Value::known(WordLoHi::empty_code_hash()),
         )?;
         self.callee_not_exists.assign_value(
             region,
@@ -848,23 +849,24 @@ impl<F: Field, T: WordExpr<F> + Clone> SstoreGasGadget<F, T> {
         let value_eq_prev = cb.is_eq_word(&value, &value_prev);
         let original_eq_prev = cb.is_eq_word(&original_value, &value_prev);
         let original_is_zero = cb.is_zero_word(&original_value);
+        let hardfork = cb.feature_config.hardfork;
         let warm_case_gas = select::expr(
             value_eq_prev.expr(),
-            GasCost::WARM_ACCESS.expr(),
+            hardfork.sload_gas().expr(),
             select::expr(
                 original_eq_prev.expr(),
                 select::expr(
                     original_is_zero.expr(),
                     GasCost::SSTORE_SET.expr(),
-                    GasCost::SSTORE_RESET.expr(),
+                    hardfork.sstore_reset().expr(),
                 ),
-                GasCost::WARM_ACCESS.expr(),
+                hardfork.sload_gas().expr(),
             ),
         );
         let gas_cost = select::expr(
             is_warm.expr(),
             warm_case_gas.expr(),
-            warm_case_gas + GasCost::COLD_SLOAD.expr(),
+            warm_case_gas + hardfork.cold_sload().expr(),
         );
 
         Self {
@@ -920,30 +922,6 @@ pub(crate) fn cal_sload_gas_cost_for_assignment(is_warm: bool) -> u64 {
     }
 }
 
-pub(crate) fn cal_sstore_gas_cost_for_assignment(
-    value: U256,
-    value_prev: U256,
-    original_value: U256,
-    is_warm: bool,
-) -> u64 {
-    let warm_case_gas = if value_prev == value {
-        GasCost::WARM_ACCESS
-    } else if original_value == value_prev {
-        if original_value.is_zero() {
-            GasCost::SSTORE_SET
-        } else {
-            GasCost::SSTORE_RESET
-        }
-    } else {
-        GasCost::WARM_ACCESS
-    };
-    if is_warm {
-        warm_case_gas
-    } else {
-        warm_case_gas + GasCost::COLD_SLOAD
-    }
-}
-
 #[derive(Clone, Debug)]
 pub(crate) struct CommonErrorGadget<F> {
     rw_counter_end_of_reversion: WordLoHiCell<F>,
@@ -1053,6 +1031,65 @@ impl<F: Field> CommonErrorGadget<F> {
     }
 }
 
+/// Common gadget for the out of gas errors.  It checks that the gas left isn't
+/// enough to cover `required_gas` (unless `is_overflow` already makes the
+/// opcode fail, e.g. on a memory address overflow) and halts the current call
+/// consuming all the remaining gas.  Gadgets of dynamic gas opcodes only need
+/// to compute their gas cost and pass it in.
+#[derive(Clone, Debug)]
+pub(crate) struct CommonErrorOOGGadget<F> {
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> CommonErrorOOGGadget<F> {
+    /// Must be constructed after all the rw lookups of the step, since they
+    /// are accounted in the rw_counter transition.
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        opcode: Expression<F>,
+        required_gas: Expression<F>,
+        is_overflow: Expression<F>,
+    ) -> Self {
+        let insufficient_gas = cb.is_lt(cb.curr.state.gas_left.expr(), required_gas);
+        cb.require_equal(
+            "Gas left is less than gas required or the inputs overflow",
+            or::expr([is_overflow, insufficient_gas.expr()]),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode, cb.rw_counter_offset());
+
+        Self {
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        call: &Call,
+        step: &ExecStep,
+        required_gas: u64,
+        rw_offset: usize,
+    ) -> Result<(), Error> {
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(required_gas),
+        )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, rw_offset)?;
+
+        Ok(())
+    }
+}
+
 /// Check if the passed in word is within the specified byte range
 /// (not overflow) and less than a maximum cap.
 #[derive(Clone, Debug)]
@@ -1272,3 +1309,300 @@ impl<F: Field> RwTablePaddingGadget<F> {
         Ok(())
     }
 }
+
+/// Derives the address of a contract deployed with CREATE, i.e. the low 20
+/// bytes of `keccak256(rlp([sender, nonce]))`, or with CREATE2, i.e. the low
+/// 20 bytes of `keccak256(0xff ++ sender ++ salt ++ keccak256(init_code))`.
+/// The hash input, including the variable-length RLP-encoding of the nonce,
+/// is built in-circuit by [`ContractCreateGadget`] from `sender` and `nonce`,
+/// and the hash is verified by the keccak table lookup emitted by
+/// [`Self::keccak_lookup`].
+#[derive(Clone, Debug)]
+pub(crate) struct CreateAddressGadget<F, const IS_CREATE2: bool> {
+    create: ContractCreateGadget<F, IS_CREATE2>,
+    keccak_output: Word32Cell<F>,
+    address: AccountAddress<F>,
+}
+
+impl<F: Field, const IS_CREATE2: bool> CreateAddressGadget<F, IS_CREATE2> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        sender: WordLoHi<Expression<F>>,
+        nonce: Expression<F>,
+    ) -> Self {
+        let create = ContractCreateGadget::construct(cb);
+        cb.require_equal_word(
+            "sender is the CREATE caller",
+            sender,
+            create.caller_address(),
+        );
+        cb.require_equal(
+            "nonce is the CREATE caller nonce",
+            nonce,
+            create.caller_nonce(),
+        );
+
+        let keccak_output = cb.query_word32();
+        // Word32Cell limbs are little-endian, so the address is the first 20 limbs.
+        let address = AccountAddress::new(
+            keccak_output.limbs[..N_BYTES_ACCOUNT_ADDRESS]
+                .to_vec()
+                .try_into()
+                .unwrap(),
+        );
+
+        Self {
+            create,
+            keccak_output,
+            address,
+        }
+    }
+
+    /// Verifies the derived address with a keccak table lookup.  Callers emit
+    /// it under the condition in which the contract is actually created.
+    pub(crate) fn keccak_lookup(&self, cb: &mut EVMConstraintBuilder<F>) {
+        cb.keccak_table_lookup(
+            self.create.input_rlc(cb),
+            self.create.input_length(),
+            self.keccak_output.to_word(),
+        );
+    }
+
+    /// Assigns the gadget and returns the derived contract address.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        sender: Address,
+        nonce: u64,
+        code_hash: Option<U256>,
+        salt: Option<U256>,
+    ) -> Result<Address, Error> {
+        self.create
+            .assign(region, offset, sender, nonce, code_hash, salt)?;
+
+        let keccak_input: Vec<u8> = if IS_CREATE2 {
+            once(0xffu8)
+                .chain(sender.to_fixed_bytes())
+                .chain(salt.unwrap_or_default().to_be_bytes())
+                .chain(code_hash.unwrap_or_default().to_be_bytes())
+                .collect()
+        } else {
+            rlp_encode_list(&[
+                rlp_encode_item(sender.as_bytes()),
+                rlp_encode_word(nonce.into()),
+            ])
+        };
+        let keccak_output = keccak256(keccak_input);
+        self.keccak_output
+            .assign_be(region, offset, &keccak_output)?;
+
+        Ok(Address::from_slice(
+            &keccak_output[32 - N_BYTES_ACCOUNT_ADDRESS..],
+        ))
+    }
+
+    /// The gadget building the hash input.
+    pub(crate) fn create(&self) -> &ContractCreateGadget<F, IS_CREATE2> {
+        &self.create
+    }
+
+    /// The derived contract address.
+    pub(crate) fn address(&self) -> WordLoHi<Expression<F>> {
+        self.address.to_word()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_circuit::util::math_gadget::test_util::*;
+    use eth_types::{address, ToWord, Word};
+    use ethers_core::utils::{get_contract_address, get_create2_address_from_hash};
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[derive(Clone)]
+    struct CreateAddressGadgetContainer<F, const IS_CREATE2: bool> {
+        create_address: CreateAddressGadget<F, IS_CREATE2>,
+        sender: WordLoHiCell<F>,
+        nonce: Cell<F>,
+        expected: WordLoHiCell<F>,
+    }
+
+    impl<F: Field, const IS_CREATE2: bool> MathGadgetContainer<F>
+        for CreateAddressGadgetContainer<F, IS_CREATE2>
+    {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let sender = cb.query_word_unchecked();
+            let nonce = cb.query_cell();
+            let expected = cb.query_word_unchecked();
+            let create_address = CreateAddressGadget::construct(cb, sender.to_word(), nonce.expr());
+            // Not enforced by the container, see `test_create_tampered_preimage`
+            // in the CREATE gadget for the keccak binding.
+            create_address.keccak_lookup(cb);
+            cb.require_equal_word(
+                "contract address is correct",
+                create_address.address(),
+                expected.to_word(),
+            );
+
+            Self {
+                create_address,
+                sender,
+                nonce,
+                expected,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            let sender = witnesses[0].to_address();
+            let nonce = witnesses[1].as_u64();
+            let (code_hash, salt) = (witnesses[3], witnesses[4]);
+            // The inputs of the gadget, which may differ from the hashed ones
+            let (input_sender, input_nonce) = (witnesses[5].to_address(), witnesses[6].as_u64());
+
+            self.sender.assign_h160(region, offset, input_sender)?;
+            self.nonce
+                .assign(region, offset, Value::known(F::from(input_nonce)))?;
+            self.expected
+                .assign_h160(region, offset, witnesses[2].to_address())?;
+            let address = self.create_address.assign(
+                region,
+                offset,
+                sender,
+                nonce,
+                Some(code_hash),
+                Some(salt),
+            )?;
+            let expected = if IS_CREATE2 {
+                get_create2_address_from_hash(sender, salt.to_be_bytes(), code_hash.to_be_bytes())
+            } else {
+                get_contract_address(sender, nonce)
+            };
+            assert_eq!(address, expected);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn create_address_nonce_zero() {
+        // rlp.encode([0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0, 0])
+        let sender = address!("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        let expected = address!("0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d");
+        try_test!(
+            CreateAddressGadgetContainer<Fr, false>,
+            [
+                sender.to_word(),
+                Word::zero(),
+                expected.to_word(),
+                Word::zero(),
+                Word::zero(),
+                sender.to_word(),
+                Word::zero()
+            ],
+            true,
+        );
+    }
+
+    #[test]
+    fn create_address_multi_byte_nonce() {
+        let sender = address!("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        for nonce in [0x7fu64, 0x80, 0xff, 0x0100, 0x01_0000_0000] {
+            let expected = get_contract_address(sender, nonce);
+            try_test!(
+                CreateAddressGadgetContainer<Fr, false>,
+                [
+                    sender.to_word(),
+                    Word::from(nonce),
+                    expected.to_word(),
+                    Word::zero(),
+                    Word::zero(),
+                    sender.to_word(),
+                    Word::from(nonce)
+                ],
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn create_address_wrong_address() {
+        let sender = address!("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        let expected = get_contract_address(sender, 1u64);
+        try_test!(
+            CreateAddressGadgetContainer<Fr, false>,
+            [
+                sender.to_word(),
+                Word::from(0x80),
+                expected.to_word(),
+                Word::zero(),
+                Word::zero(),
+                sender.to_word(),
+                Word::from(0x80)
+            ],
+            false,
+        );
+    }
+
+    #[test]
+    fn create_address_inputs_not_hashed() {
+        let sender = address!("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        let expected = get_contract_address(sender, 1u64);
+        // The address derived from (sender, 1), with a different nonce input
+        try_test!(
+            CreateAddressGadgetContainer<Fr, false>,
+            [
+                sender.to_word(),
+                Word::one(),
+                expected.to_word(),
+                Word::zero(),
+                Word::zero(),
+                sender.to_word(),
+                Word::from(2)
+            ],
+            false,
+        );
+        // The address derived from (sender, 1), with a different sender input
+        try_test!(
+            CreateAddressGadgetContainer<Fr, false>,
+            [
+                sender.to_word(),
+                Word::one(),
+                expected.to_word(),
+                Word::zero(),
+                Word::zero(),
+                Word::from(0xcafe),
+                Word::one()
+            ],
+            false,
+        );
+    }
+
+    #[test]
+    fn create2_address() {
+        let sender = address!("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        let code_hash = Word::from(keccak256([0x00u8]));
+        let salt = Word::from(0xcafe);
+        let expected =
+            get_create2_address_from_hash(sender, salt.to_be_bytes(), code_hash.to_be_bytes());
+        try_test!(
+            CreateAddressGadgetContainer<Fr, true>,
+            [
+                sender.to_word(),
+                Word::zero(),
+                expected.to_word(),
+                code_hash,
+                salt,
+                sender.to_word(),
+                Word::zero()
+            ],
+            true,
+        );
+    }
+}
@@ -1,8 +1,10 @@
+#[cfg(any(test, feature = "test-circuits"))]
+use super::evaluate_expression;
 use super::{
     common_gadget::UpdateBalanceGadget,
     math_gadget::{
-        ConstantDivisionGadget, IsEqualGadget, IsEqualWordGadget, IsZeroGadget, IsZeroWordGadget,
-        LtGadget, LtWordGadget, MinMaxGadget,
+        AllZeroWordsGadget, ConstantDivisionGadget, IsEqualGadget, IsEqualWordGadget, IsZeroGadget,
+        IsZeroWordGadget, LtGadget, LtWordGadget, MinMaxGadget,
     },
     rlc, AccountAddress, CachedRegion, CellType, MemoryAddress, StoredExpression, U64Cell,
 };
@@ -27,7 +29,7 @@ use bus_mapping::{
     circuit_input_builder::FeatureConfig, operation::Target, state_db::EMPTY_CODE_HASH_LE,
 };
 use eth_types::{Field, OpsIdentity};
-use gadgets::util::{not, sum};
+use gadgets::util::{not, select, sum};
 use halo2_proofs::{
     circuit::Value,
     plonk::{
@@ -115,6 +117,46 @@ impl<F: Field> StepStateTransition<F> {
     }
 }
 
+/// Record of a step state field constrained by
+/// [`EVMConstraintBuilder::require_step_state_transition`].  It keeps the value
+/// the gadget expects for the next step next to the next step cell, so that a
+/// wrong transition (e.g. a `rw_counter` delta off by one) can be pinpointed
+/// against the assigned witness.  Only recorded in test builds.
+#[cfg(any(test, feature = "test-circuits"))]
+#[derive(Clone, Debug)]
+pub(crate) struct StepStateTransitionRecord<F> {
+    /// Name of the step state field
+    pub(crate) field: &'static str,
+    /// Condition under which the transition is required
+    pub(crate) condition: Expression<F>,
+    /// Expected value of the field in the next step
+    pub(crate) expected: Expression<F>,
+    /// Value of the field assigned in the next step
+    pub(crate) actual: Expression<F>,
+}
+
+#[cfg(any(test, feature = "test-circuits"))]
+impl<F: Field> StepStateTransitionRecord<F> {
+    /// Returns the `(expected, actual)` values of the transition when it's
+    /// enabled and they don't match.
+    pub(crate) fn mismatch(
+        &self,
+        region: &CachedRegion<'_, '_, F>,
+        offset: usize,
+    ) -> Option<(F, F)> {
+        let mut mismatch = None;
+        evaluate_expression(&self.condition, region, offset)
+            .zip(evaluate_expression(&self.expected, region, offset))
+            .zip(evaluate_expression(&self.actual, region, offset))
+            .map(|((condition, expected), actual)| {
+                if !condition.is_zero_vartime() && expected != actual {
+                    mismatch = Some((expected, actual));
+                }
+            });
+        mismatch
+    }
+}
+
 /// ReversionInfo counts `rw_counter` of reversion for gadgets, by tracking how
 /// many reversions that have been used. Gadgets should call
 /// [`EVMConstraintBuilder::reversion_info`] to get [`ReversionInfo`] with
@@ -334,6 +376,8 @@ pub(crate) struct EVMConstraintBuilder<'a, F: Field> {
     constraints_location: ConstraintLocation,
     stored_expressions: Vec<StoredExpression<F>>,
     pub(crate) debug_expressions: Vec<(String, Expression<F>)>,
+    #[cfg(any(test, feature = "test-circuits"))]
+    pub(crate) step_state_transitions: Vec<StepStateTransitionRecord<F>>,
     meta: &'a mut ConstraintSystem<F>,
     pub(crate) feature_config: FeatureConfig,
 }
@@ -382,6 +426,8 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
             stored_expressions: Vec::new(),
             meta,
             debug_expressions: Vec::new(),
+            #[cfg(any(test, feature = "test-circuits"))]
+            step_state_transitions: Vec::new(),
             feature_config,
         }
     }
@@ -475,7 +521,20 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     // query_word32 each limb is 8 bits, and any conversion to smaller limbs inherits the type
     // check.
     pub(crate) fn query_word32(&mut self) -> Word32Cell<F> {
-        Word32::new(self.query_bytes())
+        Word32::new(self.query_bytes_le())
+    }
+
+    // query_word32_bounded is query_word32 with the bytes above `max` constrained to zero. The
+    // bound is rounded up to whole bytes, so the value is only guaranteed to be less than
+    // 256^n where n is the byte length of `max`.
+    #[allow(dead_code)]
+    pub(crate) fn query_word32_bounded(&mut self, max: u64) -> Word32Cell<F> {
+        let word = self.query_word32();
+        let n_bytes = (u64::BITS - max.leading_zeros()).div_ceil(8) as usize;
+        for byte in word.limbs[n_bytes..].iter() {
+            self.require_zero("Word32 byte above the bound is zero", byte.expr());
+        }
+        word
     }
 
     pub(crate) fn query_keccak_rlc<const N: usize>(&mut self) -> RandomLinearCombination<F, N> {
@@ -483,21 +542,38 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     }
 
     pub(crate) fn query_u64(&mut self) -> U64Cell<F> {
-        U64Cell::new(self.query_bytes())
+        U64Cell::new(self.query_bytes_le())
     }
 
     pub(crate) fn query_account_address(&mut self) -> AccountAddress<F> {
-        AccountAddress::<F>::new(self.query_bytes())
+        AccountAddress::<F>::new(self.query_bytes_le())
     }
 
     pub(crate) fn query_memory_address(&mut self) -> MemoryAddress<F> {
-        MemoryAddress::<F>::new(self.query_bytes())
+        MemoryAddress::<F>::new(self.query_bytes_le())
     }
 
     pub(crate) fn query_bytes<const N: usize>(&mut self) -> [Cell<F>; N] {
         self.query_u8_dyn(N).try_into().unwrap()
     }
 
+    // query_bytes_le returns the bytes of a value starting from the least significant one, the
+    // order expected by `Word32`, `IntDecomposition` and `from_bytes`, and produced by
+    // `to_le_bytes`.
+    pub(crate) fn query_bytes_le<const N: usize>(&mut self) -> [Cell<F>; N] {
+        self.query_bytes()
+    }
+
+    // query_bytes_be returns the bytes of a value starting from the most significant one, the
+    // order of `to_be_bytes` and of the memory.  The cells are laid out as in query_bytes_le,
+    // only the returned order is reversed.
+    #[allow(dead_code)]
+    pub(crate) fn query_bytes_be<const N: usize>(&mut self) -> [Cell<F>; N] {
+        let mut bytes = self.query_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
     pub(crate) fn query_u8_dyn(&mut self, count: usize) -> Vec<Cell<F>> {
         self.query_cells(CellType::Lookup(Table::U8), count)
     }
@@ -551,21 +627,45 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         macro_rules! constrain {
             ($name:tt) => {
                 match step_state_transition.$name {
-                    Transition::Same => self.require_equal(
-                        concat!("State transition (same) constraint of ", stringify!($name)),
-                        self.next.state.$name.expr(),
-                        self.curr.state.$name.expr(),
-                    ),
-                    Transition::Delta(delta) => self.require_equal(
-                        concat!("State transition (delta) constraint of ", stringify!($name)),
-                        self.next.state.$name.expr(),
-                        self.curr.state.$name.expr() + delta,
-                    ),
-                    Transition::To(to) => self.require_equal(
-                        concat!("State transition (to) constraint of ", stringify!($name)),
-                        self.next.state.$name.expr(),
-                        to,
-                    ),
+                    Transition::Same => {
+                        #[cfg(any(test, feature = "test-circuits"))]
+                        self.record_step_state_transition(
+                            stringify!($name),
+                            self.curr.state.$name.expr(),
+                            self.next.state.$name.expr(),
+                        );
+                        self.require_equal(
+                            concat!("State transition (same) constraint of ", stringify!($name)),
+                            self.next.state.$name.expr(),
+                            self.curr.state.$name.expr(),
+                        )
+                    }
+                    Transition::Delta(delta) => {
+                        #[cfg(any(test, feature = "test-circuits"))]
+                        self.record_step_state_transition(
+                            stringify!($name),
+                            self.curr.state.$name.expr() + delta.clone(),
+                            self.next.state.$name.expr(),
+                        );
+                        self.require_equal(
+                            concat!("State transition (delta) constraint of ", stringify!($name)),
+                            self.next.state.$name.expr(),
+                            self.curr.state.$name.expr() + delta,
+                        )
+                    }
+                    Transition::To(to) => {
+                        #[cfg(any(test, feature = "test-circuits"))]
+                        self.record_step_state_transition(
+                            stringify!($name),
+                            to.clone(),
+                            self.next.state.$name.expr(),
+                        );
+                        self.require_equal(
+                            concat!("State transition (to) constraint of ", stringify!($name)),
+                            self.next.state.$name.expr(),
+                            to,
+                        )
+                    }
                     _ => {}
                 }
             };
@@ -574,16 +674,32 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         macro_rules! constrain_word {
             ($name:tt) => {
                 match step_state_transition.$name {
-                    Transition::Same => self.require_equal_word(
-                        concat!("State transition (same) constraint of ", stringify!($name)),
-                        self.next.state.$name.to_word(),
-                        self.curr.state.$name.to_word(),
-                    ),
-                    Transition::To(to) => self.require_equal_word(
-                        concat!("State transition (to) constraint of ", stringify!($name)),
-                        self.next.state.$name.to_word(),
-                        to,
-                    ),
+                    Transition::Same => {
+                        #[cfg(any(test, feature = "test-circuits"))]
+                        self.record_step_state_transition_word(
+                            stringify!($name),
+                            self.curr.state.$name.to_word(),
+                            self.next.state.$name.to_word(),
+                        );
+                        self.require_equal_word(
+                            concat!("State transition (same) constraint of ", stringify!($name)),
+                            self.next.state.$name.to_word(),
+                            self.curr.state.$name.to_word(),
+                        )
+                    }
+                    Transition::To(to) => {
+                        #[cfg(any(test, feature = "test-circuits"))]
+                        self.record_step_state_transition_word(
+                            stringify!($name),
+                            to.clone(),
+                            self.next.state.$name.to_word(),
+                        );
+                        self.require_equal_word(
+                            concat!("State transition (to) constraint of ", stringify!($name)),
+                            self.next.state.$name.to_word(),
+                            to,
+                        )
+                    }
                     _ => {}
                 }
             };
@@ -623,6 +739,41 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         constrain!(log_id);
     }
 
+    #[cfg(any(test, feature = "test-circuits"))]
+    fn record_step_state_transition(
+        &mut self,
+        field: &'static str,
+        expected: Expression<F>,
+        actual: Expression<F>,
+    ) {
+        self.step_state_transitions.push(StepStateTransitionRecord {
+            field,
+            condition: self.condition_expr(),
+            expected,
+            actual,
+        });
+    }
+
+    #[cfg(any(test, feature = "test-circuits"))]
+    fn record_step_state_transition_word(
+        &mut self,
+        field: &'static str,
+        expected: WordLoHi<Expression<F>>,
+        actual: WordLoHi<Expression<F>>,
+    ) {
+        let (expected_lo, expected_hi) = expected.to_lo_hi();
+        let (actual_lo, actual_hi) = actual.to_lo_hi();
+        self.record_step_state_transition(field, expected_lo, actual_lo);
+        self.record_step_state_transition(field, expected_hi, actual_hi);
+    }
+
+    /// Returns the step state transitions required so far by the gadget, in
+    /// the order they were constrained.
+    #[cfg(test)]
+    pub(crate) fn step_state_transitions(&self) -> &[StepStateTransitionRecord<F>] {
+        &self.step_state_transitions
+    }
+
     // Math gadgets
 
     pub(crate) fn is_zero(&mut self, value: Expression<F>) -> IsZeroGadget<F> {
@@ -633,6 +784,16 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         IsZeroWordGadget::construct(self, value)
     }
 
+    /// Returns `1` when all of `words` are zero, and returns `0` otherwise.
+    /// The limbs of `words` must be range checked to 128 bits.
+    #[allow(dead_code)]
+    pub(crate) fn all_zero_words(
+        &mut self,
+        words: &[&WordLoHi<Expression<F>>],
+    ) -> AllZeroWordsGadget<F> {
+        AllZeroWordsGadget::construct(self, words)
+    }
+
     pub(crate) fn is_eq(&mut self, lhs: Expression<F>, rhs: Expression<F>) -> IsEqualGadget<F> {
         IsEqualGadget::construct(self, lhs, rhs)
     }
@@ -645,6 +806,15 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         IsEqualWordGadget::construct(self, lhs, rhs)
     }
 
+    /// Returns `1` when `word == c`, and returns `0` otherwise.
+    pub(crate) fn is_eq_word_u64<T: WordExpr<F>>(
+        &mut self,
+        word: &T,
+        c: u64,
+    ) -> IsEqualWordGadget<F, T, WordLoHi<Expression<F>>> {
+        IsEqualWordGadget::construct(self, word, &WordLoHi::from_lo_unchecked(c.expr()))
+    }
+
     pub(crate) fn is_lt<const N_BYTES: usize>(
         &mut self,
         lhs: Expression<F>,
@@ -661,6 +831,31 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         LtWordGadget::construct(self, lhs, rhs)
     }
 
+    /// Returns `1` when `word < c`, and returns `0` otherwise.
+    pub(crate) fn is_lt_word_u64(
+        &mut self,
+        word: &WordLoHi<Expression<F>>,
+        c: u64,
+    ) -> LtWordGadget<F> {
+        LtWordGadget::construct(self, word, &WordLoHi::from_lo_unchecked(c.expr()))
+    }
+
+    /// Returns `when_true` if `condition` is `1` and `when_false` if it is `0`,
+    /// selecting each limb separately. `condition` must be boolean.
+    pub(crate) fn select_word<T: WordExpr<F>>(
+        &self,
+        condition: Expression<F>,
+        when_true: &T,
+        when_false: &T,
+    ) -> WordLoHi<Expression<F>> {
+        let (true_lo, true_hi) = when_true.to_word().to_lo_hi();
+        let (false_lo, false_hi) = when_false.to_word().to_lo_hi();
+        WordLoHi::new([
+            select::expr(condition.clone(), true_lo, false_lo),
+            select::expr(condition, true_hi, false_hi),
+        ])
+    }
+
     pub(crate) fn min_max<const N_BYTES: usize>(
         &mut self,
         lhs: Expression<F>,
@@ -700,6 +895,14 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     // Fixed
 
     pub(crate) fn range_lookup(&mut self, value: Expression<F>, range: u64) {
+        // There is no Range2 fixed table, but a ConstantDivisionGadget by 2 range checks its
+        // remainder against 2, as EndTx does to cap the refund at half of the gas used under the
+        // EIP-2200 refund rules of Istanbul and Berlin. A boolean constraint is the same check
+        // without a lookup.
+        if range == 2 {
+            self.require_boolean("Range2", value);
+            return;
+        }
         let (name, tag) = match range {
             5 => ("Range5", FixedTableTag::Range5),
             16 => ("Range16", FixedTableTag::Range16),
@@ -747,8 +950,38 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // stack pointers
+
+    /// Looks up the number of stack items `opcode` requires, `min_stack`, and
+    /// the stack height it reaches from them, `max_stack`.
+    pub(crate) fn stack_pointer_lookup(
+        &mut self,
+        opcode: Expression<F>,
+        min_stack: Expression<F>,
+        max_stack: Expression<F>,
+    ) {
+        self.add_lookup(
+            "opcode stack pointers",
+            Lookup::Fixed {
+                tag: FixedTableTag::OpcodeStackPointers.expr(),
+                values: [opcode, min_stack, max_stack],
+            },
+        );
+    }
+
     // Opcode
 
+    /// Constrain the current execution state to be responsible for `opcode`
+    pub(crate) fn responsible_opcode_lookup(&mut self, opcode: Expression<F>) {
+        self.add_lookup(
+            "Responsible opcode lookup",
+            Lookup::Fixed {
+                tag: FixedTableTag::ResponsibleOpcode.expr(),
+                values: [self.execution_state.as_u64().expr(), opcode, 0.expr()],
+            },
+        );
+    }
+
     pub(crate) fn opcode_lookup(&mut self, opcode: Expression<F>, is_code: Expression<F>) {
         self.opcode_lookup_at(
             self.curr.state.program_counter.expr() + self.program_counter_offset.expr(),
@@ -1877,11 +2110,17 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         }
     }
 
+    /// Add a lookup enabled on the current constraint location.  Under
+    /// `not_step_last` the lookup input is only constrained on non-last steps,
+    /// the same way as the other constraints there, and is assigned 0 on the
+    /// last step.  Lookups on `step_first` are not supported, as the input
+    /// would have to be disabled on every other step; the first step lookups
+    /// are configured directly on the chunk context table instead.
     pub(crate) fn add_lookup(&mut self, name: &str, lookup: Lookup<F>) {
-        debug_assert_eq!(
+        debug_assert_ne!(
             self.constraints_location,
-            ConstraintLocation::Step,
-            "lookup do not support conditional on constraint location other than `ConstraintLocation::Step`"
+            ConstraintLocation::StepFirst,
+            "lookup do not support conditional on `ConstraintLocation::StepFirst`"
         );
         let lookup = match self.condition_expr_opt() {
             Some(condition) => lookup.conditional(condition),
@@ -1935,6 +2174,7 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
                     cell_type,
                     expr_id: expr.identifier(),
                     expr,
+                    not_step_last: self.constraints_location == ConstraintLocation::NotStepLast,
                 });
                 cell.expr()
             }
@@ -1947,9 +2187,12 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         cell_type: CellType,
     ) -> Option<&StoredExpression<F>> {
         let expr_id = expr.identifier();
-        self.stored_expressions
-            .iter()
-            .find(|&e| e.cell_type == cell_type && e.expr_id == expr_id)
+        // Expressions stored under `not_step_last` can't be reused elsewhere, as they are
+        // unconstrained on the last step.
+        let not_step_last = self.constraints_location == ConstraintLocation::NotStepLast;
+        self.stored_expressions.iter().find(|&e| {
+            e.cell_type == cell_type && e.expr_id == expr_id && e.not_step_last == not_step_last
+        })
     }
 
     fn split_expression(
@@ -2007,4 +2250,314 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
     pub fn debug_expression<S: Into<String>>(&mut self, name: S, expr: Expression<F>) {
         self.debug_expressions.push((name.into(), expr));
     }
+
+    /// Returns the names of the stored expressions whose cell is never used, neither by a
+    /// constraint (other than the one defining the cell) nor by another stored expression.
+    /// Lookup cells are always used by their lookup.
+    #[cfg(test)]
+    pub(crate) fn unused_stored_expressions(&self) -> Vec<String> {
+        fn collect_advice_queries<F: Field>(
+            expr: &Expression<F>,
+            queries: &mut std::collections::HashSet<String>,
+        ) {
+            match expr {
+                Expression::Advice(_) => {
+                    queries.insert(expr.identifier());
+                }
+                Expression::Negated(a) | Expression::Scaled(a, _) => {
+                    collect_advice_queries(a, queries)
+                }
+                Expression::Sum(a, b) | Expression::Product(a, b) => {
+                    collect_advice_queries(a, queries);
+                    collect_advice_queries(b, queries);
+                }
+                _ => {}
+            }
+        }
+
+        let mut used = std::collections::HashSet::new();
+        for (name, constraint) in self
+            .constraints
+            .step
+            .iter()
+            .chain(self.constraints.step_first.iter())
+            .chain(self.constraints.step_last.iter())
+            .chain(self.constraints.not_step_last.iter())
+        {
+            if !name.ends_with("(stored expression)") {
+                collect_advice_queries(constraint, &mut used);
+            }
+        }
+        for stored_expression in self.stored_expressions.iter() {
+            collect_advice_queries(&stored_expression.expr, &mut used);
+        }
+
+        self.stored_expressions
+            .iter()
+            .filter(|e| !matches!(e.cell_type, CellType::Lookup(_)))
+            .filter(|e| !used.contains(&e.cell.expr().identifier()))
+            .map(|e| e.name.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_circuit::{
+        param::{MAX_STEP_HEIGHT, STEP_WIDTH},
+        util::{
+            from_bytes,
+            math_gadget::test_util::{test_math_gadget_container, try_test, MathGadgetContainer},
+        },
+    };
+    use eth_types::Word;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[test]
+    fn step_state_transitions_are_recorded() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let challenges = Challenges::construct(&mut meta);
+        let challenges_exprs = challenges.exprs(&mut meta);
+        let advices = [(); STEP_WIDTH].map(|_| meta.advice_column());
+        let curr = Step::new(&mut meta, advices, 0);
+        let next = Step::new(&mut meta, advices, MAX_STEP_HEIGHT);
+        let mut cb = EVMConstraintBuilder::new(
+            &mut meta,
+            curr,
+            next,
+            &challenges_exprs,
+            ExecutionState::STOP,
+            FeatureConfig::default(),
+        );
+
+        let condition = cb.query_bool();
+        cb.condition(condition.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Transition::Delta(3.expr()),
+                program_counter: Transition::To(0.expr()),
+                code_hash: Transition::Same,
+                ..StepStateTransition::any()
+            });
+        });
+
+        let records = cb.step_state_transitions();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.field)
+                .collect::<Vec<_>>(),
+            vec!["rw_counter", "code_hash", "code_hash", "program_counter"]
+        );
+        assert_eq!(
+            records[0].expected.identifier(),
+            (cb.curr.state.rw_counter.expr() + 3.expr()).identifier()
+        );
+        assert_eq!(
+            records[0].actual.identifier(),
+            cb.next.state.rw_counter.expr().identifier()
+        );
+        assert_eq!(records[3].expected.identifier(), 0.expr().identifier());
+        assert!(records
+            .iter()
+            .all(|record| record.condition.identifier() == condition.expr().identifier()));
+    }
+
+    #[test]
+    fn lookup_not_step_last() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let challenges = Challenges::construct(&mut meta);
+        let challenges_exprs = challenges.exprs(&mut meta);
+        let advices = [(); STEP_WIDTH].map(|_| meta.advice_column());
+        let curr = Step::new(&mut meta, advices, 0);
+        let next = Step::new(&mut meta, advices, MAX_STEP_HEIGHT);
+        let mut cb = EVMConstraintBuilder::new(
+            &mut meta,
+            curr,
+            next,
+            &challenges_exprs,
+            ExecutionState::EndChunk,
+            FeatureConfig::default(),
+        );
+
+        // A gadget looking up the next step's program counter, which is only
+        // meaningful when there is a next step.
+        let value = cb.next.state.program_counter.expr();
+        cb.not_step_last(|cb| cb.range_lookup(value.clone(), 256));
+        // The same lookup on every step gets a cell of its own.
+        cb.range_lookup(value, 256);
+
+        let (constraints, stored_expressions, _, _) = cb.build();
+        let lookups = stored_expressions
+            .iter()
+            .filter(|e| matches!(e.cell_type, CellType::Lookup(Table::Fixed)))
+            .collect::<Vec<_>>();
+        assert_eq!(lookups.len(), 2);
+        assert!(lookups[0].not_step_last);
+        assert!(!lookups[1].not_step_last);
+        assert_eq!(constraints.not_step_last.len(), 1);
+        assert!(constraints.not_step_last[0]
+            .0
+            .contains("(stored expression)"));
+    }
+
+    #[derive(Clone)]
+    /// SelectWordTestContainer: require(select_word(condition, a, b) == c)
+    struct SelectWordTestContainer<F> {
+        condition: Cell<F>,
+        a: Word32Cell<F>,
+        b: Word32Cell<F>,
+        c: Word32Cell<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for SelectWordTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let condition = cb.query_bool();
+            let a = cb.query_word32();
+            let b = cb.query_word32();
+            let c = cb.query_word32();
+            let selected = cb.select_word(condition.expr(), &a, &b);
+            cb.require_equal_word("select_word(condition, a, b) == c", selected, c.to_word());
+            SelectWordTestContainer { condition, a, b, c }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            self.condition
+                .assign(region, offset, Value::known(F::from(witnesses[0].as_u64())))?;
+            self.a.assign_u256(region, offset, witnesses[1])?;
+            self.b.assign_u256(region, offset, witnesses[2])?;
+            self.c.assign_u256(region, offset, witnesses[3])?;
+            Ok(())
+        }
+    }
+
+    const BOUNDED_WORD_MAX: u64 = u32::MAX as u64;
+
+    #[derive(Clone)]
+    /// BoundedWordTestContainer: a word queried with query_word32_bounded(u32::MAX)
+    struct BoundedWordTestContainer<F> {
+        a: Word32Cell<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for BoundedWordTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_word32_bounded(BOUNDED_WORD_MAX);
+            BoundedWordTestContainer { a }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            self.a.assign_u256(region, 0, witnesses[0])?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_query_word32_bounded() {
+        try_test!(BoundedWordTestContainer<Fr>, [Word::zero()], true);
+        // At the bound
+        try_test!(
+            BoundedWordTestContainer<Fr>,
+            [Word::from(BOUNDED_WORD_MAX)],
+            true
+        );
+        // One above the bound sets the first byte above it
+        try_test!(
+            BoundedWordTestContainer<Fr>,
+            [Word::from(BOUNDED_WORD_MAX) + 1],
+            false
+        );
+        try_test!(BoundedWordTestContainer<Fr>, [Word::one() << 200], false);
+    }
+
+    #[derive(Clone)]
+    /// BytesEndiannessTestContainer: a u64 queried in little and big endian
+    /// bytes and as a U64Cell, all required to be equal to a.
+    struct BytesEndiannessTestContainer<F> {
+        a: Cell<F>,
+        bytes_le: [Cell<F>; 8],
+        bytes_be: [Cell<F>; 8],
+        u64_cell: U64Cell<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for BytesEndiannessTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_cell();
+            let bytes_le = cb.query_bytes_le();
+            let bytes_be = cb.query_bytes_be();
+            let u64_cell = cb.query_u64();
+            cb.require_equal("bytes_le == a", from_bytes::expr(&bytes_le), a.expr());
+            let bytes_be_reversed = bytes_be.iter().rev().cloned().collect::<Vec<_>>();
+            cb.require_equal(
+                "reversed bytes_be == a",
+                from_bytes::expr(&bytes_be_reversed),
+                a.expr(),
+            );
+            cb.require_equal("u64_cell == a", u64_cell.expr(), a.expr());
+            BytesEndiannessTestContainer {
+                a,
+                bytes_le,
+                bytes_be,
+                u64_cell,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            let a = witnesses[0].as_u64();
+            self.a.assign(region, offset, Value::known(F::from(a)))?;
+            for (cell, byte) in self.bytes_le.iter().zip(a.to_le_bytes()) {
+                cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
+            }
+            for (cell, byte) in self.bytes_be.iter().zip(a.to_be_bytes()) {
+                cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
+            }
+            self.u64_cell
+                .assign(region, offset, Some(a.to_le_bytes()))?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_query_bytes_endianness() {
+        try_test!(BytesEndiannessTestContainer<Fr>, [Word::zero()], true);
+        try_test!(
+            BytesEndiannessTestContainer<Fr>,
+            [Word::from(0x0102030405060708u64)],
+            true
+        );
+        try_test!(
+            BytesEndiannessTestContainer<Fr>,
+            [Word::from(u64::MAX)],
+            true
+        );
+    }
+
+    #[test]
+    fn test_select_word() {
+        // Limbs differ in both halves so a swapped limb is caught.
+        let a = Word::from(1) << 128 | Word::from(2);
+        let b = Word::from(3) << 128 | Word::from(4);
+        try_test!(SelectWordTestContainer<Fr>, [Word::one(), a, b, a], true);
+        try_test!(SelectWordTestContainer<Fr>, [Word::zero(), a, b, b], true);
+        try_test!(SelectWordTestContainer<Fr>, [Word::one(), a, b, b], false);
+        try_test!(SelectWordTestContainer<Fr>, [Word::zero(), a, b, a], false);
+        try_test!(
+            SelectWordTestContainer<Fr>,
+            [Word::one(), a, b, Word::from(1) << 128 | Word::from(4)],
+            false
+        );
+    }
 }
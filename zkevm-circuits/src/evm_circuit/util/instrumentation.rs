@@ -6,6 +6,7 @@ use crate::{
 };
 use eth_types::Field;
 use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
 
 type StepSize = Vec<(CellType, ColumnSize)>;
 /// Contains (width, height, num_cells)
@@ -16,6 +17,9 @@ type ColumnSize = (usize, usize, usize);
 pub struct Instrument {
     // States -> Cell Types -> (width, height, num_cells)
     states: Vec<(ExecutionState, StepSize)>,
+    // States -> Names of the stored expressions whose cell is never used
+    #[cfg(test)]
+    unused_stored_expressions: Vec<(ExecutionState, Vec<String>)>,
 }
 
 impl Instrument {
@@ -35,6 +39,41 @@ impl Instrument {
             .collect::<Vec<_>>();
 
         self.states.push((execution_state, sizes));
+
+        #[cfg(test)]
+        {
+            let unused = cb.unused_stored_expressions();
+            if !unused.is_empty() {
+                self.unused_stored_expressions
+                    .push((execution_state, unused));
+            }
+        }
+    }
+
+    /// Returns the stored expressions, per `ExecutionState`, whose cell isn't used by any
+    /// constraint or lookup. These cells are wasted, and usually left behind when refactoring a
+    /// gadget.
+    #[cfg(test)]
+    pub(crate) fn unused_stored_expressions(&self) -> &[(ExecutionState, Vec<String>)] {
+        &self.unused_stored_expressions
+    }
+
+    /// Returns the number of lookups per table done by the gadget of each `ExecutionState`, as
+    /// every lookup takes a cell of its table's lookup columns.
+    pub fn lookup_counts(&self) -> HashMap<ExecutionState, BTreeMap<Table, usize>> {
+        self.states
+            .iter()
+            .map(|(state, sizes)| {
+                let counts = sizes
+                    .iter()
+                    .filter_map(|(cell_type, (_, _, cells))| match cell_type {
+                        CellType::Lookup(table) if *cells > 0 => Some((*table, *cells)),
+                        _ => None,
+                    })
+                    .collect();
+                (*state, counts)
+            })
+            .collect()
     }
 
     /// Disassembles the instrumentation data and returns a collection of
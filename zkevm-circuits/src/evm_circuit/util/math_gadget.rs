@@ -4,6 +4,7 @@ use halo2_proofs::plonk::Expression;
 
 mod abs_word;
 mod add_words;
+mod all_zero_words;
 mod binary_number;
 mod byte_size;
 mod cmp_words;
@@ -24,10 +25,11 @@ mod pair_select;
 mod range_check;
 mod rlp;
 #[cfg(test)]
-mod test_util;
+pub(crate) mod test_util;
 
 pub(crate) use abs_word::AbsWordGadget;
 pub(crate) use add_words::AddWordsGadget;
+pub(crate) use all_zero_words::AllZeroWordsGadget;
 pub(crate) use binary_number::BinaryNumberGadget;
 pub(crate) use byte_size::ByteSizeGadget;
 pub(crate) use cmp_words::CmpWordsGadget;
@@ -48,6 +50,30 @@ pub(crate) use pair_select::PairSelectGadget;
 pub(crate) use range_check::RangeCheckGadget;
 pub(crate) use rlp::ContractCreateGadget;
 
+/// Returns the number of bytes needed to represent every value in `[0, max]`,
+/// which is the smallest `N_BYTES` a range-bounded gadget can be sized with.
+/// At least one byte is always returned.
+pub(crate) const fn bytes_for_max(max: u64) -> usize {
+    let bits = u64::BITS - max.leading_zeros();
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(8) as usize
+    }
+}
+
+/// Compile-time check that `N_BYTES` is enough to hold values up to `MAX`.
+/// Referencing `NBytesFor::<N_BYTES, MAX>::CHECK` fails to compile when the
+/// gadget is under-sized.
+pub(crate) struct NBytesFor<const N_BYTES: usize, const MAX: u64>;
+
+impl<const N_BYTES: usize, const MAX: u64> NBytesFor<N_BYTES, MAX> {
+    pub(crate) const CHECK: () = assert!(
+        N_BYTES >= bytes_for_max(MAX),
+        "N_BYTES is too small for the declared maximum value"
+    );
+}
+
 // This function generates a Lagrange polynomial in the range [start, end) which
 // will be evaluated to 1 when `exp == value`, otherwise 0
 pub(crate) fn generate_lagrange_base_polynomial<
@@ -69,3 +95,23 @@ pub(crate) fn generate_lagrange_base_polynomial<
     }
     numerator * denominator.invert().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_for_max_values() {
+        assert_eq!(bytes_for_max(0), 1);
+        assert_eq!(bytes_for_max(1), 1);
+        assert_eq!(bytes_for_max(255), 1);
+        assert_eq!(bytes_for_max(256), 2);
+        assert_eq!(bytes_for_max(u16::MAX as u64), 2);
+        assert_eq!(bytes_for_max(u32::MAX as u64 + 1), 5);
+        assert_eq!(bytes_for_max(u64::MAX), 8);
+        // Usable in const context.
+        const N: usize = bytes_for_max(1 << 24);
+        assert_eq!(N, 4);
+        let () = NBytesFor::<2, 256>::CHECK;
+    }
+}
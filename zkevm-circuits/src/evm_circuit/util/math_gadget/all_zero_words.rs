@@ -0,0 +1,151 @@
+use crate::{
+    evm_circuit::util::{constraint_builder::EVMConstraintBuilder, CachedRegion},
+    util::word::WordLoHi,
+};
+use eth_types::Field;
+use halo2_proofs::plonk::{Error, Expression};
+
+use super::IsZeroGadget;
+
+/// Returns `1` when all of `words` are zero, and returns `0` otherwise.
+///
+/// Instead of one [`IsZeroWordGadget`](super::IsZeroWordGadget) per word, the
+/// limbs of all words are summed up and checked with a single
+/// [`IsZeroGadget`], so only one cell is used regardless of the number of
+/// words. This requires every limb to be range checked to 128 bits, so that
+/// the sum can't wrap around the field.
+#[derive(Clone, Debug)]
+pub struct AllZeroWordsGadget<F> {
+    is_zero: IsZeroGadget<F>,
+}
+
+#[allow(dead_code)]
+impl<F: Field> AllZeroWordsGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        words: &[&WordLoHi<Expression<F>>],
+    ) -> Self {
+        let sum = words
+            .iter()
+            .map(|word| {
+                let (lo, hi) = word.to_lo_hi();
+                lo + hi
+            })
+            .fold(Expression::Constant(F::from(0)), |acc, limbs| acc + limbs);
+        let is_zero = IsZeroGadget::construct(cb, sum);
+
+        Self { is_zero }
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        self.is_zero.expr()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        words: &[WordLoHi<F>],
+    ) -> Result<F, Error> {
+        let sum = words
+            .iter()
+            .map(|word| {
+                let (lo, hi) = word.to_lo_hi();
+                lo + hi
+            })
+            .fold(F::from(0), |acc, limbs| acc + limbs);
+        self.is_zero.assign(region, offset, sum)
+    }
+
+    pub(crate) fn assign_u256(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        words: &[eth_types::Word],
+    ) -> Result<F, Error> {
+        let words = words
+            .iter()
+            .map(|word| WordLoHi::from(*word))
+            .collect::<Vec<_>>();
+        self.assign(region, offset, &words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::test_util::*, *};
+    use crate::{
+        evm_circuit::util::constraint_builder::ConstrainBuilderCommon,
+        util::{
+            word::{Word32Cell, WordExpr},
+            Expr,
+        },
+    };
+    use eth_types::Word;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[derive(Clone)]
+    /// AllZeroWordsTestContainer: require(all_zero_words(words) == EXPECTED)
+    /// for N words
+    struct AllZeroWordsTestContainer<F, const N: usize, const EXPECTED: bool> {
+        all_zero: AllZeroWordsGadget<F>,
+        words: [Word32Cell<F>; N],
+    }
+
+    impl<F: Field, const N: usize, const EXPECTED: bool> MathGadgetContainer<F>
+        for AllZeroWordsTestContainer<F, N, EXPECTED>
+    {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let words = [(); N].map(|_| cb.query_word32());
+            let word_exprs = words.iter().map(|word| word.to_word()).collect::<Vec<_>>();
+            let all_zero = cb.all_zero_words(&word_exprs.iter().collect::<Vec<_>>());
+            cb.require_equal(
+                "all_zero_words(words) == EXPECTED",
+                all_zero.expr(),
+                EXPECTED.expr(),
+            );
+            AllZeroWordsTestContainer { all_zero, words }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            for (word, value) in self.words.iter().zip(witnesses) {
+                word.assign_u256(region, offset, *value)?;
+            }
+            self.all_zero.assign_u256(region, offset, witnesses)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_all_zero_words() {
+        let zero = Word::zero();
+        try_test!(AllZeroWordsTestContainer<Fr, 3, true>, [zero, zero, zero], true);
+        try_test!(AllZeroWordsTestContainer<Fr, 3, false>, [zero, zero, zero], false);
+    }
+
+    #[test]
+    fn test_all_zero_words_empty() {
+        // No words are all zero
+        try_test!(AllZeroWordsTestContainer<Fr, 0, true>, [Word::zero(); 0], true);
+        try_test!(AllZeroWordsTestContainer<Fr, 0, false>, [Word::zero(); 0], false);
+    }
+
+    #[test]
+    fn test_not_all_zero_words() {
+        let zero = Word::zero();
+        for words in [
+            [Word::one(), zero, zero],
+            [zero, Word::one() << 128, zero],
+            [zero, zero, Word::MAX],
+            [Word::MAX, Word::MAX, Word::MAX],
+        ] {
+            try_test!(AllZeroWordsTestContainer<Fr, 3, false>, words, true);
+            try_test!(AllZeroWordsTestContainer<Fr, 3, true>, words, false);
+        }
+    }
+}
@@ -173,6 +173,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bytesize_0xff_eq_1() {
+        try_test!(ByteSizeGadgetContainer<Fr, 1>, [Word::from(0xff)], true)
+    }
+
+    #[test]
+    fn test_bytesize_0xff_neq_2() {
+        try_test!(ByteSizeGadgetContainer<Fr, 2>, [Word::from(0xff)], false)
+    }
+
     #[test]
     fn test_bytesize_256_eq_2() {
         try_test!(ByteSizeGadgetContainer<Fr, 2>,
@@ -185,4 +195,9 @@ mod tests {
     fn test_bytesize_wordmax_eq_32() {
         try_test!(ByteSizeGadgetContainer<Fr, 32>, [Word::MAX], true)
     }
+
+    #[test]
+    fn test_bytesize_leading_byte_1_eq_32() {
+        try_test!(ByteSizeGadgetContainer<Fr, 32>, [Word::one() << 248], true)
+    }
 }
@@ -56,6 +56,24 @@ impl<F: Field, const N_BYTES: usize> ConstantDivisionGadget<F, N_BYTES> {
         }
     }
 
+    /// Same as `construct`, but checks that `N_BYTES` is large enough for the
+    /// quotient of any numerator up to `max_numerator`.
+    pub(crate) fn construct_for_max(
+        cb: &mut EVMConstraintBuilder<F>,
+        numerator: Expression<F>,
+        denominator: u64,
+        max_numerator: u64,
+    ) -> Self {
+        debug_assert!(
+            N_BYTES >= bytes_for_max(max_numerator / denominator),
+            "ConstantDivisionGadget with {} bytes cannot hold the quotient of {} / {}",
+            N_BYTES,
+            max_numerator,
+            denominator
+        );
+        Self::construct(cb, numerator, denominator)
+    }
+
     pub(crate) fn quotient(&self) -> Expression<F> {
         self.quotient.expr()
     }
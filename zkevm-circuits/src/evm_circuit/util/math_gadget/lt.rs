@@ -1,7 +1,9 @@
 use crate::{
     evm_circuit::util::{
         constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
-        from_bytes, pow_of_two, transpose_val_ret, CachedRegion, Cell,
+        from_bytes,
+        math_gadget::{bytes_for_max, NBytesFor},
+        pow_of_two, transpose_val_ret, CachedRegion, Cell,
     },
     util::Expr,
 };
@@ -48,6 +50,33 @@ impl<F: Field, const N_BYTES: usize> LtGadget<F, N_BYTES> {
         Self { lt, diff, range }
     }
 
+    /// Same as `construct`, but checks that `N_BYTES` is large enough for
+    /// inputs up to `max`.
+    pub(crate) fn construct_for_max(
+        cb: &mut EVMConstraintBuilder<F>,
+        lhs: Expression<F>,
+        rhs: Expression<F>,
+        max: u64,
+    ) -> Self {
+        debug_assert!(
+            N_BYTES >= bytes_for_max(max),
+            "LtGadget with {} bytes cannot hold values up to {}",
+            N_BYTES,
+            max
+        );
+        Self::construct(cb, lhs, rhs)
+    }
+
+    /// Same as `construct_for_max`, but the bound is checked at compile time.
+    pub(crate) fn construct_bounded<const MAX: u64>(
+        cb: &mut EVMConstraintBuilder<F>,
+        lhs: Expression<F>,
+        rhs: Expression<F>,
+    ) -> Self {
+        let () = NBytesFor::<N_BYTES, MAX>::CHECK;
+        Self::construct(cb, lhs, rhs)
+    }
+
     pub(crate) fn expr(&self) -> Expression<F> {
         self.lt.expr()
     }
@@ -167,6 +196,36 @@ mod tests {
         );
     }
 
+    #[derive(Clone)]
+    /// LtGadgetUndersizedContainer: a 1 byte gadget declared for values up to 256
+    struct LtGadgetUndersizedContainer<F> {
+        _lt_gadget: LtGadget<F, 1>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for LtGadgetUndersizedContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_cell();
+            let b = cb.query_cell();
+            let _lt_gadget = LtGadget::<F, 1>::construct_for_max(cb, a.expr(), b.expr(), 256);
+            LtGadgetUndersizedContainer { _lt_gadget }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            _witnesses: &[Word],
+            _region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "cannot hold values up to 256")]
+    fn test_lt_undersized_for_max() {
+        try_test!(LtGadgetUndersizedContainer<Fr>, [Word::from(0)], true);
+    }
+
     #[test]
     fn test_lt_out_of_range() {
         try_test!(
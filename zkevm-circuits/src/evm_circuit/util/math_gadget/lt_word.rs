@@ -64,8 +64,11 @@ impl<F: Field> LtWordGadget<F> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        evm_circuit::util::constraint_builder::ConstrainBuilderCommon,
-        util::word::{Word32Cell, WordExpr},
+        evm_circuit::util::{constraint_builder::ConstrainBuilderCommon, Cell},
+        util::{
+            word::{Word32Cell, WordExpr},
+            Expr,
+        },
     };
 
     use super::{test_util::*, *};
@@ -109,6 +112,78 @@ mod tests {
         }
     }
 
+    #[derive(Clone)]
+    /// WordU64TestContainer: require(a == 256) == eq && require(a < 256) == lt
+    struct WordU64TestContainer<F> {
+        eq_gadget: IsEqualWordGadget<F, WordLoHi<Expression<F>>, WordLoHi<Expression<F>>>,
+        lt_gadget: LtWordGadget<F>,
+        a: Word32Cell<F>,
+        eq: Cell<F>,
+        lt: Cell<F>,
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for WordU64TestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let a = cb.query_word32();
+            let eq = cb.query_cell();
+            let lt = cb.query_cell();
+            let eq_gadget = cb.is_eq_word_u64(&a.to_word(), 256);
+            let lt_gadget = cb.is_lt_word_u64(&a.to_word(), 256);
+            cb.require_equal("a == 256", eq_gadget.expr(), eq.expr());
+            cb.require_equal("a < 256", lt_gadget.expr(), lt.expr());
+            WordU64TestContainer {
+                eq_gadget,
+                lt_gadget,
+                a,
+                eq,
+                lt,
+            }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let a = witnesses[0];
+            let offset = 0;
+
+            self.a.assign_u256(region, offset, a)?;
+            self.eq
+                .assign(region, offset, Value::known(F::from(witnesses[1].as_u64())))?;
+            self.lt
+                .assign(region, offset, Value::known(F::from(witnesses[2].as_u64())))?;
+            self.eq_gadget
+                .assign_u256(region, offset, a, Word::from(256))?;
+            self.lt_gadget.assign(region, offset, a, Word::from(256))?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_word_cmp_u64() {
+        for (a, eq, lt) in [
+            (Word::from(0), 0, 1),
+            (Word::from(255), 0, 1),
+            (Word::from(256), 1, 0),
+            (Word::from(257), 0, 0),
+            (Word::from(256) + WORD_HIGH_MAX, 0, 0),
+            (Word::MAX, 0, 0),
+        ] {
+            try_test!(
+                WordU64TestContainer<Fr>,
+                [a, Word::from(eq), Word::from(lt)],
+                true,
+            );
+        }
+        try_test!(
+            WordU64TestContainer<Fr>,
+            [Word::from(256), Word::from(0), Word::from(0)],
+            false,
+        );
+    }
+
     #[test]
     fn test_ltword_expect() {
         try_test!(
@@ -22,7 +22,9 @@ pub(crate) use halo2_proofs::circuit::{Layouter, Value};
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
     dev::MockProver,
-    plonk::{Circuit, ConstraintSystem, Error, FirstPhase, SecondPhase, Selector, ThirdPhase},
+    plonk::{
+        Circuit, ConstraintSystem, Error, Expression, FirstPhase, SecondPhase, Selector, ThirdPhase,
+    },
 };
 
 pub(crate) const WORD_LOW_MAX: Word = U256([u64::MAX, u64::MAX, 0, 0]);
@@ -67,33 +69,60 @@ where
 
 pub(crate) struct UnitTestMathGadgetBaseCircuit<G> {
     witnesses: Vec<Word>,
+    /// Mock randomness used as the challenges instead of the ones of the prover
+    randomness: Option<u64>,
     _marker: PhantomData<G>,
 }
 
 impl<G> UnitTestMathGadgetBaseCircuit<G> {
-    fn new(witnesses: Vec<Word>) -> Self {
+    fn new(witnesses: Vec<Word>, randomness: Option<u64>) -> Self {
         UnitTestMathGadgetBaseCircuit {
             witnesses,
+            randomness,
             _marker: PhantomData,
         }
     }
 }
 
 impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseCircuit<G> {
-    type Config = (UnitTestMathGadgetBaseCircuitConfig<F, G>, Challenges);
+    type Config = (
+        UnitTestMathGadgetBaseCircuitConfig<F, G>,
+        Option<Challenges>,
+    );
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = ();
+    type Params = Option<u64>;
 
     fn without_witnesses(&self) -> Self {
         UnitTestMathGadgetBaseCircuit {
             witnesses: vec![],
+            randomness: self.randomness,
             _marker: PhantomData,
         }
     }
 
+    fn params(&self) -> Self::Params {
+        self.randomness
+    }
+
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let challenges = Challenges::construct(meta);
-        let challenges_exprs = challenges.exprs(meta);
+        Self::configure_with_params(meta, None)
+    }
+
+    fn configure_with_params(
+        meta: &mut ConstraintSystem<F>,
+        randomness: Self::Params,
+    ) -> Self::Config {
+        let (challenges, challenges_exprs) = match randomness {
+            Some(randomness) => {
+                let randomness = Expression::Constant(F::from(randomness));
+                (None, Challenges::mock(randomness.clone(), randomness))
+            }
+            None => {
+                let challenges = Challenges::construct(meta);
+                let challenges_exprs = challenges.exprs(meta);
+                (Some(challenges), challenges_exprs)
+            }
+        };
 
         let q_usable = meta.selector();
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
@@ -175,7 +204,13 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
         let (config, challenges) = config;
-        let challenge_values = challenges.values(&mut layouter);
+        let challenge_values = match challenges {
+            Some(challenges) => challenges.values(&mut layouter),
+            None => {
+                let randomness = Value::known(F::from(self.randomness.unwrap()));
+                Challenges::mock(randomness, randomness)
+            }
+        };
         layouter.assign_region(
             || "assign test container",
             |mut region| {
@@ -246,9 +281,27 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
 pub(crate) fn test_math_gadget_container<F: Field, G: MathGadgetContainer<F>>(
     witnesses: Vec<Word>,
     expected_success: bool,
+) {
+    run_math_gadget_container::<F, G>(witnesses, None, expected_success)
+}
+
+/// Same as [`test_math_gadget_container`], but replaces the challenges of the
+/// prover with the given mock `randomness`.
+pub(crate) fn test_math_gadget_container_with_randomness<F: Field, G: MathGadgetContainer<F>>(
+    witnesses: Vec<Word>,
+    randomness: u64,
+    expected_success: bool,
+) {
+    run_math_gadget_container::<F, G>(witnesses, Some(randomness), expected_success)
+}
+
+fn run_math_gadget_container<F: Field, G: MathGadgetContainer<F>>(
+    witnesses: Vec<Word>,
+    randomness: Option<u64>,
+    expected_success: bool,
 ) {
     const K: usize = 12;
-    let circuit = UnitTestMathGadgetBaseCircuit::<G>::new(witnesses);
+    let circuit = UnitTestMathGadgetBaseCircuit::<G>::new(witnesses, randomness);
 
     let prover = MockProver::<F>::run(K as u32, &circuit, vec![]).unwrap();
     if expected_success {
@@ -267,3 +320,92 @@ macro_rules! try_test {
 
 #[cfg(test)]
 pub(crate) use try_test;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        evm_circuit::util::{constraint_builder::ConstrainBuilderCommon, Cell},
+        util::Expr,
+    };
+    use eth_types::ToScalar;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    /// Randomness the broken container hardcodes in place of the challenge
+    const HARDCODED_RANDOMNESS: u64 = 0x100;
+
+    #[derive(Clone)]
+    /// RlcTestContainer: require(rlc == bytes[0] * r + bytes[1]), where `r` is
+    /// the keccak input challenge, or the hardcoded randomness when `BROKEN`.
+    struct RlcTestContainer<F, const BROKEN: bool> {
+        bytes: [Cell<F>; 2],
+        rlc: Cell<F>,
+    }
+
+    impl<F: Field, const BROKEN: bool> MathGadgetContainer<F> for RlcTestContainer<F, BROKEN> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let bytes = [cb.query_cell(), cb.query_cell()];
+            let rlc = cb.query_cell_phase2();
+            let randomness = if BROKEN {
+                HARDCODED_RANDOMNESS.expr()
+            } else {
+                cb.challenges().keccak_input()
+            };
+            cb.require_equal(
+                "rlc of the bytes",
+                rlc.expr(),
+                bytes[0].expr() * randomness + bytes[1].expr(),
+            );
+            RlcTestContainer { bytes, rlc }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let offset = 0;
+            let bytes: [F; 2] = [
+                witnesses[0].to_scalar().unwrap(),
+                witnesses[1].to_scalar().unwrap(),
+            ];
+            for (cell, byte) in self.bytes.iter().zip(bytes) {
+                cell.assign(region, offset, Value::known(byte))?;
+            }
+            let rlc = region
+                .challenges()
+                .keccak_input()
+                .map(|randomness| bytes[0] * randomness + bytes[1]);
+            self.rlc.assign(region, offset, rlc)?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rlc_independent_of_randomness() {
+        for randomness in [HARDCODED_RANDOMNESS, 0x12345] {
+            test_math_gadget_container_with_randomness::<Fr, RlcTestContainer<Fr, false>>(
+                vec![Word::from(0xab), Word::from(0xcd)],
+                randomness,
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn test_rlc_with_hardcoded_randomness() {
+        // The broken constraint holds for the randomness it hardcodes, so a
+        // single run can't tell it apart from the correct one.
+        test_math_gadget_container_with_randomness::<Fr, RlcTestContainer<Fr, true>>(
+            vec![Word::from(0xab), Word::from(0xcd)],
+            HARDCODED_RANDOMNESS,
+            true,
+        );
+        test_math_gadget_container_with_randomness::<Fr, RlcTestContainer<Fr, true>>(
+            vec![Word::from(0xab), Word::from(0xcd)],
+            0x12345,
+            false,
+        );
+    }
+}
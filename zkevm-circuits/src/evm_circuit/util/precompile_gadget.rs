@@ -20,7 +20,7 @@ impl<F: Field> PrecompileGadget<F> {
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn construct(
         cb: &mut EVMConstraintBuilder<F>,
-        _is_success: Expression<F>,
+        is_success: Expression<F>,
         callee_address: Expression<F>,
         _caller_id: Expression<F>,
         _cd_offset: Expression<F>,
@@ -40,6 +40,7 @@ impl<F: Field> PrecompileGadget<F> {
         let conditions = vec![
             address.value_equals(PrecompileCalls::Identity),
             address.value_equals(PrecompileCalls::Ecrecover),
+            address.value_equals(PrecompileCalls::Bn128Pairing),
             // match more precompiles
         ]
         .into_iter()
@@ -51,6 +52,7 @@ impl<F: Field> PrecompileGadget<F> {
         let next_states = vec![
             ExecutionState::PrecompileIdentity, // add more precompile execution states
             ExecutionState::PrecompileEcrecover,
+            ExecutionState::PrecompileBn256Pairing,
         ];
 
         let constraints: Vec<BoxedClosure<F>> = vec![
@@ -70,6 +72,14 @@ impl<F: Field> PrecompileGadget<F> {
                     128.expr(),
                 );
             }),
+            Box::new(|cb| {
+                // EcPairing
+                cb.require_equal(
+                    "EcPairing: 32 bytes are returned on success",
+                    precompile_return_length.clone(),
+                    is_success.expr() * 32.expr(),
+                );
+            }),
         ];
 
         cb.constrain_mutually_exclusive_next_step(conditions, next_states, constraints);
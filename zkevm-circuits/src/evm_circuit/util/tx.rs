@@ -1,6 +1,6 @@
 use crate::{
     evm_circuit::{
-        param::N_BYTES_U64,
+        param::{N_BYTES_GAS, N_BYTES_U64},
         step::ExecutionState,
         util::{
             constraint_builder::{
@@ -8,6 +8,7 @@ use crate::{
             },
             math_gadget::{
                 AddWordsGadget, ConstantDivisionGadget, IsEqualGadget, MulWordByU64Gadget,
+                RangeCheckGadget,
             },
             CachedRegion, Cell,
         },
@@ -272,12 +273,13 @@ impl<F: Field> TxDataGadget<F> {
         }
     }
 
-    pub(crate) fn intrinsic_gas(&self) -> Expression<F> {
+    /// Intrinsic gas of the tx, charging `init_code_word_gas` per word of
+    /// init code of a creation.
+    pub(crate) fn intrinsic_gas(&self, init_code_word_gas: u64) -> Expression<F> {
         // Calculate gas cost of init code for EIP-3860.
         let init_code_gas_cost = select::expr(
             self.is_create.expr(),
-            self.call_data_word_length.quotient().expr()
-                * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
+            self.call_data_word_length.quotient().expr() * init_code_word_gas.expr(),
             0.expr(),
         );
 
@@ -348,3 +350,60 @@ impl<F: Field> TxDataGadget<F> {
         Ok(())
     }
 }
+
+/// Gadget for the intrinsic gas charged at the beginning of a tx, which is
+/// - 21000 for a call, or 53000 for a creation,
+/// - 4 per zero and 16 per non-zero calldata byte, precomputed by the tx circuit as
+///   `CallDataGasCost`,
+/// - 2 per word of init code for a creation (EIP-3860), unless
+///   `FeatureConfig::limit_init_code_size` is disabled.
+///
+/// Access list costs (EIP-2930) are not charged, as access list txs aren't supported yet.
+/// The gadget checks the tx gas covers the intrinsic gas, leaving `gas_left` for the execution.
+#[derive(Clone, Debug)]
+pub(crate) struct IntrinsicGasGadget<F> {
+    sufficient_gas_left: RangeCheckGadget<F, N_BYTES_GAS>,
+    gas_left: Expression<F>,
+}
+
+impl<F: Field> IntrinsicGasGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, tx: &TxDataGadget<F>) -> Self {
+        let gas_left = tx.gas.expr() - tx.intrinsic_gas(cb.feature_config.init_code_word_gas());
+        let sufficient_gas_left = RangeCheckGadget::construct(cb, gas_left.clone());
+
+        Self {
+            sufficient_gas_left,
+            gas_left,
+        }
+    }
+
+    /// Gas left for the execution of the tx
+    pub(crate) fn gas_left(&self) -> Expression<F> {
+        self.gas_left.clone()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        tx: &Transaction,
+        init_code_word_gas: u64,
+    ) -> Result<u64, Error> {
+        let intrinsic_gas = Self::intrinsic_gas(tx, init_code_word_gas);
+        self.sufficient_gas_left
+            .assign(region, offset, F::from(tx.gas() - intrinsic_gas))?;
+        Ok(intrinsic_gas)
+    }
+
+    /// Intrinsic gas of the tx, as constrained by [`TxDataGadget::intrinsic_gas`]
+    pub(crate) fn intrinsic_gas(tx: &Transaction, init_code_word_gas: u64) -> u64 {
+        let call_data_word_length = (tx.call_data.len() as u64 + 31) / 32;
+        if tx.is_create() {
+            GasCost::CREATION_TX
+                + tx.call_data_gas_cost()
+                + call_data_word_length * init_code_word_gas
+        } else {
+            GasCost::TX + tx.call_data_gas_cost()
+        }
+    }
+}
@@ -11,7 +11,7 @@ pub use dev::ExpCircuit as TestExpCircuit;
 use crate::{
     evm_circuit::util::constraint_builder::BaseConstraintBuilder,
     table::{ExpTable, LookupTable},
-    util::{Challenges, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, Challenges, SubCircuit, SubCircuitConfig},
     witness::{self, Chunk},
 };
 use bus_mapping::circuit_input_builder::{ExpEvent, ExpStep};
@@ -521,6 +521,13 @@ impl<F: Field> SubCircuit<F> for ExpCircuit<F> {
         11
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let exp_table = ExpTable::construct(meta);
+            ExpCircuitConfig::new(meta, exp_table);
+        })
+    }
+
     fn new_from_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> Self {
         // Hardcoded to pass unit tests for now. In the future, insert:
         // "chunk.fixed_param.max_exp_rows"
@@ -283,6 +283,18 @@ impl PublicData {
         result.chain(all_wd_bytes).collect_vec()
     }
 
+    /// keccak256 digest of the public data bytes
+    pub fn get_rpi_digest(
+        &self,
+        max_txs: usize,
+        max_withdrawals: usize,
+        max_calldata: usize,
+    ) -> Word {
+        let mut keccak = Keccak::default();
+        keccak.update(&self.get_pi_bytes(max_txs, max_withdrawals, max_calldata));
+        Word::from_big_endian(&keccak.digest())
+    }
+
     /// generate public data from validator perspective
     pub fn get_rpi_digest_word<F: Field>(
         &self,
@@ -290,10 +302,7 @@ impl PublicData {
         max_withdrawals: usize,
         max_calldata: usize,
     ) -> WordLoHi<F> {
-        let mut keccak = Keccak::default();
-        keccak.update(&self.get_pi_bytes(max_txs, max_withdrawals, max_calldata));
-        let digest = keccak.digest();
-        WordLoHi::from(Word::from_big_endian(&digest))
+        WordLoHi::from(self.get_rpi_digest(max_txs, max_withdrawals, max_calldata))
     }
 }
 
@@ -32,6 +32,7 @@ use crate::{
     table::{KeccakTable, LookupTable},
     util::{
         cell_manager::{CMFixedHeightStrategy, Cell, CellManager, CellType},
+        count_advice_columns,
         word::{Word32, WordExpr},
         Challenges, SubCircuit, SubCircuitConfig,
     },
@@ -44,7 +45,7 @@ use halo2_proofs::{
     plonk::{Column, ConstraintSystem, Error, Expression, Fixed, TableColumn, VirtualCells},
     poly::Rotation,
 };
-use log::debug;
+use log::{debug, error};
 
 pub(crate) static DEFAULT_CELL_TYPE: CellType = CellType::StoragePhase1;
 
@@ -1005,6 +1006,20 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
         keccak_unusable_rows()
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let keccak_table = KeccakTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            KeccakCircuitConfig::new(
+                meta,
+                KeccakCircuitConfigArgs {
+                    keccak_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     /// The `chunk.fixed_param.keccak_padding` parameter, when enabled, sets
     /// up the circuit to support a fixed number of permutations/keccak_f's,
     /// independently of the permutations required by `inputs`.
@@ -1035,6 +1050,14 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
         challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
+        let required_rows = Self::required_rows(&self.inputs);
+        if self.num_rows > 0 && required_rows > self.num_rows {
+            error!(
+                "keccak inputs need max_keccak_rows >= {}, but max_keccak_rows = {}",
+                required_rows, self.num_rows
+            );
+            return Err(Error::Synthesis);
+        }
         config.load_aux_tables(layouter)?;
         let witness = self.generate_witness(*challenges);
         config.assign(layouter, witness.as_slice())
@@ -1061,6 +1084,28 @@ impl<F: Field> KeccakCircuit<F> {
         }
     }
 
+    /// The number of rows used by each round of a keccak_f, set by the `KECCAK_ROWS`
+    /// environment variable.
+    pub fn rows_per_round() -> usize {
+        get_num_rows_per_round()
+    }
+
+    /// The number of rows assigned to hash an input of `len` bytes: one keccak_f per `RATE`
+    /// bytes plus one for the padding, each taking `NUM_ROUNDS + 1` rounds.
+    pub fn rows_for_input_len(len: usize) -> usize {
+        (len / RATE + 1) * (NUM_ROUNDS + 1) * Self::rows_per_round()
+    }
+
+    /// The minimum `num_rows` (`max_keccak_rows`) needed to hash `inputs`:
+    /// the rows of each input, plus two keccak_f's worth of unusable rows (see `capacity`).
+    pub fn required_rows(inputs: &[Vec<u8>]) -> usize {
+        let input_rows: usize = inputs
+            .iter()
+            .map(|bytes| Self::rows_for_input_len(bytes.len()))
+            .sum();
+        input_rows + 2 * (NUM_ROUNDS + 1) * Self::rows_per_round()
+    }
+
     /// Sets the witness using the data to be hashed
     pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<KeccakRow<F>> {
         multi_keccak(self.inputs.as_slice(), challenges, self.capacity())
@@ -3,8 +3,7 @@ use crate::{
     evm_circuit::util::rlc,
     util::{unusable_rows, word::WordLoHi},
 };
-use bus_mapping::state_db::EMPTY_CODE_HASH_LE;
-use eth_types::{Field, H256, U256};
+use eth_types::{Field, H256};
 use halo2_proofs::{
     dev::{CellValue, MockProver},
     halo2curves::bn256::Fr,
@@ -95,8 +94,7 @@ fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, digests: Vec<String>, success:
         assert_eq!(hash.2, expected.2);
         assert_eq!(hash.3, expected.3);
     }
-    let (lo, hi) =
-        WordLoHi::from(U256::from_little_endian(EMPTY_CODE_HASH_LE.as_slice())).to_lo_hi();
+    let (lo, hi) = WordLoHi::empty_code_hash().to_lo_hi();
 
     // Check that other digests are the digest of the empty message.
     let empty_hash = (F::ZERO, F::ZERO, lo, hi);
@@ -165,6 +163,58 @@ fn variadic_size_check() {
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
 
+#[test]
+fn rows_for_input_len() {
+    let rows_per_keccak_f = (NUM_ROUNDS + 1) * KeccakCircuit::<Fr>::rows_per_round();
+    assert_eq!(
+        KeccakCircuit::<Fr>::rows_for_input_len(0),
+        rows_per_keccak_f
+    );
+    assert_eq!(
+        KeccakCircuit::<Fr>::rows_for_input_len(135),
+        rows_per_keccak_f
+    );
+    assert_eq!(
+        KeccakCircuit::<Fr>::rows_for_input_len(136),
+        2 * rows_per_keccak_f
+    );
+
+    // Cross-check against the rows actually assigned, after the dummy first round
+    let circuit = KeccakCircuit::<Fr>::new(0, vec![(0u8..200).collect::<Vec<_>>()]);
+    let witness = circuit.generate_witness(Challenges::mock(
+        Value::known(Fr::from(123)),
+        Value::known(Fr::from(456)),
+    ));
+    assert_eq!(
+        witness.len(),
+        KeccakCircuit::<Fr>::rows_per_round() + KeccakCircuit::<Fr>::rows_for_input_len(200)
+    );
+}
+
+#[test]
+fn required_rows_exceeds_max_keccak_rows() {
+    let rows_per_keccak_f = (NUM_ROUNDS + 1) * get_num_rows_per_round();
+    let inputs = vec![
+        vec![],
+        (0u8..135).collect::<Vec<_>>(),
+        (0u8..136).collect::<Vec<_>>(),
+    ];
+    // 1 + 1 + 2 keccak_f's, plus 2 unusable ones
+    let required_rows = KeccakCircuit::<Fr>::required_rows(&inputs);
+    assert_eq!(required_rows, 6 * rows_per_keccak_f);
+
+    let k = 14;
+    let circuit = KeccakCircuit::new(required_rows, inputs.clone());
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let circuit = KeccakCircuit::new(required_rows - 1, inputs);
+    assert!(matches!(
+        MockProver::<Fr>::run(k, &circuit, vec![]),
+        Err(Error::Synthesis)
+    ));
+}
+
 #[test]
 fn test_target_part_sizes() {
     // Uniform 8 parts of 8 bits each.
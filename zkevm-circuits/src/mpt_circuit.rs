@@ -23,6 +23,8 @@ mod param;
 mod rlp_gadgets;
 mod start;
 mod storage_leaf;
+#[cfg(test)]
+mod trie;
 /// MPT witness row
 pub mod witness_row;
 
@@ -53,8 +55,10 @@ use crate::{
     util::Challenges,
 };
 
+pub use account_leaf::AccountLeafBytes;
 use extension_branch::ExtensionBranchConfig;
 use param::HASH_WIDTH;
+pub(crate) use rlp_gadgets::{rlp_encode_item, rlp_encode_list, rlp_encode_word};
 
 #[derive(Debug, Eq, PartialEq)]
 pub(crate) enum MPTRegion {
@@ -765,9 +769,17 @@ pub fn load_proof_from_file(path: &str) -> Vec<Node> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+    use crate::util::word::WordLoHi;
+    use halo2_proofs::{
+        dev::{CellValue, MockProver},
+        halo2curves::bn256::Fr,
+    };
     use itertools::Itertools;
-    use std::{fs, ops::Deref, path::PathBuf};
+    use std::{
+        fs,
+        ops::Deref,
+        path::{Path, PathBuf},
+    };
 
     #[test]
     fn test_mpt() {
@@ -783,6 +795,95 @@ mod tests {
             });
     }
 
+    #[test]
+    fn account_creation() {
+        // Value transfer to a fresh address
+        let path = Path::new("src/mpt_circuit/tests/ImplicitlyCreateAccountWithBalance.json");
+        let (num_rows, circuit) = load_witness(path);
+        let degree = 15;
+        let prover = MockProver::<Fr>::run(degree, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify_at_rows(0..num_rows, 0..num_rows), Ok(()));
+
+        let (config, _) = MPTCircuit::<Fr>::configure_with_params(
+            &mut ConstraintSystem::default(),
+            circuit.params(),
+        );
+        let mpt_table = config.mpt_table;
+        let column = |column: Column<Advice>| -> Vec<Fr> {
+            prover
+                .advice_values(column)
+                .iter()
+                .take(num_rows)
+                .map(|cell| match cell {
+                    CellValue::Assigned(value) => *value,
+                    _ => Fr::from(0),
+                })
+                .collect()
+        };
+        let row = column(mpt_table.proof_type)
+            .iter()
+            .position(|proof_type| *proof_type == MPTProofType::BalanceChanged.scalar())
+            .expect("balance update in mpt table");
+        let value = |word: WordLoHi<Column<Advice>>| {
+            WordLoHi::new([column(word.lo())[row], column(word.hi())[row]])
+        };
+
+        // The account doesn't exist in `old_root` and is created in `root` with the new balance
+        assert_eq!(
+            value(mpt_table.old_value).to_lo_hi(),
+            WordLoHi::<Fr>::from(0u64).to_lo_hi()
+        );
+        assert_eq!(
+            value(mpt_table.new_value).to_lo_hi(),
+            WordLoHi::<Fr>::from(7u64).to_lo_hi()
+        );
+        assert_ne!(
+            value(mpt_table.old_root).to_lo_hi(),
+            value(mpt_table.new_root).to_lo_hi()
+        );
+    }
+
+    // Account leaf of `AccountAfterFirstLevel.json`: nonce 0, balance 23, empty
+    // storage and no code
+    const ACCOUNT_LEAF: &str = "f869a03ced34088582b4a78f611c7366195e3e94f9080637f4104bbbd0d07ffb783d49b846f8448017a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+
+    #[test]
+    fn account_leaf_bytes() {
+        let node_bytes = hex::decode(ACCOUNT_LEAF).unwrap();
+        let leaf = AccountLeafBytes::parse(&node_bytes).unwrap();
+        let bytes = |s: &str| hex::decode(s).unwrap();
+
+        assert_eq!(leaf.list_rlp_bytes, bytes("f869"));
+        assert_eq!(
+            leaf.key,
+            bytes("a03ced34088582b4a78f611c7366195e3e94f9080637f4104bbbd0d07ffb783d49")
+        );
+        assert_eq!(leaf.value_rlp_bytes, bytes("b846"));
+        assert_eq!(leaf.value_list_rlp_bytes, bytes("f844"));
+        assert_eq!(leaf.nonce, bytes("80"));
+        assert_eq!(leaf.balance, bytes("17"));
+        assert_eq!(
+            leaf.storage,
+            [vec![0xa0], param::EMPTY_TRIE_HASH.to_vec()].concat()
+        );
+        assert_eq!(
+            leaf.codehash,
+            bytes("a0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+
+        assert_eq!(
+            AccountLeafBytes::parse(&node_bytes[..node_bytes.len() - 1]),
+            None
+        );
+        // A storage leaf `[key, value]`
+        assert_eq!(
+            AccountLeafBytes::parse(&bytes(
+                "e3a120000000000000000000000000000000000000000000000000000000000000000001"
+            )),
+            None
+        );
+    }
+
     #[test]
     fn variadic_size_check() {
         let mut circuits = get_witnesses();
@@ -812,33 +913,34 @@ mod tests {
             .sorted_by(|a, b| a.file_name().cmp(&b.file_name()))
             .map(|f| {
                 let path = f.path();
-                let mut parts = path.to_str().unwrap().split('-');
-                parts.next();
+                let (num_rows, circuit) = load_witness(&path);
+                (path, num_rows, circuit)
+            })
+    }
 
-                let nodes = load_proof_from_file(path.to_str().unwrap());
-                let num_rows: usize = nodes.iter().map(|node| node.values.len()).sum();
+    fn load_witness(path: &Path) -> (usize, MPTCircuit<Fr>) {
+        let nodes = load_proof_from_file(path.to_str().unwrap());
+        let num_rows: usize = nodes.iter().map(|node| node.values.len()).sum();
 
-                let mut keccak_data = vec![];
-                for node in nodes.iter() {
-                    for k in node.keccak_data.iter() {
-                        keccak_data.push(k.deref().clone());
-                    }
-                }
-                let disable_preimage_check = nodes[0].start.clone().unwrap().disable_preimage_check;
-                let degree = 15;
-                let max_nodes = 520;
-                (
-                    path,
-                    num_rows,
-                    MPTCircuit::<Fr> {
-                        nodes,
-                        keccak_data,
-                        degree,
-                        max_nodes,
-                        disable_preimage_check,
-                        _marker: PhantomData,
-                    },
-                )
-            })
+        let mut keccak_data = vec![];
+        for node in nodes.iter() {
+            for k in node.keccak_data.iter() {
+                keccak_data.push(k.deref().clone());
+            }
+        }
+        let disable_preimage_check = nodes[0].start.clone().unwrap().disable_preimage_check;
+        let degree = 15;
+        let max_nodes = 520;
+        (
+            num_rows,
+            MPTCircuit::<Fr> {
+                nodes,
+                keccak_data,
+                degree,
+                max_nodes,
+                disable_preimage_check,
+                _marker: PhantomData,
+            },
+        )
     }
 }
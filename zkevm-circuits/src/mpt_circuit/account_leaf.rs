@@ -8,7 +8,7 @@ use halo2_proofs::{
 use super::{
     helpers::{KeyDataWitness, ListKeyGadget, MainData, ParentDataWitness},
     mod_extension::ModExtensionGadget,
-    rlp_gadgets::RLPItemWitness,
+    rlp_gadgets::{rlp_decode_header, RLPItemWitness},
     witness_row::{AccountRowType, Node},
 };
 use crate::{
@@ -202,6 +202,12 @@ impl<F: Field> AccountLeafConfig<F> {
                 config.main_data.proof_type.expr(),
                 MPTProofType::CodeHashChanged.expr(),
             );
+            let is_account_mod = sum::expr([
+                config.is_nonce_mod.expr(),
+                config.is_balance_mod.expr(),
+                config.is_storage_mod.expr(),
+                config.is_codehash_mod.expr(),
+            ]);
 
             for is_s in [true, false] {
                 ifx! {not!(config.is_mod_extension[is_s.idx()].expr()) => {
@@ -284,11 +290,18 @@ impl<F: Field> AccountLeafConfig<F> {
                         require!((1.expr(), leaf_rlc, rlp_key.rlp_list.num_bytes(), hash.lo(), hash.hi()) =>> @KECCAK);
                     } elsex {
                         // For NonExistingAccountProof prove there is no leaf.
+                        // The same holds for a modification with a placeholder leaf in S: the account is being
+                        // created, so it must not exist in the trie before the modification.
 
                         // When there is only one leaf in the trie, `getProof` will always return this leaf - so we will have
                         // either the required leaf or the wrong leaf, so for NonExistingAccountProof we don't handle this
                         // case here (handled by WrongLeaf gadget).
-                        ifx! {config.is_non_existing_account_proof.expr() => {
+                        let proves_absence = if is_s {
+                            or::expr([config.is_non_existing_account_proof.expr(), is_account_mod.expr()])
+                        } else {
+                            config.is_non_existing_account_proof.expr()
+                        };
+                        ifx! {proves_absence => {
                             ifx! {parent_data[is_s.idx()].is_root.expr() => {
                                 // If leaf is placeholder and the parent is root (no branch above leaf) and the proof is NonExistingStorageProof,
                                 // the trie needs to be empty.
@@ -376,6 +389,16 @@ impl<F: Field> AccountLeafConfig<F> {
                 &cb.key_r.expr(),
             );
 
+            // Account creation: the account is modified, but there is no leaf for it in S. Either
+            // there is nil at the modified position of the parent branch (placeholder
+            // leaf), or another leaf was there and a new branch was added in C
+            // (placeholder branch, handled by the drifted leaf).
+            let is_account_create = is_account_mod.expr()
+                * or::expr([
+                    config.is_placeholder_leaf[true.idx()].expr(),
+                    config.parent_data[true.idx()].is_placeholder.expr(),
+                ]);
+
             // Anything following this node is below the account
             // TODO(Brecht): For non-existing accounts it should be impossible to prove
             // storage leaves unless it's also a non-existing proof?
@@ -427,6 +450,23 @@ impl<F: Field> AccountLeafConfig<F> {
                         require!(codehash[false.idx()] => codehash[true.idx()]);
                     }}
                 }}
+                ifx! {is_account_create => {
+                    // A new account is empty except for the field being modified
+                    ifx!{not!(config.is_nonce_mod) => {
+                        require!(nonce[false.idx()] => WordLoHi::zero());
+                    }}
+                    ifx!{not!(config.is_balance_mod) => {
+                        require!(balance[false.idx()] => WordLoHi::zero());
+                    }}
+                    ifx!{not!(config.is_storage_mod) => {
+                        let empty_trie_hash = WordLoHi::<F>::from(U256::from_big_endian(&EMPTY_TRIE_HASH));
+                        require!(storage[false.idx()] => empty_trie_hash.map(Expression::Constant));
+                    }}
+                    ifx!{not!(config.is_codehash_mod) => {
+                        let empty_code_hash = WordLoHi::<F>::empty_code_hash();
+                        require!(codehash[false.idx()] => empty_code_hash.map(Expression::Constant));
+                    }}
+                }}
             }}
             ifx! {config.is_non_existing_account_proof => {
                 // For non-existing proofs the tree needs to remain the same
@@ -462,6 +502,7 @@ impl<F: Field> AccountLeafConfig<F> {
 
             ifx! {not!(config.parent_data[false.idx()].is_placeholder) => {
                 ifx! {not!(config.is_non_existing_account_proof) => {
+                    // The old value of a created account is 0, `old_root` doesn't contain the account.
                     ctx.mpt_table.constrain(
                         meta,
                         &mut cb.base,
@@ -471,7 +512,10 @@ impl<F: Field> AccountLeafConfig<F> {
                         config.main_data.new_root.expr(),
                         config.main_data.old_root.expr(),
                         WordLoHi::<Expression<F>>::new([new_value_lo, new_value_hi]),
-                        WordLoHi::<Expression<F>>::new([old_value_lo.clone(), old_value_hi.clone()]),
+                        WordLoHi::<Expression<F>>::new([
+                            old_value_lo.clone() * not!(is_account_create),
+                            old_value_hi.clone() * not!(is_account_create),
+                        ]),
                     );
                 } elsex {
                     // Non-existing proof doesn't have the value set to 0 in the case of a wrong leaf - we set it to 0
@@ -560,6 +604,7 @@ impl<F: Field> AccountLeafConfig<F> {
         let mut codehash = vec![WordLoHi::zero(); 2];
         let mut key_data = vec![KeyDataWitness::default(); 2];
         let mut parent_data = vec![ParentDataWitness::default(); 2];
+        let mut is_placeholder_leaf = [false; 2];
         for is_s in [true, false] {
             self.is_mod_extension[is_s.idx()].assign(
                 region,
@@ -595,7 +640,7 @@ impl<F: Field> AccountLeafConfig<F> {
                 0,
             )?;
 
-            self.is_placeholder_leaf[is_s.idx()].assign(
+            is_placeholder_leaf[is_s.idx()] = self.is_placeholder_leaf[is_s.idx()].assign(
                 region,
                 offset,
                 parent_data[is_s.idx()].hash,
@@ -682,6 +727,9 @@ impl<F: Field> AccountLeafConfig<F> {
             main_data.proof_type.scalar(),
             MPTProofType::CodeHashChanged.scalar(),
         )? == true.scalar();
+        let is_account_create =
+            (is_nonce_mod || is_balance_mod || is_storage_mod || is_codehash_mod)
+                && (is_placeholder_leaf[true.idx()] || parent_data[true.idx()].is_placeholder);
         // Drifted leaf handling
         self.drifted.assign(
             region,
@@ -750,6 +798,8 @@ impl<F: Field> AccountLeafConfig<F> {
         } else if is_non_existing_proof {
             new_value = WordLoHi::zero();
             old_value = WordLoHi::zero();
+        } else if is_account_create {
+            old_value = WordLoHi::zero();
         }
         mpt_config.mpt_table.assign_cached(
             region,
@@ -770,3 +820,74 @@ impl<F: Field> AccountLeafConfig<F> {
         Ok(())
     }
 }
+
+/// An account leaf `[key, rlp([nonce, balance, storage_root, code_hash])]` split
+/// into the RLP bytes and RLP items of an account [`Node`], to build MPT test
+/// inputs from the RLP encoding of a leaf.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountLeafBytes {
+    /// RLP header of the leaf list
+    pub list_rlp_bytes: Vec<u8>,
+    /// Key RLP item
+    pub key: Vec<u8>,
+    /// RLP header of the value string
+    pub value_rlp_bytes: Vec<u8>,
+    /// RLP header of the value list
+    pub value_list_rlp_bytes: Vec<u8>,
+    /// Nonce RLP item
+    pub nonce: Vec<u8>,
+    /// Balance RLP item
+    pub balance: Vec<u8>,
+    /// Storage root RLP item
+    pub storage: Vec<u8>,
+    /// Code hash RLP item
+    pub codehash: Vec<u8>,
+}
+
+impl AccountLeafBytes {
+    /// Splits the RLP encoding of an account leaf, or returns `None` if the
+    /// bytes don't encode one.
+    pub fn parse(node_bytes: &[u8]) -> Option<Self> {
+        let (list_rlp_bytes, mut rest) = split_rlp_header(node_bytes, true)?;
+        let key = take_rlp_string(&mut rest)?;
+        let (value_rlp_bytes, rest) = split_rlp_header(rest, false)?;
+        let (value_list_rlp_bytes, mut rest) = split_rlp_header(rest, true)?;
+        let nonce = take_rlp_string(&mut rest)?;
+        let balance = take_rlp_string(&mut rest)?;
+        let storage = take_rlp_string(&mut rest)?;
+        let codehash = take_rlp_string(&mut rest)?;
+        if !rest.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            list_rlp_bytes: list_rlp_bytes.to_vec(),
+            key,
+            value_rlp_bytes: value_rlp_bytes.to_vec(),
+            value_list_rlp_bytes: value_list_rlp_bytes.to_vec(),
+            nonce,
+            balance,
+            storage,
+            codehash,
+        })
+    }
+}
+
+/// Splits `bytes` into the header of the RLP item filling all of it and its
+/// payload.
+fn split_rlp_header(bytes: &[u8], is_list: bool) -> Option<(&[u8], &[u8])> {
+    let (is_item_list, num_header_bytes, len) = rlp_decode_header(bytes)?;
+    (is_item_list == is_list && num_header_bytes > 0 && num_header_bytes + len == bytes.len())
+        .then(|| bytes.split_at(num_header_bytes))
+}
+
+/// Takes the RLP string item at the start of `bytes`, header included.
+fn take_rlp_string(bytes: &mut &[u8]) -> Option<Vec<u8>> {
+    let (is_list, num_header_bytes, len) = rlp_decode_header(bytes)?;
+    if is_list {
+        return None;
+    }
+    let (item, rest) = bytes.split_at(num_header_bytes + len);
+    *bytes = rest;
+    Some(item.to_vec())
+}
@@ -1100,17 +1100,14 @@ impl<F: Field> IsPlaceholderLeafGadget<F> {
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
         hash: WordLoHi<F>,
-    ) -> Result<(), Error> {
+    ) -> Result<bool, Error> {
         let empty_hash = WordLoHi::<F>::from(U256::from_big_endian(&EMPTY_TRIE_HASH));
+        let nil_hash = WordLoHi::<F>::from(U256::zero());
         self.is_empty_trie
             .assign(region, offset, hash, empty_hash)?;
-        self.is_nil_in_branch_at_mod_index.assign(
-            region,
-            offset,
-            hash,
-            WordLoHi::<F>::from(U256::zero()),
-        )?;
-        Ok(())
+        self.is_nil_in_branch_at_mod_index
+            .assign(region, offset, hash, nil_hash)?;
+        Ok(hash.to_lo_hi() == empty_hash.to_lo_hi() || hash.to_lo_hi() == nil_hash.to_lo_hi())
     }
 }
 
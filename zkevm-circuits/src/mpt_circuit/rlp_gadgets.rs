@@ -14,7 +14,7 @@ use crate::{
     },
     util::{word::WordLoHi, Expr},
 };
-use eth_types::Field;
+use eth_types::{Field, Word};
 use gadgets::util::{not, pow, Scalar};
 use halo2_proofs::plonk::{Error, Expression, VirtualCells};
 
@@ -57,6 +57,76 @@ pub(crate) fn decode_rlp(byte: u8) -> (bool, bool, bool, bool) {
     }
 }
 
+/// RLP encodes a byte string, the encoding decoded by [`RLPValueGadget`].
+pub(crate) fn rlp_encode_item(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [byte] if *byte < RLP_SHORT => vec![*byte],
+        _ => rlp_encode_payload(bytes, RLP_SHORT, RLP_LONG),
+    }
+}
+
+/// RLP encodes a list of already RLP encoded items, the encoding decoded by
+/// [`RLPListGadget`].
+pub(crate) fn rlp_encode_list<T: AsRef<[u8]>>(items: &[T]) -> Vec<u8> {
+    let payload = items
+        .iter()
+        .flat_map(|item| item.as_ref().iter().cloned())
+        .collect::<Vec<_>>();
+    rlp_encode_payload(&payload, RLP_LIST_SHORT, RLP_LIST_LONG)
+}
+
+/// RLP encodes an integer, as its big-endian bytes without leading zeros.
+pub(crate) fn rlp_encode_word(value: Word) -> Vec<u8> {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    rlp_encode_item(&bytes[bytes.iter().take_while(|byte| **byte == 0).count()..])
+}
+
+// Prefixes the payload with its length, in the short form up to 55 bytes and
+// in the long form (the big-endian length prefixed by its byte count) above.
+fn rlp_encode_payload(payload: &[u8], short: u8, long: u8) -> Vec<u8> {
+    let max_short_len = (long - short) as usize;
+    let mut out = if payload.len() <= max_short_len {
+        vec![short + payload.len() as u8]
+    } else {
+        let len = payload.len().to_be_bytes();
+        let len = &len[len.iter().take_while(|byte| **byte == 0).count()..];
+        let mut out = vec![long + len.len() as u8];
+        out.extend_from_slice(len);
+        out
+    };
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Decodes the RLP header at the start of `bytes` into whether it's a list, the
+/// number of header bytes and the number of payload bytes.
+pub(crate) fn rlp_decode_header(bytes: &[u8]) -> Option<(bool, usize, usize)> {
+    let prefix = *bytes.first()?;
+    let long_len = |num_len_bytes: u8| -> Option<usize> {
+        let len_bytes = bytes.get(1..1 + num_len_bytes as usize)?;
+        Some(
+            len_bytes
+                .iter()
+                .fold(0usize, |len, byte| (len << 8) | *byte as usize),
+        )
+    };
+    let (is_list, num_header_bytes, len) = if prefix < RLP_SHORT {
+        (false, 0, 1)
+    } else if prefix <= RLP_LONG {
+        (false, 1, (prefix - RLP_SHORT) as usize)
+    } else if prefix < RLP_LIST_SHORT {
+        let num_len_bytes = prefix - RLP_LONG;
+        (false, 1 + num_len_bytes as usize, long_len(num_len_bytes)?)
+    } else if prefix <= RLP_LIST_LONG {
+        (true, 1, (prefix - RLP_LIST_SHORT) as usize)
+    } else {
+        let num_len_bytes = prefix - RLP_LIST_LONG;
+        (true, 1 + num_len_bytes as usize, long_len(num_len_bytes)?)
+    };
+    (bytes.len() >= num_header_bytes + len).then_some((is_list, num_header_bytes, len))
+}
+
 #[derive(Clone, Debug, Default)]
 pub(crate) struct RLPListGadget<F> {
     pub(crate) is_short: Cell<F>,
@@ -893,3 +963,77 @@ impl RLPItemWitness {
         WordLoHi::new([lo, hi])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::utils::rlp::RlpStream;
+
+    #[test]
+    fn rlp_encode_item_vectors() {
+        // Empty string
+        assert_eq!(rlp_encode_item(&[]), vec![0x80]);
+        // Single bytes below 0x80 are their own encoding
+        assert_eq!(rlp_encode_item(&[0x00]), vec![0x00]);
+        assert_eq!(rlp_encode_item(&[0x7f]), vec![0x7f]);
+        assert_eq!(rlp_encode_item(&[0x80]), vec![0x81, 0x80]);
+        assert_eq!(rlp_encode_item(b"dog"), vec![0x83, b'd', b'o', b'g']);
+
+        // 55 bytes is the longest short string, 56 bytes the shortest long one
+        let bytes = [0xaa; 56];
+        assert_eq!(
+            rlp_encode_item(&bytes[..55]),
+            [&[0xb7][..], &bytes[..55]].concat()
+        );
+        assert_eq!(
+            rlp_encode_item(&bytes),
+            [&[0xb8, 56][..], &bytes[..]].concat()
+        );
+
+        let bytes = [0xaa; 1024];
+        assert_eq!(
+            rlp_encode_item(&bytes),
+            [&[0xb9, 0x04, 0x00][..], &bytes[..]].concat()
+        );
+    }
+
+    #[test]
+    fn rlp_encode_word_vectors() {
+        // Integers are encoded without their leading zeros
+        assert_eq!(rlp_encode_word(Word::zero()), vec![0x80]);
+        assert_eq!(rlp_encode_word(Word::from(0x7f)), vec![0x7f]);
+        assert_eq!(rlp_encode_word(Word::from(0x400)), vec![0x82, 0x04, 0x00]);
+    }
+
+    #[test]
+    fn rlp_encode_list_vectors() {
+        assert_eq!(rlp_encode_list::<Vec<u8>>(&[]), vec![0xc0]);
+        assert_eq!(
+            rlp_encode_list(&[rlp_encode_item(b"cat"), rlp_encode_item(b"dog")]),
+            vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g']
+        );
+
+        // A 56 bytes payload needs the long form
+        let item = rlp_encode_item(&[0xaa; 55]);
+        assert_eq!(item.len(), 56);
+        assert_eq!(
+            rlp_encode_list(&[&item]),
+            [&[0xf8, 56][..], item.as_slice()].concat()
+        );
+    }
+
+    #[test]
+    fn rlp_encode_matches_rlp_stream() {
+        for len in [0, 1, 2, 31, 32, 55, 56, 255, 256] {
+            let bytes = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&bytes).append(&bytes);
+            assert_eq!(
+                rlp_encode_list(&[rlp_encode_item(&bytes), rlp_encode_item(&bytes)]),
+                stream.out().to_vec(),
+                "length {}",
+                len
+            );
+        }
+    }
+}
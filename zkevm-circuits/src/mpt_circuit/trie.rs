@@ -0,0 +1,163 @@
+//! Minimal account trie, to compute the roots expected by MPT test fixtures
+//! without a node client.
+
+use super::{
+    param::EMPTY_TRIE_HASH,
+    rlp_gadgets::{rlp_encode_item, rlp_encode_list, rlp_encode_word},
+};
+use crate::util::word::WordLoHi;
+use bus_mapping::state_db::CodeDB;
+use eth_types::{Address, Field, Word, H256};
+use ethers_core::utils::keccak256;
+
+/// The fields of an account leaf
+#[derive(Clone, Debug)]
+pub(crate) struct AccountData {
+    pub(crate) nonce: Word,
+    pub(crate) balance: Word,
+    pub(crate) storage_root: H256,
+    pub(crate) code_hash: H256,
+}
+
+impl Default for AccountData {
+    /// An empty account
+    fn default() -> Self {
+        Self {
+            nonce: Word::zero(),
+            balance: Word::zero(),
+            storage_root: H256(EMPTY_TRIE_HASH),
+            code_hash: CodeDB::empty_code_hash(),
+        }
+    }
+}
+
+/// Compute the root of the account trie containing `leaves` (with distinct
+/// addresses), as stored in `MainData`. The whole trie is rebuilt in memory,
+/// so this is only meant for the handful of accounts of a test.
+pub(crate) fn compute_root<F: Field>(leaves: &[(Address, AccountData)]) -> WordLoHi<F> {
+    let mut entries = leaves
+        .iter()
+        .map(|(address, account)| {
+            let key = keccak256(address.as_bytes())
+                .iter()
+                .flat_map(|byte| [byte >> 4, byte & 0xf])
+                .collect::<Vec<_>>();
+            let leaf = rlp_encode_list(&[
+                rlp_encode_word(account.nonce),
+                rlp_encode_word(account.balance),
+                rlp_encode_item(account.storage_root.as_bytes()),
+                rlp_encode_item(account.code_hash.as_bytes()),
+            ]);
+            (key, leaf)
+        })
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    let root = if entries.is_empty() {
+        H256(EMPTY_TRIE_HASH)
+    } else {
+        H256(keccak256(encode_node(&entries, 0)))
+    };
+    WordLoHi::from(root)
+}
+
+/// RLP encode the node holding the sorted `entries` (key nibbles, value), of
+/// which the first `depth` nibbles are already consumed by the parents.
+fn encode_node(entries: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    if let [(key, value)] = entries {
+        return rlp_encode_list(&[
+            rlp_encode_item(&hex_prefix(&key[depth..], true)),
+            rlp_encode_item(value),
+        ]);
+    }
+
+    // The keys are sorted, so the prefix shared by all is the one of the first and the last
+    let (first, last) = (&entries[0].0, &entries[entries.len() - 1].0);
+    let prefix_len = first[depth..]
+        .iter()
+        .zip(last[depth..].iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix_len > 0 {
+        return rlp_encode_list(&[
+            rlp_encode_item(&hex_prefix(&first[depth..depth + prefix_len], false)),
+            encode_child(encode_node(entries, depth + prefix_len)),
+        ]);
+    }
+
+    let mut items = (0..16)
+        .map(|nibble| {
+            let children = entries
+                .iter()
+                .filter(|(key, _)| key[depth] == nibble)
+                .cloned()
+                .collect::<Vec<_>>();
+            if children.is_empty() {
+                rlp_encode_item(&[])
+            } else {
+                encode_child(encode_node(&children, depth + 1))
+            }
+        })
+        .collect::<Vec<_>>();
+    // No value in the branches of the account trie
+    items.push(rlp_encode_item(&[]));
+    rlp_encode_list(&items)
+}
+
+/// Nodes shorter than 32 bytes are embedded in their parent, the others are
+/// referenced by hash.
+fn encode_child(node: Vec<u8>) -> Vec<u8> {
+    if node.len() < 32 {
+        node
+    } else {
+        rlp_encode_item(&keccak256(node))
+    }
+}
+
+/// Compact (hex-prefix) encoding of a nibble path
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = 2 * is_leaf as u8 + nibbles.len() as u8 % 2;
+    let mut bytes = vec![];
+    let rest = if nibbles.len() % 2 == 1 {
+        bytes.push((flag << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        bytes.push(flag << 4);
+        nibbles
+    };
+    bytes.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpt_circuit::load_proof_from_file;
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    #[test]
+    fn compute_root_matches_fixture() {
+        assert_eq!(
+            compute_root::<Fr>(&[]).to_lo_hi(),
+            WordLoHi::<Fr>::from(H256(EMPTY_TRIE_HASH)).to_lo_hi()
+        );
+
+        // The account is the only leaf of the trie, before and after the nonce update.
+        let nodes = load_proof_from_file("src/mpt_circuit/tests/AccountInFirstLevel.json");
+        let root =
+            |idx: usize| WordLoHi::<Fr>::from(H256::from_slice(&nodes[0].values[idx][1..33]));
+        let address = Address::from_low_u64_be(0x21);
+        assert_eq!(
+            compute_root::<Fr>(&[(address, AccountData::default())]).to_lo_hi(),
+            root(0).to_lo_hi()
+        );
+        let account = AccountData {
+            nonce: Word::one(),
+            ..Default::default()
+        };
+        assert_eq!(
+            compute_root::<Fr>(&[(address, account)]).to_lo_hi(),
+            root(1).to_lo_hi()
+        );
+    }
+}
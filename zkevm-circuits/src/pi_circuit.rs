@@ -10,8 +10,8 @@ use std::{cmp::min, iter, marker::PhantomData};
 #[cfg(feature = "test-circuits")]
 pub use PiCircuit as TestPiCircuit;
 
-use bus_mapping::circuit_input_builder::Withdrawal;
-use eth_types::{self, Field, ToLittleEndian};
+use bus_mapping::circuit_input_builder::{FixedCParams, Withdrawal};
+use eth_types::{self, Field, ToLittleEndian, Word};
 use halo2_proofs::plonk::{Expression, Instance, SecondPhase};
 use itertools::Itertools;
 use param::*;
@@ -33,7 +33,7 @@ use crate::{
     },
     table::{BlockTable, KeccakTable, LookupTable, TxFieldTag, TxTable, WdTable},
     tx_circuit::TX_LEN,
-    util::{word::WordLoHi, Challenges, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, word::WordLoHi, Challenges, SubCircuit, SubCircuitConfig},
     witness::{self, Chunk},
 };
 use gadgets::{
@@ -1449,6 +1449,13 @@ impl<F: Field> PiCircuit<F> {
             _marker: PhantomData,
         }
     }
+
+    /// The keccak digest of the public data that the circuit constrains.  The
+    /// instance exposes it as its lo and hi 128-bit limbs.
+    pub fn public_input_hash(&self) -> Word {
+        self.public_data
+            .get_rpi_digest(self.max_txs, self.max_withdrawals, self.max_calldata)
+    }
 }
 
 impl<F: Field> SubCircuit<F> for PiCircuit<F> {
@@ -1460,6 +1467,30 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         6
     }
 
+    fn num_advice_columns() -> usize {
+        let params = FixedCParams::default();
+        count_advice_columns::<F>(|meta| {
+            let block_table = BlockTable::construct(meta);
+            let tx_table = TxTable::construct(meta);
+            let wd_table = WdTable::construct(meta);
+            let keccak_table = KeccakTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            PiCircuitConfig::new(
+                meta,
+                PiCircuitConfigArgs {
+                    max_txs: params.max_txs,
+                    max_withdrawals: params.max_withdrawals,
+                    max_calldata: params.max_calldata,
+                    block_table,
+                    tx_table,
+                    wd_table,
+                    keccak_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     fn new_from_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> Self {
         let public_data = public_data_convert(block);
         PiCircuit::new(
@@ -1485,15 +1516,15 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
     /// Compute the public inputs for this circuit.
     fn instance(&self) -> Vec<Vec<F>> {
-        let rpi_digest_byte_field = self.public_data.get_rpi_digest_word(
-            self.max_txs,
-            self.max_withdrawals,
-            self.max_calldata,
-        );
+        let rpi_digest_byte_field = WordLoHi::<F>::from(self.public_input_hash());
 
         vec![vec![rpi_digest_byte_field.lo(), rpi_digest_byte_field.hi()]]
     }
 
+    fn instance_layout() -> Vec<usize> {
+        vec![2]
+    }
+
     /// Make the assignments to the PiCircuit
     fn synthesize_sub(
         &self,
@@ -3,14 +3,14 @@ use std::collections::HashMap;
 use crate::{
     pi_circuit::dev::PiCircuitParams,
     util::unusable_rows,
-    witness::{block_convert, chunk_convert},
+    witness::{block_convert, Chunk},
 };
 
 use super::*;
 use bus_mapping::{
     circuit_input_builder::FixedCParams, mock::BlockData, state_db::EMPTY_CODE_HASH_LE,
 };
-use eth_types::{bytecode, geth_types::GethData, Address, Word, H160, H256};
+use eth_types::{bytecode, geth_types::GethData, keccak256, Address, Word, H160, H256};
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
@@ -67,6 +67,34 @@ fn test_default_pi() {
     );
 }
 
+#[test]
+fn public_input_hash_matches_instance() {
+    let (max_txs, max_withdrawals, max_calldata) = (2, 2, 8);
+    let mut public_data = PublicData::default();
+    public_data.chain_id = *MOCK_CHAIN_ID;
+    public_data.block_constants.coinbase = H160([1u8; 20]);
+    public_data
+        .transactions
+        .push(CORRECT_MOCK_TXS[0].clone().into());
+
+    let circuit = PiCircuit::<Fr>::new(max_txs, max_withdrawals, max_calldata, public_data);
+    let hash = circuit.public_input_hash();
+    assert_eq!(
+        hash,
+        Word::from_big_endian(&keccak256(&circuit.public_data.get_pi_bytes(
+            max_txs,
+            max_withdrawals,
+            max_calldata
+        )))
+    );
+
+    let hash_lo_hi = WordLoHi::<Fr>::from(hash);
+    let instance = vec![vec![hash_lo_hi.lo(), hash_lo_hi.hi()]];
+    assert_eq!(circuit.instance(), instance);
+    let prover = MockProver::run(17, &circuit, instance).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
 #[test]
 fn test_simple_pi() {
     let max_txs = 8;
@@ -149,7 +177,7 @@ fn test_1tx_1maxtx() {
 
     block.sign(&wallets);
     let block = block_convert(&builder).unwrap();
-    let chunk = chunk_convert(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     // MAX_TXS, MAX_TXS align with `CircuitsParams`
     let circuit = PiCircuit::<Fr>::new_from_block(&block, &chunk);
     let public_inputs = circuit.instance();
@@ -230,7 +258,7 @@ fn test_1wd_1wdmax() {
         .unwrap();
 
     let block = block_convert(&builder).unwrap();
-    let chunk = chunk_convert(&block, &builder).unwrap().remove(0);
+    let chunk = Chunk::single(&block, builder.circuits_params);
     // MAX_TXS, MAX_TXS align with `CircuitsParams`
     let circuit = PiCircuit::<Fr>::new_from_block(&block, &chunk);
     let public_inputs = circuit.instance();
@@ -23,7 +23,7 @@ use crate::{
         utils::{calc_required_advices, FpChip},
     },
     table::{KeccakTable, SigTable},
-    util::{word::WordLoHi, Challenges, Expr, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, word::WordLoHi, Challenges, Expr, SubCircuit, SubCircuitConfig},
 };
 use eth_types::{
     self,
@@ -222,6 +222,22 @@ impl<F: Field + halo2_base::utils::ScalarField> SubCircuit<F> for SigCircuit<F>
         .unwrap()
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let keccak_table = KeccakTable::construct(meta);
+            let sig_table = SigTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            SigCircuitConfig::new(
+                meta,
+                SigCircuitConfigArgs {
+                    _keccak_table: keccak_table,
+                    sig_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     fn synthesize_sub(
         &self,
         config: &Self::Config,
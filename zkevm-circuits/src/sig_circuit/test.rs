@@ -15,6 +15,7 @@ use std::marker::PhantomData;
 use super::utils::LOG_TOTAL_NUM_ROWS;
 use crate::sig_circuit::utils::MAX_NUM_SIG;
 use eth_types::{
+    geth_types::Transaction,
     sign_types::{biguint_to_32bytes_le, recover_pk, SECP256K1_Q},
     word, ToBigEndian, ToLittleEndian, Word,
 };
@@ -178,6 +179,27 @@ fn sign_verify_nonzero_msg_hash() {
     log::debug!("end of testing for msg_hash = 1");
 }
 
+#[test]
+fn sign_verify_tx_high_s() {
+    // (r, q - s) with the flipped recovery id is the malleable twin of the
+    // signature of a mock tx, rejected by EIP-2.
+    let chain_id = mock::MOCK_CHAIN_ID.as_u64();
+    let mut tx = mock::CORRECT_MOCK_TXS[0].clone();
+    let q = Word::from_big_endian(&SECP256K1_Q.to_bytes_be());
+    let v = tx.v.unwrap().as_u64();
+    let recovery_id = v - 35 - chain_id * 2;
+    tx.sig_data((
+        v - recovery_id + (1 - recovery_id),
+        tx.r.unwrap(),
+        q - tx.s.unwrap(),
+    ));
+    let sign_data = Transaction::from(tx).sign_data(chain_id).unwrap();
+
+    // The sig table entry of the tx is invalid, which the circuit proves.
+    assert!(sign_data.get_addr().is_zero());
+    run(LOG_TOTAL_NUM_ROWS as u32, 1, vec![sign_data]);
+}
+
 #[test]
 fn test_sign_verify() {
     let max_sigs = [1, 4];
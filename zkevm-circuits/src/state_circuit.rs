@@ -9,7 +9,7 @@ mod param;
 mod dev;
 #[cfg(test)]
 mod test;
-use bus_mapping::operation::Target;
+use bus_mapping::{circuit_input_builder::FixedCParams, operation::Target};
 #[cfg(feature = "test-circuits")]
 pub use dev::StateCircuit as TestStateCircuit;
 
@@ -19,9 +19,13 @@ use self::{
 };
 use crate::{
     table::{AccountFieldTag, LookupTable, MPTProofType, MptTable, RwTable, UXTable},
-    util::{word::WordLoHi, Challenges, Expr, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, word::WordLoHi, Challenges, Expr, SubCircuit, SubCircuitConfig},
     witness::{
         self,
+        chunk::{
+            get_permutation_fingerprint_of_rwmap, get_permutation_fingerprint_of_rwvec,
+            get_permutation_randomness,
+        },
         rw::{RwFingerprints, ToVec},
         Chunk, MptUpdates, Rw, RwMap,
     },
@@ -495,6 +499,28 @@ impl<F: Field> StateCircuit<F> {
             _marker: PhantomData,
         }
     }
+
+    /// make a new state circuit directly from rw rows, without a block.  The rows are sorted
+    /// by address and the table is padded up to `n_rows`.
+    pub fn new_from_rws(rws: Vec<Rw>, n_rows: usize) -> Self {
+        Self::new(&new_chunk_from_rw_map(&RwMap::from(rws), n_rows, None))
+    }
+
+    /// Start the table from the last row of a previous chunk instead of `Rw::Start`, e.g. to
+    /// test a middle chunk standalone.  The fingerprints are recomputed accordingly.
+    pub fn with_prev_chunk_last_rw(mut self, prev_chunk_last_rw: Rw) -> Result<Self, String> {
+        RwMap::validate_padding_start_rw(&self.rows, &prev_chunk_last_rw)?;
+        self.rw_fingerprints = get_permutation_fingerprint_of_rwvec(
+            &self.rows,
+            self.n_rows,
+            self.permu_alpha,
+            self.permu_gamma,
+            self.rw_fingerprints.prev_mul_acc,
+            Some(prev_chunk_last_rw),
+        );
+        self.prev_chunk_last_rw = Some(prev_chunk_last_rw);
+        Ok(self)
+    }
 }
 
 impl<F: Field> SubCircuit<F> for StateCircuit<F> {
@@ -510,6 +536,28 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
         6
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let rw_table = RwTable::construct(meta);
+            let mpt_table = MptTable::construct(meta);
+            let u8_table = UXTable::construct(meta);
+            let u10_table = UXTable::construct(meta);
+            let u16_table = UXTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            StateCircuitConfig::new(
+                meta,
+                StateCircuitConfigArgs {
+                    rw_table,
+                    mpt_table,
+                    u8_table,
+                    u10_table,
+                    u16_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(_block: &witness::Block<F>, chunk: &Chunk<F>) -> (usize, usize) {
         (
@@ -525,6 +573,12 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
         _challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
+        if let Some(prev_chunk_last_rw) = &self.prev_chunk_last_rw {
+            RwMap::validate_padding_start_rw(&self.rows, prev_chunk_last_rw).map_err(|err| {
+                log::error!("invalid last rw of the previous chunk: {}", err);
+                Error::Synthesis
+            })?;
+        }
         config.load_aux_tables(layouter)?;
 
         // Assigning to same columns in different regions should be avoided.
@@ -637,6 +691,40 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
             self.rw_fingerprints.mul_acc,
         ]]
     }
+
+    fn instance_layout() -> Vec<usize> {
+        vec![6]
+    }
+}
+
+/// Chunk holding only the by address sorted `rws`, to assign the state circuit without a block.
+/// The table is padded up to `max_rws` rows, starting from `padding_start_rw` if any.
+pub(crate) fn new_chunk_from_rw_map<F: Field>(
+    rws: &RwMap,
+    max_rws: usize,
+    padding_start_rw: Option<Rw>,
+) -> Chunk<F> {
+    let (permu_alpha, permu_gamma) = get_permutation_randomness();
+    let by_address_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
+        rws,
+        max_rws,
+        permu_alpha,
+        permu_gamma,
+        F::from(1),
+        false,
+        padding_start_rw,
+    );
+    Chunk {
+        by_address_rws: rws.clone(),
+        permu_alpha,
+        permu_gamma,
+        by_address_rw_fingerprints,
+        fixed_param: FixedCParams {
+            max_rws,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
 }
 
 fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>) -> Queries<F> {
@@ -4,8 +4,9 @@ use crate::{
     util::{unusable_rows, SubCircuit},
     witness::{chunk::*, MptUpdates, Rw, RwMap},
 };
-use bus_mapping::operation::{
-    MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW,
+use bus_mapping::{
+    circuit_input_builder::FixedCParams,
+    operation::{MemoryOp, Operation, OperationContainer, RWCounter, StackOp, StorageOp, RW},
 };
 use eth_types::{
     address,
@@ -35,23 +36,7 @@ fn state_circuit_unusable_rows() {
 }
 
 fn new_chunk_from_rw_map<F: Field>(rws: &RwMap, padding_start_rw: Option<Rw>) -> Chunk<F> {
-    let (alpha, gamma) = get_permutation_randomness();
-    let mut chunk = Chunk {
-        by_address_rws: rws.clone(),
-        ..Default::default()
-    };
-
-    let rw_fingerprints = get_permutation_fingerprint_of_rwmap(
-        &chunk.by_address_rws,
-        chunk.fixed_param.max_rws,
-        alpha,
-        gamma,
-        F::from(1),
-        false,
-        padding_start_rw,
-    );
-    chunk.by_address_rw_fingerprints = rw_fingerprints;
-    chunk
+    super::new_chunk_from_rw_map(rws, FixedCParams::default().max_rws, padding_start_rw)
 }
 
 fn test_state_circuit_ok(
@@ -75,6 +60,43 @@ fn test_state_circuit_ok(
     assert_eq!(verify_result, Ok(()));
 }
 
+#[test]
+fn state_circuit_from_rws() {
+    let account = address!("0x00000000000000000000000000000000000cafe1");
+    let rows = vec![
+        Rw::Account {
+            rw_counter: 1,
+            is_write: true,
+            account_address: account,
+            field_tag: AccountFieldTag::Nonce,
+            value: U256::one(),
+            value_prev: U256::zero(),
+        },
+        Rw::Account {
+            rw_counter: 2,
+            is_write: true,
+            account_address: account,
+            field_tag: AccountFieldTag::Balance,
+            value: U256::from(100),
+            value_prev: U256::zero(),
+        },
+        Rw::Account {
+            rw_counter: 3,
+            is_write: true,
+            account_address: account,
+            field_tag: AccountFieldTag::Nonce,
+            value: U256::from(2),
+            value_prev: U256::one(),
+        },
+    ];
+
+    let circuit = StateCircuit::<Fr>::new_from_rws(rows, N_ROWS);
+    let instance = circuit.instance();
+
+    let prover = MockProver::<Fr>::run(17, &circuit, instance).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
 #[test]
 fn degree() {
     let mut meta = ConstraintSystem::<Fr>::default();
@@ -996,6 +1018,41 @@ fn variadic_size_check() {
     assert_eq!(prover1.permutation(), prover2.permutation());
 }
 
+#[test]
+fn state_circuit_with_prev_chunk_last_rw() {
+    let stack_write = |rw_counter| Rw::Stack {
+        rw_counter,
+        is_write: true,
+        call_id: 1,
+        stack_pointer: 1023,
+        value: U256::from(rw_counter),
+    };
+    let rw_map = RwMap::from((1000..1004).map(stack_write).collect::<Vec<_>>());
+    let prev_chunk_last_rw = stack_write(999);
+
+    let circuit = StateCircuit::<Fr>::new(&new_chunk_from_rw_map(&rw_map, None))
+        .with_prev_chunk_last_rw(prev_chunk_last_rw)
+        .unwrap();
+    let expected =
+        new_chunk_from_rw_map::<Fr>(&rw_map, Some(prev_chunk_last_rw)).by_address_rw_fingerprints;
+    assert_eq!(circuit.rw_fingerprints.mul_acc, expected.mul_acc);
+    assert_eq!(circuit.rw_fingerprints.ending_row, expected.ending_row);
+    let instance = circuit.instance();
+    let prover = MockProver::<Fr>::run(19, &circuit, instance).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    assert!(
+        StateCircuit::<Fr>::new(&new_chunk_from_rw_map(&rw_map, None))
+            .with_prev_chunk_last_rw(stack_write(1001))
+            .is_err()
+    );
+    // A chunk starting inside the rows of the previous one fails synthesis.
+    let mut circuit = StateCircuit::<Fr>::new(&new_chunk_from_rw_map(&rw_map, None));
+    circuit.prev_chunk_last_rw = Some(stack_write(1001));
+    let instance = circuit.instance();
+    assert!(MockProver::<Fr>::run(19, &circuit, instance).is_err());
+}
+
 #[test]
 #[ignore = "TxReceipt constraints not yet implemented"]
 fn bad_initial_tx_receipt_value() {
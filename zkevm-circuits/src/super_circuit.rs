@@ -56,7 +56,7 @@ pub(crate) mod test;
 use crate::{
     bytecode_circuit::{BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs},
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
-    evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
+    evm_circuit::{table::CustomFixedTables, EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
@@ -66,7 +66,10 @@ use crate::{
         RwTable, SigTable, TxTable, UXTable, WdTable,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
-    util::{chunk_ctx::ChunkContextConfig, log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
+    util::{
+        chunk_ctx::ChunkContextConfig, count_advice_columns, log2_ceil, Challenges, SubCircuit,
+        SubCircuitConfig,
+    },
     witness::{block_convert, chunk_convert, Block, Chunk, MptUpdates},
 };
 use bus_mapping::{
@@ -256,6 +259,8 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 sig_table,
                 chunk_ctx_config: chunk_ctx_config.clone(),
                 feature_config,
+                custom_fixed_tables: CustomFixedTables::default(),
+                enabled_execution_states: None,
             },
         );
 
@@ -396,6 +401,12 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         .unwrap()
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            <Self as Circuit<F>>::configure_with_params(meta, Self::default().params());
+        })
+    }
+
     fn new_from_block(block: &Block<F>, chunk: &Chunk<F>) -> Self {
         let evm_circuit = EvmCircuit::new_from_block(block, chunk);
         let state_circuit = StateCircuit::new_from_block(block, chunk);
@@ -450,6 +461,21 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         instance
     }
 
+    fn instance_layout() -> Vec<usize> {
+        [
+            vec![5],
+            KeccakCircuit::<F>::instance_layout(),
+            PiCircuit::<F>::instance_layout(),
+            TxCircuit::<F>::instance_layout(),
+            BytecodeCircuit::<F>::instance_layout(),
+            CopyCircuit::<F>::instance_layout(),
+            StateCircuit::<F>::instance_layout(),
+            ExpCircuit::<F>::instance_layout(),
+            EvmCircuit::<F>::instance_layout()[1..].to_vec(),
+        ]
+        .concat()
+    }
+
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &Block<F>, chunk: &Chunk<F>) -> (usize, usize) {
         let evm = EvmCircuit::min_num_rows_block(block, chunk);
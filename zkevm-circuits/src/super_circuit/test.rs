@@ -222,6 +222,49 @@ fn serial_test_multi_chunk_super_circuit_2tx_2max_tx() {
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
 
+#[test]
+fn instance_layout_matches_instance() {
+    let circuits_params = FixedCParams {
+        total_chunks: 1,
+        max_txs: 1,
+        max_withdrawals: 5,
+        max_calldata: 32,
+        max_rws: 256,
+        max_copy_rows: 256,
+        max_exp_steps: 256,
+        max_bytecode: 512,
+        max_evm_rows: 0,
+        max_keccak_rows: 0,
+        max_vertical_circuit_rows: 0,
+    };
+    let (_, circuits, instances, _) =
+        SuperCircuit::<Fr>::build(block_1tx(), circuits_params, Fr::from(TEST_MOCK_RANDOMNESS))
+            .unwrap();
+    let layout = |instance: Vec<Vec<Fr>>| instance.iter().map(Vec::len).collect::<Vec<_>>();
+
+    let circuit = &circuits[0];
+    assert_eq!(
+        layout(circuit.evm_circuit.instance()),
+        EvmCircuit::<Fr>::instance_layout()
+    );
+    assert_eq!(
+        layout(circuit.state_circuit.instance()),
+        StateCircuit::<Fr>::instance_layout()
+    );
+    assert_eq!(
+        layout(circuit.pi_circuit.instance()),
+        PiCircuit::<Fr>::instance_layout()
+    );
+    assert_eq!(
+        layout(circuit.tx_circuit.instance()),
+        TxCircuit::<Fr>::instance_layout()
+    );
+    assert_eq!(
+        layout(instances[0].clone()),
+        SuperCircuit::<Fr>::instance_layout()
+    );
+}
+
 #[ignore]
 #[test]
 fn test_rw_table_commitment() {
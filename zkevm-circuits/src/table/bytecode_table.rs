@@ -1,5 +1,6 @@
 use super::*;
 use bus_mapping::state_db::CodeDB;
+use eth_types::Bytecode;
 
 /// Tag to identify the field in a Bytecode Table row
 #[derive(Clone, Copy, Debug)]
@@ -104,6 +105,21 @@ impl BytecodeTable {
             },
         )
     }
+
+    /// Assign the `BytecodeTable` from bytecodes that may repeat, such as the
+    /// bytecodes of several blocks replayed in one proof.  Identical bytecodes
+    /// are assigned once, which is safe as the lookups are keyed by code hash.
+    pub fn load_deduped<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        codes: impl IntoIterator<Item = Bytecode>,
+    ) -> Result<(), Error> {
+        let mut bytecodes = CodeDB::default();
+        for code in codes {
+            bytecodes.insert(code.code());
+        }
+        self.load(layouter, bytecodes)
+    }
 }
 
 impl<F: Field> LookupTable<F> for BytecodeTable {
@@ -129,3 +145,57 @@ impl<F: Field> LookupTable<F> for BytecodeTable {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, halo2curves::bn256::Fr};
+
+    #[derive(Default)]
+    struct LoadDedupedCircuit {
+        codes: Vec<Bytecode>,
+    }
+
+    impl Circuit<Fr> for LoadDedupedCircuit {
+        type Config = BytecodeTable;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            BytecodeTable::construct(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            config.load_deduped(&mut layouter, self.codes.clone())
+        }
+    }
+
+    #[test]
+    fn load_deduped_assigns_repeated_code_once() {
+        let code = Bytecode::from((0..100).collect::<Vec<u8>>());
+        // The all-zero row, the header and 100 bytes fit in 2^7 rows once, but
+        // not twice.
+        let k = 7;
+        let circuit = LoadDedupedCircuit {
+            codes: vec![code.clone(), code.clone()],
+        };
+        MockProver::run(k, &circuit, vec![])
+            .unwrap()
+            .assert_satisfied();
+
+        let mut other = code.code();
+        other[0] = 0xff;
+        let circuit = LoadDedupedCircuit {
+            codes: vec![code, Bytecode::from(other)],
+        };
+        assert!(MockProver::run(k, &circuit, vec![]).is_err());
+    }
+}
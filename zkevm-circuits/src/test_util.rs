@@ -1,7 +1,10 @@
 //! Testing utilities
 
 use crate::{
-    evm_circuit::{cached::EvmCircuitCached, EvmCircuit},
+    evm_circuit::{
+        cached::EvmCircuitCached, mock_challenges::EvmCircuitMockChallenges, step::ExecutionState,
+        EvmCircuit,
+    },
     state_circuit::StateCircuit,
     util::SubCircuit,
     witness::{Block, Chunk, Rw},
@@ -10,9 +13,22 @@ use bus_mapping::{
     circuit_input_builder::{FeatureConfig, FixedCParams},
     mock::BlockData,
 };
-use eth_types::geth_types::GethData;
+use eth_types::{
+    bytecode,
+    geth_types::{Account, GethData},
+    Address, Block as EthBlock, Bytes, GethExecTrace, Transaction as EthTransaction, Word, U64,
+};
 use itertools::{all, Itertools};
-use std::cmp;
+use serde::Deserialize;
+use std::{
+    cmp,
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 use crate::util::log2_ceil;
@@ -91,7 +107,10 @@ pub struct CircuitTestBuilder<const NACC: usize, const NTX: usize> {
     feature_config: Option<FeatureConfig>,
     block: Option<Block<Fr>>,
     chunks: Option<Vec<Chunk<Fr>>>,
+    mock_randomness: Option<u64>,
     block_modifiers: Vec<Box<dyn Fn(&mut Block<Fr>, &mut Vec<Chunk<Fr>>)>>,
+    expected_execution_states: Vec<(usize, ExecutionState)>,
+    validate_memory_growth: bool,
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
@@ -103,7 +122,10 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             feature_config: None,
             block: None,
             chunks: None,
+            mock_randomness: None,
             block_modifiers: vec![],
+            expected_execution_states: vec![],
+            validate_memory_growth: false,
         }
     }
 
@@ -151,6 +173,15 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self
     }
 
+    /// Verifies the EVM circuit with the challenges of the prover replaced by
+    /// the mock values derived from `seed`, instead of the ones of the
+    /// MockProver. Running a test with several seeds helps to catch
+    /// constraints that accidentally depend on a specific randomness.
+    pub fn with_mock_randomness(mut self, seed: u64) -> Self {
+        self.mock_randomness = Some(seed);
+        self
+    }
+
     #[allow(clippy::type_complexity)]
     /// Allows to provide modifier functions for the [`Block, Chunk`] that will be
     /// generated within this builder.
@@ -164,6 +195,27 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self.block_modifiers.push(modifier);
         self
     }
+
+    /// Checks, before proving, that the step at `step_index` of the first
+    /// transaction of the block is assigned to `execution_state`. This catches
+    /// an opcode routed to the wrong gadget even when the constraints of that
+    /// gadget happen to be satisfied.
+    pub fn expect_execution_state(
+        mut self,
+        step_index: usize,
+        execution_state: ExecutionState,
+    ) -> Self {
+        self.expected_execution_states
+            .push((step_index, execution_state));
+        self
+    }
+
+    /// Checks, before proving, that the memory of every call never shrinks
+    /// between two of its steps. A violation is a bug in the bus-mapping.
+    pub fn validate_memory_growth(mut self) -> Self {
+        self.validate_memory_growth = true;
+        self
+    }
 }
 
 impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
@@ -193,7 +245,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                 BlockData::new_from_geth_data_with_params(block.clone(), fixed_param)
                     .new_circuit_input_builder_with_feature(self.feature_config.unwrap_or_default())
                     .handle_block(&block.eth_block, &block.geth_traces)
-                    .map_err(|err| CircuitTestError::CannotHandleBlock(err.to_string()))?
+                    .map_err(|err| self.handle_block_error(err, &block, fixed_param))?
             }
             None => BlockData::new_from_geth_data_chunked(block.clone(), total_chunks.unwrap_or(1))
                 .new_circuit_input_builder_with_feature(self.feature_config.unwrap_or_default())
@@ -203,7 +255,8 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         // Build a witness block from trace result.
         let mut block = crate::witness::block_convert(&builder)
             .map_err(|err| CircuitTestError::CannotConvertBlock(err.to_string()))?;
-        let mut chunks = crate::witness::chunk_convert(&block, &builder).unwrap();
+        let mut chunks = crate::witness::chunk_convert(&block, &builder)
+            .map_err(|err| CircuitTestError::CannotConvertBlock(err.to_string()))?;
 
         for modifier_fn in &self.block_modifiers {
             modifier_fn.as_ref()(&mut block, &mut chunks);
@@ -211,10 +264,71 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         Ok((block, chunks))
     }
 
+    /// Maps an error of `handle_block` with the fixed `params`.  The builder stops as soon as a
+    /// chunk runs past its `max_rws`, so the block is handled again with dynamic parameters to
+    /// report the exact rw count of the overflowing chunk as [`CircuitTestError::TooManyRws`].
+    fn handle_block_error(
+        &self,
+        err: bus_mapping::Error,
+        block: &GethData,
+        params: FixedCParams,
+    ) -> CircuitTestError {
+        if !matches!(err, bus_mapping::Error::RwsNotEnough(..)) {
+            return CircuitTestError::CannotHandleBlock(err.to_string());
+        }
+        BlockData::new_from_geth_data_chunked(block.clone(), params.total_chunks)
+            .new_circuit_input_builder_with_feature(self.feature_config.unwrap_or_default())
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .ok()
+            .and_then(|builder| {
+                builder.chunks.iter().enumerate().find_map(|(chunk, c)| {
+                    let rw_count = c.ctx.end_rwc - c.ctx.initial_rwc;
+                    // The Start row takes one row of the rw table.
+                    (rw_count >= params.max_rws).then_some(CircuitTestError::TooManyRws {
+                        chunk,
+                        rw_count,
+                        max_rws: params.max_rws,
+                    })
+                })
+            })
+            .unwrap_or_else(|| CircuitTestError::CannotHandleBlock(err.to_string()))
+    }
+
+    fn check_execution_states(&self, block: &Block<Fr>) -> Result<(), CircuitTestError> {
+        let steps = block.txs.first().map(|tx| tx.steps()).unwrap_or_default();
+        self.expected_execution_states
+            .iter()
+            .try_for_each(|&(step_index, expected)| {
+                let found = steps.get(step_index).map(ExecutionState::from);
+                if found == Some(expected) {
+                    Ok(())
+                } else {
+                    Err(CircuitTestError::UnexpectedExecutionState {
+                        step_index,
+                        expected,
+                        found,
+                    })
+                }
+            })
+    }
+
+    fn check_memory_growth(&self, block: &Block<Fr>) -> Result<(), CircuitTestError> {
+        if !self.validate_memory_growth {
+            return Ok(());
+        }
+        block.txs.iter().try_for_each(|tx| {
+            tx.validate_memory_growth().map_err(|err| {
+                CircuitTestError::InvalidMemoryGrowth(format!("tx {}: {err}", tx.id))
+            })
+        })
+    }
+
     fn run_evm_circuit_test(
         &self,
         block: Block<Fr>,
         chunks: Vec<Chunk<Fr>>,
+        mock_randomness: Option<u64>,
+        timings: &mut RunTimings,
     ) -> Result<(), CircuitTestError> {
         if chunks.is_empty() {
             return Err(CircuitTestError::SanityCheckChunks(
@@ -301,7 +415,16 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             .find_map(|(i, chunk)| {
                 // Mainnet EVM circuit constraints can be cached for test performance.
                 // No cache for EVM circuit with customized features
-                let prover = if block.feature_config.is_mainnet() {
+                let start = Instant::now();
+                let prover = if let Some(seed) = mock_randomness {
+                    let circuit = EvmCircuitMockChallenges::get_test_circuit_from_block(
+                        block.clone(),
+                        chunk,
+                        seed,
+                    );
+                    let instance = circuit.instance();
+                    MockProver::<Fr>::run(k, &circuit, instance)
+                } else if block.feature_config.is_mainnet() {
                     let circuit =
                         EvmCircuitCached::get_test_circuit_from_block(block.clone(), chunk);
                     let instance = circuit.instance();
@@ -311,6 +434,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                     let instance = circuit.instance();
                     MockProver::<Fr>::run(k, &circuit, instance)
                 };
+                timings.assignment += start.elapsed();
 
                 if let Err(err) = prover {
                     return Some(Err(CircuitTestError::SynthesisFailure {
@@ -321,15 +445,24 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
 
                 let prover = prover.unwrap();
 
+                let start = Instant::now();
                 let res = prover
                     .verify_at_rows(
                         active_gate_rows.iter().cloned(),
                         active_lookup_rows.iter().cloned(),
                     )
-                    .map_err(|err| CircuitTestError::VerificationFailed {
-                        circuit: Circuit::EVM,
-                        reasons: err,
+                    .map_err(|reasons| match mock_randomness {
+                        Some(seed) => CircuitTestError::VerificationFailedWithSeed {
+                            circuit: Circuit::EVM,
+                            seed,
+                            reasons,
+                        },
+                        None => CircuitTestError::VerificationFailed {
+                            circuit: Circuit::EVM,
+                            reasons,
+                        },
                     });
+                timings.verification += start.elapsed();
                 if res.is_err() {
                     println!("failed on chunk index {}", i);
                     Some(res)
@@ -345,6 +478,7 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         &self,
         block: Block<Fr>,
         chunks: Vec<Chunk<Fr>>,
+        timings: &mut RunTimings,
     ) -> Result<(), CircuitTestError> {
         // sanity check
         assert!(!chunks.is_empty());
@@ -367,12 +501,14 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
             .find_map(|chunk| {
                 let state_circuit = StateCircuit::<Fr>::new(chunk);
                 let instance = state_circuit.instance();
+                let start = Instant::now();
                 let prover = MockProver::<Fr>::run(k, &state_circuit, instance).map_err(|err| {
                     CircuitTestError::SynthesisFailure {
                         circuit: Circuit::State,
                         reason: err,
                     }
                 });
+                timings.assignment += start.elapsed();
                 if let Err(err) = prover {
                     return Some(Err(err));
                 }
@@ -386,12 +522,14 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
                     })
                     .count();
                 let rows = 1..1 + non_padding_rows_len;
+                let start = Instant::now();
                 let result: Result<(), CircuitTestError> = prover
                     .verify_at_rows(rows.clone(), rows)
                     .map_err(|err| CircuitTestError::VerificationFailed {
                         circuit: Circuit::EVM,
                         reasons: err,
                     });
+                timings.verification += start.elapsed();
                 if result.is_ok() {
                     None
                 } else {
@@ -415,16 +553,93 @@ impl<const NACC: usize, const NTX: usize> CircuitTestBuilder<NACC, NTX> {
         self,
         total_chunks: Option<usize>,
     ) -> Result<(), CircuitTestError> {
+        self.run_timed(total_chunks, &mut RunTimings::default())
+    }
+
+    fn run_timed(
+        &self,
+        total_chunks: Option<usize>,
+        timings: &mut RunTimings,
+    ) -> Result<(), CircuitTestError> {
+        let start = Instant::now();
         let (block, chunks) = self.build_block(total_chunks)?;
+        timings.conversion = start.elapsed();
 
-        self.run_evm_circuit_test(block.clone(), chunks.clone())?;
-        self.run_state_circuit_test(block, chunks)
+        check_rw_counts(&chunks)?;
+        self.check_execution_states(&block)?;
+        self.check_memory_growth(&block)?;
+        self.run_evm_circuit_test(block.clone(), chunks.clone(), self.mock_randomness, timings)?;
+        self.run_state_circuit_test(block, chunks, timings)
     }
 
     /// Convenient method to run in test cases that error handling is not required.
     pub fn run(self) {
         self.run_with_result().unwrap()
     }
+
+    /// Same as [`Self::run_with_result`], but also reports where the time was spent.
+    pub fn run_with_timing(self) -> Result<RunTimings, CircuitTestError> {
+        let start = Instant::now();
+        let mut timings = RunTimings::default();
+        self.run_timed(None, &mut timings)?;
+        timings.total = start.elapsed();
+        Ok(timings)
+    }
+
+    /// Triggers the `CircuitTestBuilder` to convert the [`TestContext`] if any,
+    /// into a [`Block`] and verify the EVM circuit once for each of the given
+    /// mock randomness `seeds`, which replace the challenges of the prover.
+    /// A witness that only satisfies the constraints for one specific
+    /// challenge value fails for the other seeds.
+    pub fn run_multi_randomness_with_result(self, seeds: &[u64]) -> Result<(), CircuitTestError> {
+        assert!(
+            seeds.iter().unique().count() >= 2,
+            "at least two different seeds are required"
+        );
+        let (block, chunks) = self.build_block(None)?;
+
+        check_rw_counts(&chunks)?;
+        self.check_execution_states(&block)?;
+        self.check_memory_growth(&block)?;
+
+        seeds.iter().try_for_each(|&seed| {
+            self.run_evm_circuit_test(
+                block.clone(),
+                chunks.clone(),
+                Some(seed),
+                &mut RunTimings::default(),
+            )
+        })
+    }
+
+    /// Convenient method to run [`Self::run_multi_randomness_with_result`] in
+    /// test cases that error handling is not required.
+    pub fn run_multi_randomness(self, seeds: &[u64]) {
+        self.run_multi_randomness_with_result(seeds).unwrap()
+    }
+}
+
+/// Time spent in each stage of [`CircuitTestBuilder::run_with_timing`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunTimings {
+    /// Handling of the block by the bus-mapping and conversion into a witness
+    pub conversion: Duration,
+    /// Witness assignment of the circuits, i.e. the synthesis in the MockProver
+    pub assignment: Duration,
+    /// Verification of the constraints by the MockProver
+    pub verification: Duration,
+    /// Total time of the run, including the checks between the stages
+    pub total: Duration,
+}
+
+impl fmt::Display for RunTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "conversion: {:?}, assignment: {:?}, verification: {:?}, total: {:?}",
+            self.conversion, self.assignment, self.verification, self.total
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -442,6 +657,9 @@ pub enum CircuitTestError {
     /// We didn't specify enough attributes to define a block for the circuit test
     #[error("NotEnoughAttributes")]
     NotEnoughAttributes,
+    /// A trace file replayed by [`replay_trace_dir`] can't be read
+    #[error("InvalidTraceFile({0})")]
+    InvalidTraceFile(String),
     /// Something wrong in the handle_block
     #[error("CannotHandleBlock({0})")]
     CannotHandleBlock(String),
@@ -451,6 +669,38 @@ pub enum CircuitTestError {
     /// Something wrong in the chunk_convert
     #[error("SanityCheckChunks({0})")]
     SanityCheckChunks(String),
+    /// A step of the first transaction is not assigned to the expected
+    /// [`ExecutionState`]
+    #[error("UnexpectedExecutionState(step {step_index}, expected {expected:?}, found {found:?})")]
+    UnexpectedExecutionState {
+        /// The index of the step in the first transaction
+        step_index: usize,
+        /// The expected execution state
+        expected: ExecutionState,
+        /// The execution state of the step, if any
+        found: Option<ExecutionState>,
+    },
+    /// The memory of a call shrinks between two of its steps
+    #[error("InvalidMemoryGrowth({0})")]
+    InvalidMemoryGrowth(String),
+    /// A chunk has more rws than its `max_rws` can hold
+    #[error("TooManyRws(chunk {chunk}: {rw_count} rws, max_rws {max_rws})")]
+    TooManyRws {
+        /// The index of the chunk
+        chunk: usize,
+        /// The number of rws of the chunk
+        rw_count: usize,
+        /// The `max_rws` of the chunk
+        max_rws: usize,
+    },
+    /// The instance fingerprint of a circuit doesn't match the golden value
+    #[error("InstanceFingerprintMismatch(expected {expected:?}, found {found:?})")]
+    InstanceFingerprintMismatch {
+        /// The golden value
+        expected: Vec<Fr>,
+        /// The fingerprint computed from the circuit
+        found: Vec<Fr>,
+    },
     /// Problem constructing MockProver
     #[error("SynthesisFailure({circuit:?}, reason: {reason:?})")]
     SynthesisFailure {
@@ -467,6 +717,17 @@ pub enum CircuitTestError {
         /// The list of verification failure
         reasons: Vec<VerifyFailure>,
     },
+    /// Failed to verify a circuit in the MockProver with the mock challenges
+    /// derived from `seed`
+    #[error("VerificationFailed({circuit:?}, seed {seed}, reasons: {reasons:?})")]
+    VerificationFailedWithSeed {
+        /// The circuit that causes the failure
+        circuit: Circuit,
+        /// The seed of the mock challenges
+        seed: u64,
+        /// The list of verification failure
+        reasons: Vec<VerifyFailure>,
+    },
 }
 
 impl CircuitTestError {
@@ -475,7 +736,10 @@ impl CircuitTestError {
     /// Errors must come from EVM circuit and must be unsatisfied constraints or lookup failure
     pub fn assert_evm_failure(&self) {
         match self {
-            Self::VerificationFailed { circuit, reasons } => {
+            Self::VerificationFailed { circuit, reasons }
+            | Self::VerificationFailedWithSeed {
+                circuit, reasons, ..
+            } => {
                 assert!(matches!(circuit, Circuit::EVM));
                 assert!(!reasons.is_empty());
 
@@ -488,3 +752,389 @@ impl CircuitTestError {
         }
     }
 }
+
+/// Block captured from geth, in the format of the files replayed by
+/// [`replay_trace_dir`].
+#[derive(Deserialize)]
+struct TraceFile {
+    chain_id: Word,
+    #[serde(default)]
+    history_hashes: Vec<Word>,
+    block: EthBlock<EthTransaction>,
+    accounts: Vec<TraceFileAccount>,
+    traces: Vec<GethExecTrace>,
+}
+
+/// Prestate of an account touched by the block of a [`TraceFile`].
+#[derive(Deserialize)]
+struct TraceFileAccount {
+    address: Address,
+    nonce: U64,
+    balance: Word,
+    #[serde(default)]
+    code: Bytes,
+    #[serde(default)]
+    storage: HashMap<Word, Word>,
+}
+
+impl From<TraceFile> for GethData {
+    fn from(trace: TraceFile) -> Self {
+        GethData {
+            chain_id: trace.chain_id,
+            history_hashes: trace.history_hashes,
+            eth_block: trace.block,
+            geth_traces: trace.traces,
+            accounts: trace
+                .accounts
+                .into_iter()
+                .map(|account| Account {
+                    address: account.address,
+                    nonce: account.nonce,
+                    balance: account.balance,
+                    code: account.code,
+                    storage: account.storage,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns a block for which `detect_fixed_table_tags` keeps every
+/// [`FixedTableTag`] of the EVM circuit.
+///
+/// Only the bitwise tags are ever dropped by the detection, so it is the
+/// bitwise opcodes of the transaction that make it keep all of them. The range
+/// checked opcodes and the call to the identity precompile that follow don't
+/// change the detected tags, they only add lookups into some of the other
+/// tags.
+///
+/// [`FixedTableTag`]: crate::evm_circuit::table::FixedTableTag
+pub fn block_exercising_all_fixed_tags() -> GethData {
+    let code = bytecode! {
+        // bitwise
+        PUSH1(0x0f)
+        PUSH1(0xf5)
+        AND
+        PUSH1(0x30)
+        OR
+        PUSH1(0xff)
+        XOR
+        NOT
+        // range checked
+        PUSH1(0x1f)
+        BYTE
+        PUSH1(0)
+        SIGNEXTEND
+        PUSH1(0x02)
+        EXP
+        POP
+        // precompile
+        PUSH1(0x20) // retLength
+        PUSH1(0x00) // retOffset
+        PUSH1(0x20) // argsLength
+        PUSH1(0x00) // argsOffset
+        PUSH1(0x00) // value
+        PUSH1(0x04) // addr
+        PUSH2(0xffff) // gas
+        CALL
+        STOP
+    };
+
+    TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+        .unwrap()
+        .into()
+}
+
+/// Percentage of `max_rws` above which a chunk is reported as nearly full.
+const RW_COUNT_WARN_PERCENT: usize = 95;
+
+/// Checks that the rws of every chunk fit in its `max_rws`, and warns about
+/// the chunks within 5% of it, which a slightly longer trace would overflow.
+///
+/// Returns the indices of the nearly full chunks.
+fn check_rw_counts(chunks: &[Chunk<Fr>]) -> Result<Vec<usize>, CircuitTestError> {
+    let mut nearly_full = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let (rw_count, max_rws) = (chunk.rw_count(), chunk.fixed_param.max_rws);
+        // The Start row takes one row of the rw table.
+        if rw_count >= max_rws {
+            log::error!("chunk {i} uses {rw_count} rws, over max_rws {max_rws}");
+            return Err(CircuitTestError::TooManyRws {
+                chunk: i,
+                rw_count,
+                max_rws,
+            });
+        }
+        if rw_count * 100 >= max_rws * RW_COUNT_WARN_PERCENT {
+            log::warn!("chunk {i} uses {rw_count} rws, close to max_rws {max_rws}");
+            nearly_full.push(i);
+        }
+    }
+    Ok(nearly_full)
+}
+
+/// Compares the [`SubCircuit::instance_fingerprint`] of `circuit` against a
+/// committed `golden` value, so that an accidental change to the order or
+/// length of the public inputs fails the test that checks it.
+pub fn check_instance_fingerprint(
+    circuit: &impl SubCircuit<Fr>,
+    golden: &[Fr],
+) -> Result<(), CircuitTestError> {
+    let found = circuit.instance_fingerprint();
+    if found != golden {
+        return Err(CircuitTestError::InstanceFingerprintMismatch {
+            expected: golden.to_vec(),
+            found,
+        });
+    }
+    Ok(())
+}
+
+/// Replays every `.json` trace file in the directory at `path` through the EVM
+/// and State circuits, with the circuit parameters fitted to each block.
+///
+/// The fitting is done by the
+/// [`DynamicCParams`](bus_mapping::circuit_input_builder::DynamicCParams)
+/// builder, whose `handle_block` dry runs the block to compute its
+/// [`FixedCParams`], and the degree of the circuits is then derived from the
+/// converted block.
+///
+/// A trace file holds a JSON object with the `chain_id`, the optional
+/// `history_hashes`, the `block` with its transactions as returned by
+/// `eth_getBlockByNumber`, the prestate `accounts` touched by the block and
+/// the `traces` of its transactions as returned by `debug_traceTransaction`.
+///
+/// Returns the failures along with the file that caused each of them.
+pub fn replay_trace_dir(path: impl AsRef<Path>) -> Vec<(PathBuf, CircuitTestError)> {
+    let path = path.as_ref();
+    let mut trace_files = fs::read_dir(path)
+        .unwrap_or_else(|err| panic!("cannot read trace dir {}: {err}", path.display()))
+        .map(|entry| entry.expect("trace dir entry").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+        .collect_vec();
+    trace_files.sort();
+
+    trace_files
+        .into_iter()
+        .filter_map(|trace_file| {
+            replay_trace_file(&trace_file)
+                .err()
+                .map(|err| (trace_file, err))
+        })
+        .collect()
+}
+
+fn replay_trace_file(path: &Path) -> Result<(), CircuitTestError> {
+    let file =
+        File::open(path).map_err(|err| CircuitTestError::InvalidTraceFile(err.to_string()))?;
+    let trace: TraceFile = serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| CircuitTestError::InvalidTraceFile(err.to_string()))?;
+    let geth_data = GethData::from(trace);
+
+    let builder = BlockData::new_from_geth_data(geth_data.clone())
+        .new_circuit_input_builder()
+        .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+        .map_err(|err| CircuitTestError::CannotHandleBlock(err.to_string()))?;
+    let block = crate::witness::block_convert(&builder)
+        .map_err(|err| CircuitTestError::CannotConvertBlock(err.to_string()))?;
+    let chunks = crate::witness::chunk_convert(&block, &builder)
+        .map_err(|err| CircuitTestError::CannotConvertBlock(err.to_string()))?;
+
+    CircuitTestBuilder::<1, 1>::new_from_block(block, chunks).run_with_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        evm_circuit::{detect_fixed_table_tags, table::FixedTableTag},
+        witness::rw::RwFingerprints,
+    };
+    use eth_types::Bytecode;
+    use mock::test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn all_fixed_tags_exercised() {
+        let geth_data = block_exercising_all_fixed_tags();
+        let builder = BlockData::new_from_geth_data(geth_data.clone())
+            .new_circuit_input_builder()
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)
+            .unwrap();
+        let block = crate::witness::block_convert::<Fr>(&builder).unwrap();
+
+        assert_eq!(
+            detect_fixed_table_tags(&block),
+            FixedTableTag::iter().collect_vec()
+        );
+    }
+
+    #[test]
+    fn with_mock_randomness() {
+        for seed in [0x100, 0xcafe, 0x1234_5678_9abc_def0] {
+            let code = bytecode! {
+                PUSH1(0x20)
+                PUSH1(0)
+                SHA3
+                STOP
+            };
+            CircuitTestBuilder::new_from_test_ctx(
+                TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+            )
+            .with_mock_randomness(seed)
+            .run();
+        }
+    }
+
+    #[test]
+    fn run_with_timing() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap();
+        let timings = CircuitTestBuilder::new_from_test_ctx(ctx)
+            .run_with_timing()
+            .unwrap();
+
+        // Every stage was timed, and the stages don't overlap
+        for stage in [timings.conversion, timings.assignment, timings.verification] {
+            assert!(stage > Duration::ZERO, "{timings}");
+        }
+        let sum = timings.conversion + timings.assignment + timings.verification;
+        assert!(sum <= timings.total, "{timings}");
+    }
+
+    fn evm_circuit_with_chunk_instance(alpha: u64, gamma: u64) -> EvmCircuit<Fr> {
+        let mut chunk = Chunk::default();
+        chunk.chunk_context.idx = 1;
+        chunk.chunk_context.total_chunks = 3;
+        chunk.chunk_context.initial_rwc = 10;
+        chunk.chunk_context.end_rwc = 20;
+        chunk.permu_alpha = Fr::from(alpha);
+        chunk.permu_gamma = Fr::from(gamma);
+        chunk.chrono_rw_fingerprints =
+            RwFingerprints::new(Fr::from(4), Fr::from(5), Fr::from(6), Fr::from(7));
+        EvmCircuit::new(Block::default(), chunk)
+    }
+
+    // 5 followed by [chunk index, next chunk index, total chunks, initial rwc, end
+    // rwc], then 6 followed by `pi_chunk_continuity`: [alpha, gamma, prev ending
+    // row, ending row, prev mul acc, mul acc]
+    const EVM_INSTANCE_GOLDEN: [u64; 13] = [5, 1, 2, 3, 10, 20, 6, 2, 3, 4, 5, 6, 7];
+
+    #[test]
+    fn evm_circuit_instance_fingerprint() {
+        let golden = EVM_INSTANCE_GOLDEN.map(Fr::from);
+        let circuit = evm_circuit_with_chunk_instance(2, 3);
+        check_instance_fingerprint(&circuit, &golden).unwrap();
+        assert_eq!(
+            circuit.instance_fingerprint()[0],
+            Fr::from(EvmCircuit::<Fr>::instance_layout()[0] as u64)
+        );
+    }
+
+    #[test]
+    fn evm_circuit_instance_reordering_detected() {
+        let golden = EVM_INSTANCE_GOLDEN.map(Fr::from);
+        // alpha and gamma swapped in `pi_chunk_continuity`
+        let err = check_instance_fingerprint(&evm_circuit_with_chunk_instance(3, 2), &golden)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CircuitTestError::InstanceFingerprintMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn evm_circuit_instance_column_shift_detected() {
+        let circuit = evm_circuit_with_chunk_instance(2, 3);
+        // The same values in the same order, with alpha moved from the start of the
+        // second column to the end of the first one
+        let mut shifted = circuit.instance();
+        let alpha = shifted[1].remove(0);
+        shifted[0].push(alpha);
+        assert_eq!(shifted.concat(), circuit.instance().concat());
+        let golden = shifted
+            .into_iter()
+            .flat_map(|column| std::iter::once(Fr::from(column.len() as u64)).chain(column))
+            .collect::<Vec<_>>();
+        let err = check_instance_fingerprint(&circuit, &golden).unwrap_err();
+        assert!(matches!(
+            err,
+            CircuitTestError::InstanceFingerprintMismatch { .. }
+        ));
+    }
+
+    fn rw_count_test_builder(max_rws_headroom: Option<isize>) -> CircuitTestBuilder<2, 1> {
+        // Enough stack rws for a headroom of one rw to be within 5% of max_rws
+        let mut code = Bytecode::default();
+        for _ in 0..20 {
+            code.op_push1(0).op_pop();
+        }
+        code.op_stop();
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block,
+        )
+        .unwrap();
+        let builder = CircuitTestBuilder::new_from_test_ctx(ctx);
+        match max_rws_headroom {
+            None => builder,
+            Some(headroom) => {
+                let (rw_count, fixed_param) = rw_count_and_params();
+                builder.params(FixedCParams {
+                    max_rws: rw_count.checked_add_signed(headroom).unwrap(),
+                    ..fixed_param
+                })
+            }
+        }
+    }
+
+    /// The rw count and the parameters of the single chunk of the test block,
+    /// built with dynamic parameters.
+    fn rw_count_and_params() -> (usize, FixedCParams) {
+        let (_, chunks) = rw_count_test_builder(None).build_block(None).unwrap();
+        (chunks[0].rw_count(), chunks[0].fixed_param)
+    }
+
+    #[test]
+    fn rw_count_near_max_rws_warns() {
+        let (rw_count, _) = rw_count_and_params();
+        let (_, chunks) = rw_count_test_builder(Some(1)).build_block(None).unwrap();
+        assert_eq!(chunks[0].rw_count(), rw_count);
+        assert_eq!(check_rw_counts(&chunks).unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn rw_count_over_max_rws_errors() {
+        let (rw_count, _) = rw_count_and_params();
+        for headroom in [0, -1] {
+            let err = rw_count_test_builder(Some(headroom))
+                .build_block(None)
+                .unwrap_err();
+            let max_rws = rw_count.checked_add_signed(headroom).unwrap();
+            assert!(
+                matches!(
+                    err,
+                    CircuitTestError::TooManyRws { chunk: 0, rw_count: count, max_rws: max }
+                        if count == rw_count && max == max_rws
+                ),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn replay_sample_traces() {
+        let failures = replay_trace_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/traces"));
+        for (trace_file, err) in failures.iter() {
+            println!("{}: {err}", trace_file.display());
+        }
+        assert!(failures.is_empty());
+    }
+}
@@ -15,10 +15,11 @@ pub use dev::TxCircuit as TestTxCircuit;
 
 use crate::{
     table::{KeccakTable, TxFieldTag, TxTable},
-    util::{word::WordLoHi, Challenges, SubCircuit, SubCircuitConfig},
+    util::{count_advice_columns, word::WordLoHi, Challenges, SubCircuit, SubCircuitConfig},
     witness::{self, Chunk},
 };
 use eth_types::{geth_types::Transaction, sign_types::SignData, Field};
+use ethers_core::utils::rlp::{Decodable, Rlp};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed},
@@ -162,6 +163,28 @@ impl<F: Field> TxCircuit<F> {
         }
     }
 
+    /// Return a new TxCircuit, sized to fit them exactly, from signed raw
+    /// transactions: legacy RLP lists or EIP-2718 typed envelopes (EIP-2930
+    /// and EIP-1559).  The sender of each transaction is recovered from its
+    /// signature.  Note that only EIP-155 signatures can currently be
+    /// verified by the circuit.
+    pub fn new_from_raw_txs(raw_txs: &[Vec<u8>], chain_id: u64) -> Result<Self, String> {
+        let txs: Vec<Transaction> = raw_txs
+            .iter()
+            .enumerate()
+            .map(|(idx, raw_tx)| {
+                let mut tx = eth_types::Transaction::decode(&Rlp::new(raw_tx))
+                    .map_err(|e| format!("raw tx {} decoding error: {:?}", idx, e))?;
+                tx.from = tx
+                    .recover_from()
+                    .map_err(|e| format!("raw tx {} sender recovery error: {:?}", idx, e))?;
+                Ok::<_, String>(Transaction::from(&tx))
+            })
+            .try_collect()?;
+        let max_calldata = txs.iter().map(|tx| tx.call_data.len()).sum();
+        Ok(Self::new(txs.len(), max_calldata, chain_id, txs))
+    }
+
     /// Return the minimum number of rows required to prove an input of a
     /// particular size.
     pub fn min_num_rows(txs_len: usize, call_data_len: usize) -> usize {
@@ -309,6 +332,22 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         6
     }
 
+    fn num_advice_columns() -> usize {
+        count_advice_columns::<F>(|meta| {
+            let tx_table = TxTable::construct(meta);
+            let keccak_table = KeccakTable::construct(meta);
+            let challenges = Challenges::construct(meta).exprs(meta);
+            TxCircuitConfig::new(
+                meta,
+                TxCircuitConfigArgs {
+                    tx_table,
+                    keccak_table,
+                    challenges,
+                },
+            );
+        })
+    }
+
     fn new_from_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> Self {
         Self::new(
             chunk.fixed_param.max_txs,
@@ -341,10 +380,15 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
             .txs
             .iter()
             .map(|tx| {
-                tx.sign_data(self.chain_id).map_err(|e| {
+                let sign_data = tx.sign_data(self.chain_id).map_err(|e| {
                     error!("tx_to_sign_data error for tx {:?}", e);
                     Error::Synthesis
-                })
+                })?;
+                if sign_data.get_addr().is_zero() {
+                    error!("invalid signature for tx from {:?}", tx.from);
+                    return Err(Error::Synthesis);
+                }
+                Ok(sign_data)
             })
             .try_collect()?;
 
@@ -361,4 +405,8 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         // "empty" instance column
         vec![vec![]]
     }
+
+    fn instance_layout() -> Vec<usize> {
+        vec![0]
+    }
 }
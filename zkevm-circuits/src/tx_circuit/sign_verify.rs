@@ -16,7 +16,9 @@ use ecc::{maingate, EccConfig, GeneralEccChip};
 use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey, EcdsaChip};
 use eth_types::{
     self, keccak256,
-    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
+    sign_types::{
+        biguint_to_32bytes_le, pk_bytes_le, pk_bytes_swap_endianness, SignData, SECP256K1_Q,
+    },
     Field,
 };
 use halo2_proofs::{
@@ -90,10 +92,18 @@ impl<F: Field> SignVerifyChip<F> {
         let rows_range_chip_table = 295188;
         let rows_ecc_chip_aux = 226;
         let rows_ecdsa_chip_verification = 104471;
+        // Upper bound of the rows of `assert_s_is_low`, counting 4 bytes per main gate row: 11
+        // rows for the bytes of s (limbs of 9, 9, 9 and 7 bytes), 5 rows for each of the 4
+        // compositions of 16 bytes (s and t, lo and hi), 1 row for the borrow bit and 1 row for
+        // each of the 2 sums.
+        let rows_low_s_check = 11 + 4 * 5 + 1 + 2;
         let rows_signature_address_verify = 76;
         std::cmp::max(
             rows_range_chip_table,
-            (rows_ecc_chip_aux + rows_ecdsa_chip_verification + rows_signature_address_verify)
+            (rows_ecc_chip_aux
+                + rows_ecdsa_chip_verification
+                + rows_low_s_check
+                + rows_signature_address_verify)
                 * num_verif,
         )
     }
@@ -320,6 +330,82 @@ fn integer_to_bytes_le<F: Field, FE: PrimeField>(
     Ok(bytes.try_into().unwrap())
 }
 
+// Constrain the integer of little endian bytes `s_le` to be at most (n - 1) / 2, where n is the
+// order of the secp256k1 curve, by assigning the difference t = (n - 1) / 2 - s as two 128 bits
+// halves, so that s + t = (n - 1) / 2 holds without wrapping around.
+fn assert_s_is_low<F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB: usize>(
+    ctx: &mut RegionCtx<'_, F>,
+    chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    s_le: &[AssignedValue<F>; 32],
+    sig_s: &secp256k1::Fq,
+) -> Result<(), Error> {
+    let ChipsRef {
+        main_gate,
+        range_chip,
+        ..
+    } = chips;
+
+    let half_n_le = biguint_to_32bytes_le((&*SECP256K1_Q - 1u32) >> 1);
+    let halves = |bytes_le: &[u8; 32]| {
+        (
+            u128::from_le_bytes(bytes_le[..16].try_into().unwrap()),
+            u128::from_le_bytes(bytes_le[16..].try_into().unwrap()),
+        )
+    };
+    let (half_n_lo, half_n_hi) = halves(&half_n_le);
+    let (s_lo, s_hi) = halves(&sig_s.to_bytes());
+    // The subtraction wraps around for a high s, which then fails the constraints
+    let (t_lo, borrow) = half_n_lo.overflowing_sub(s_lo);
+    let t_hi = half_n_hi
+        .wrapping_sub(s_hi)
+        .wrapping_sub(u128::from(borrow));
+
+    let powers_of_256 = iter::successors(Some(F::ONE), |coeff| Some(F::from(256) * coeff))
+        .take(16)
+        .collect_vec();
+    let compose = |ctx: &mut RegionCtx<'_, F>, bytes: &[AssignedValue<F>]| {
+        main_gate
+            .decompose(
+                ctx,
+                &bytes
+                    .iter()
+                    .zip_eq(&powers_of_256)
+                    .map(|(cell, coeff)| maingate::Term::Assigned(cell, *coeff))
+                    .collect_vec(),
+                F::ZERO,
+                |_, _| Ok(()),
+            )
+            .map(|(cell, _)| cell)
+    };
+    let s_lo_cell = compose(ctx, &s_le[..16])?;
+    let s_hi_cell = compose(ctx, &s_le[16..])?;
+    let (t_lo_cell, _) = range_chip.decompose(ctx, Value::known(F::from_u128(t_lo)), 8, 128)?;
+    let (t_hi_cell, _) = range_chip.decompose(ctx, Value::known(F::from_u128(t_hi)), 8, 128)?;
+    let two_pow_128 = F::from_u128(u128::MAX) + F::ONE;
+    let borrow_cell = main_gate.assign_bit(ctx, Value::known(F::from(u64::from(borrow))))?;
+
+    // s_lo + t_lo == half_n_lo + borrow * 2^128
+    main_gate.assert_zero_sum(
+        ctx,
+        &[
+            maingate::Term::Assigned(&s_lo_cell, F::ONE),
+            maingate::Term::Assigned(&t_lo_cell, F::ONE),
+            maingate::Term::Assigned(&borrow_cell, -two_pow_128),
+        ],
+        -F::from_u128(half_n_lo),
+    )?;
+    // s_hi + t_hi + borrow == half_n_hi
+    main_gate.assert_zero_sum(
+        ctx,
+        &[
+            maingate::Term::Assigned(&s_hi_cell, F::ONE),
+            maingate::Term::Assigned(&t_hi_cell, F::ONE),
+            maingate::Term::Assigned(&borrow_cell, F::ONE),
+        ],
+        -F::from_u128(half_n_hi),
+    )
+}
+
 /// Helper structure pass around references to all the chips required for an
 /// ECDSA verification.
 struct ChipsRef<'a, F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB: usize> {
@@ -369,6 +455,11 @@ impl<F: Field> SignVerifyChip<F> {
 
         let r_assigned = scalar_chip.assign_integer(ctx, integer_r, Range::Remainder)?;
         let s_assigned = scalar_chip.assign_integer(ctx, integer_s, Range::Remainder)?;
+
+        // Ref. spec SignVerifyChip 5. Verify that s is in the lower half of the curve order
+        // (EIP-2), otherwise (r, n - s) would be an alternative valid signature of the same tx
+        let s_le = integer_to_bytes_le(ctx, range_chip, &s_assigned)?;
+        assert_s_is_low(ctx, chips, &s_le, sig_s)?;
         let sig = AssignedEcdsaSig {
             r: r_assigned,
             s: s_assigned,
@@ -718,7 +809,10 @@ mod sign_verify_tests {
     use super::*;
     use crate::util::Challenges;
     use bus_mapping::circuit_input_builder::keccak_inputs_sign_verify;
-    use eth_types::{sign_types::sign, Bytes};
+    use eth_types::{
+        sign_types::{is_high_s, sign},
+        Bytes,
+    };
     use halo2_proofs::{
         arithmetic::Field as HaloField,
         circuit::SimpleFloorPlanner,
@@ -798,7 +892,7 @@ mod sign_verify_tests {
         }
     }
 
-    fn run<F: Field>(k: u32, max_verif: usize, signatures: Vec<SignData>) {
+    fn prover<F: Field>(k: u32, max_verif: usize, signatures: Vec<SignData>) -> MockProver<F> {
         let mut rng = XorShiftRng::seed_from_u64(2);
         let aux_generator =
             <Secp256k1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
@@ -814,11 +908,14 @@ mod sign_verify_tests {
             signatures,
         };
 
-        let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        match MockProver::run(k, &circuit, vec![vec![]]) {
             Ok(prover) => prover,
             Err(e) => panic!("{:#?}", e),
-        };
-        prover.assert_satisfied();
+        }
+    }
+
+    fn run<F: Field>(k: u32, max_verif: usize, signatures: Vec<SignData>) {
+        prover::<F>(k, max_verif, signatures).assert_satisfied();
     }
 
     // Generate a test key pair
@@ -837,14 +934,20 @@ mod sign_verify_tests {
         secp256k1::Fq::random(rng)
     }
 
-    // Returns (r, s)
+    // Returns (r, s, v), with s in the lower half of the curve order as required
+    // for tx signatures
     fn sign_with_rng(
         rng: impl RngCore,
         sk: secp256k1::Fq,
         msg_hash: secp256k1::Fq,
     ) -> (secp256k1::Fq, secp256k1::Fq, u8) {
         let randomness = secp256k1::Fq::random(rng);
-        sign(randomness, sk, msg_hash)
+        let (r, s, v) = sign(randomness, sk, msg_hash);
+        if is_high_s(&s) {
+            (r, -s, v ^ 1)
+        } else {
+            (r, s, v)
+        }
     }
 
     #[test]
@@ -876,4 +979,27 @@ mod sign_verify_tests {
         let k = 19;
         run::<Fr>(k, MAX_VERIF, signatures);
     }
+
+    #[test]
+    fn sign_verify_high_s() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let (sk, pk) = gen_key_pair(&mut rng);
+        let msg_hash = gen_msg_hash(&mut rng);
+        let (r, s, v) = sign_with_rng(&mut rng, sk, msg_hash);
+        let sign_data = |signature| SignData {
+            signature,
+            pk,
+            msg_hash,
+            msg: Bytes::new(),
+        };
+
+        let k = 19;
+        run::<Fr>(k, 1, vec![sign_data((r, s, v))]);
+        // (r, n - s) is a valid ECDSA signature of the same message, rejected
+        // by EIP-2.
+        assert!(is_high_s(&-s));
+        assert!(prover::<Fr>(k, 1, vec![sign_data((r, -s, v ^ 1))])
+            .verify()
+            .is_err());
+    }
 }
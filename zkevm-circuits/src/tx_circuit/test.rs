@@ -1,6 +1,11 @@
 use super::*;
 use crate::util::{log2_ceil, unusable_rows};
-use eth_types::address;
+use eth_types::{address, geth_types::TxType, sign_types::SECP256K1_Q, AccessList, Word};
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, Eip1559TransactionRequest, Eip2930TransactionRequest,
+    TransactionRequest,
+};
+use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
@@ -96,6 +101,119 @@ fn tx_circuit_bad_address() {
     .is_err(),);
 }
 
+#[test]
+fn tx_signature_with_high_s_is_invalid() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    let chain_id = mock::MOCK_CHAIN_ID.as_u64();
+    let tx = mock::CORRECT_MOCK_TXS[0].clone();
+    let sign_data = Transaction::from(tx.clone()).sign_data(chain_id).unwrap();
+    assert_eq!(sign_data.get_addr(), tx.from.address());
+
+    // (r, q - s) with the flipped recovery id is the malleable twin of the
+    // original signature, rejected by EIP-2.
+    let q = Word::from_big_endian(&SECP256K1_Q.to_bytes_be());
+    let v = tx.v.unwrap().as_u64();
+    let recovery_id = v - 35 - chain_id * 2;
+    let mut malleated = tx.clone();
+    malleated.sig_data((
+        v - recovery_id + (1 - recovery_id),
+        tx.r.unwrap(),
+        q - tx.s.unwrap(),
+    ));
+    let malleated = Transaction::from(malleated);
+    let sign_data = malleated.sign_data(chain_id).unwrap();
+    assert!(sign_data.get_addr().is_zero());
+
+    let k = log2_ceil(
+        TxCircuit::<Fr>::unusable_rows() + TxCircuit::<Fr>::min_num_rows(MAX_TXS, MAX_CALLDATA),
+    );
+    let circuit = TxCircuit::<Fr>::new(MAX_TXS, MAX_CALLDATA, chain_id, vec![malleated]);
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+}
+
+#[test]
+fn tx_circuit_from_raw_txs() {
+    let chain_id = mock::MOCK_CHAIN_ID.as_u64();
+    let txs = mock::CORRECT_MOCK_TXS[..2]
+        .iter()
+        .map(|tx| eth_types::Transaction::from(tx.clone()))
+        .collect_vec();
+    let raw_txs = txs.iter().map(|tx| tx.rlp().to_vec()).collect_vec();
+
+    let circuit = TxCircuit::<Fr>::new_from_raw_txs(&raw_txs, chain_id).unwrap();
+    assert_eq!(
+        circuit.txs.iter().map(|tx| tx.from).collect_vec(),
+        txs.iter().map(|tx| tx.from).collect_vec()
+    );
+
+    let k = log2_ceil(
+        TxCircuit::<Fr>::unusable_rows()
+            + TxCircuit::<Fr>::min_num_rows(circuit.max_txs, circuit.max_calldata),
+    );
+    let prover = MockProver::run(k, &circuit, circuit.instance()).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn tx_from_raw_txs_recovers_sender() {
+    // Signed transfer from the EIP-155 example, with private key 0x4646..46
+    let raw_tx = hex::decode(
+        "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080\
+         25a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761a\
+         ecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+    )
+    .unwrap();
+    let circuit = TxCircuit::<Fr>::new_from_raw_txs(&[raw_tx], 1).unwrap();
+    assert_eq!(
+        circuit.txs[0].from,
+        address!("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F")
+    );
+    assert_eq!(circuit.txs[0].tx_type, TxType::Eip155);
+    assert_eq!(circuit.txs[0].nonce.as_u64(), 9);
+
+    // Typed transactions
+    let wallet = LocalWallet::from_bytes(&[0x46; 32])
+        .unwrap()
+        .with_chain_id(1u64);
+    let request = TransactionRequest::new()
+        .to(address!("0x3535353535353535353535353535353535353535"))
+        .value(1u64)
+        .gas(21000u64)
+        .nonce(9u64);
+    for (typed_tx, tx_type) in [
+        (
+            TypedTransaction::Eip2930(Eip2930TransactionRequest::new(
+                request.gas_price(1u64).chain_id(1u64),
+                AccessList::default(),
+            )),
+            TxType::Eip2930,
+        ),
+        (
+            TypedTransaction::Eip1559(
+                Eip1559TransactionRequest::new()
+                    .to(address!("0x3535353535353535353535353535353535353535"))
+                    .value(1u64)
+                    .gas(21000u64)
+                    .nonce(9u64)
+                    .max_fee_per_gas(2u64)
+                    .max_priority_fee_per_gas(1u64)
+                    .chain_id(1u64),
+            ),
+            TxType::Eip1559,
+        ),
+    ] {
+        let signature = wallet.sign_transaction_sync(&typed_tx).unwrap();
+        let raw_tx = typed_tx.rlp_signed(&signature).to_vec();
+        let circuit = TxCircuit::<Fr>::new_from_raw_txs(&[raw_tx], 1).unwrap();
+        assert_eq!(circuit.txs[0].from, wallet.address());
+        assert_eq!(circuit.txs[0].tx_type, tx_type);
+    }
+
+    assert!(TxCircuit::<Fr>::new_from_raw_txs(&[vec![0xc0]], 1).is_err());
+}
+
 #[test]
 fn variadic_size_check() {
     const MAX_TXS: usize = 2;
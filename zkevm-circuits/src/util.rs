@@ -99,7 +99,7 @@ impl<T: Clone> Challenges<T> {
     }
 
     /// Returns a mock Challenges for testing purposes
-    #[cfg(feature = "mock-challenge")]
+    #[cfg(any(feature = "mock-challenge", feature = "test-util", test))]
     pub fn mock(keccak_input: T, lookup_input: T) -> Self {
         Self {
             keccak_input,
@@ -164,6 +164,24 @@ pub trait SubCircuit<F: Field> {
     fn instance(&self) -> Vec<Vec<F>> {
         vec![]
     }
+
+    /// Returns the length of each instance column returned by `instance`, which
+    /// doesn't depend on the witness.
+    fn instance_layout() -> Vec<usize> {
+        vec![]
+    }
+
+    /// Returns the instance columns returned by `instance` flattened in
+    /// order, each one preceded by its length. Comparing it against a known
+    /// value catches changes to the public input ordering that aggregation
+    /// depends on, including values moving from one column to the next.
+    fn instance_fingerprint(&self) -> Vec<F> {
+        self.instance()
+            .into_iter()
+            .flat_map(|column| std::iter::once(F::from(column.len() as u64)).chain(column))
+            .collect()
+    }
+
     /// Assign only the columns used by this sub-circuit.  This includes the
     /// columns that belong to the exposed lookup table contained within, if
     /// any; and excludes external tables that this sub-circuit does lookups
@@ -178,6 +196,18 @@ pub trait SubCircuit<F: Field> {
     /// Return the minimum number of rows required to prove the block.
     /// Row numbers without/with padding are both returned.
     fn min_num_rows_block(block: &witness::Block<F>, chunk: &Chunk<F>) -> (usize, usize);
+
+    /// Return a rough estimate of the number of rows of the proving key for
+    /// the block, that is the required or padded rows (whichever is larger)
+    /// plus the unusable rows, rounded up to a power of two.
+    fn estimate_pk_rows(block: &witness::Block<F>, chunk: &Chunk<F>) -> usize {
+        let (num_rows, num_padded_rows) = Self::min_num_rows_block(block, chunk);
+        (num_rows.max(num_padded_rows) + Self::unusable_rows()).next_power_of_two()
+    }
+
+    /// Return the number of advice columns of the SubCircuit configured on its
+    /// own, including the columns of the tables it is configured with.
+    fn num_advice_columns() -> usize;
 }
 
 /// SubCircuit configuration
@@ -189,6 +219,16 @@ pub trait SubCircuitConfig<F: Field> {
     fn new(meta: &mut ConstraintSystem<F>, args: Self::ConfigArgs) -> Self;
 }
 
+/// Returns the number of advice columns allocated by `configure` in an empty
+/// [`ConstraintSystem`].
+pub(crate) fn count_advice_columns<F: Field>(
+    configure: impl FnOnce(&mut ConstraintSystem<F>),
+) -> usize {
+    let mut meta = ConstraintSystem::default();
+    configure(&mut meta);
+    meta.num_advice_columns()
+}
+
 /// Ceiling of log_2(n)
 pub fn log2_ceil(n: usize) -> u32 {
     u32::BITS - (n as u32).leading_zeros() - (n & (n - 1) == 0) as u32
@@ -3,8 +3,7 @@
 // - Limbs: An EVEN word is 256 bits. Limbs N means split 256 into N limb. For example, N = 4, each
 //   limb is 256/4 = 64 bits
 
-use bus_mapping::state_db::CodeDB;
-use eth_types::{Field, OpsIdentity, ToLittleEndian, H160, H256};
+use eth_types::{Field, OpsIdentity, ToLittleEndian, H160, H256, U256};
 use gadgets::util::{not, or, Expr};
 use halo2_proofs::{
     circuit::{AssignedCell, Region, Value},
@@ -22,6 +21,14 @@ const BASE_128_BYTES: [u8; 32] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 ];
 
+/// Hash of the empty code, `keccak256([])`, which is the code hash of the accounts without code.
+pub const EMPTY_CODE_HASH: eth_types::Word = U256([
+    0x7bfad8045d85a470,
+    0xe500b653ca82273b,
+    0x927e7db2dcc703c0,
+    0xc5d2460186f7233c,
+]);
+
 /// The EVM word for witness
 #[derive(Clone, Debug, Copy)]
 pub struct WordLimbs<T, const N: usize> {
@@ -164,6 +171,22 @@ impl<F: Field, const N: usize> WordLimbs<Cell<F>, N> {
         )
     }
 
+    /// assign big-endian bytes to wordlimbs, such as data taken from RLP or calldata. Slices
+    /// shorter than 32 bytes are padded with zeros in the high bytes.
+    pub fn assign_be(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        bytes: &[u8],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(
+            bytes.len() <= 2 * N_BYTES_HALF_WORD,
+            "{} bytes don't fit in a word",
+            bytes.len()
+        );
+        self.assign_u256(region, offset, U256::from_big_endian(bytes))
+    }
+
     /// assign u64 to wordlimbs
     pub fn assign_u64(
         &self,
@@ -365,10 +388,43 @@ impl<F: Field, T: Expr<F> + Clone> WordExpr<F> for WordLoHi<T> {
 }
 
 impl<F: Field> WordLoHi<F> {
+    /// The [`EMPTY_CODE_HASH`] word
+    pub fn empty_code_hash() -> Self {
+        Self::from(EMPTY_CODE_HASH)
+    }
+
     /// Convert address (h160) to single field element.
     /// This method is Address specific
     pub fn compress_f(&self) -> F {
-        self.lo() + self.hi() * F::from_repr(BASE_128_BYTES).unwrap()
+        compress_address_f(self.lo(), self.hi())
+    }
+
+    /// Compute `lhs + rhs` modulo 2^256 together with the carry out of the lo limb and the carry
+    /// out of the whole word, i.e. the witness expected by
+    /// [`WordLoHi::add_with_carry_unchecked`].
+    pub fn add_with_carry(lhs: eth_types::Word, rhs: eth_types::Word) -> (Self, F, F) {
+        let (lhs_lo, rhs_lo) = (lhs.low_u128(), rhs.low_u128());
+        let (_, carry_lo) = lhs_lo.overflowing_add(rhs_lo);
+        let (sum, carry_hi) = lhs.overflowing_add(rhs);
+        (
+            sum.into(),
+            F::from(carry_lo as u64),
+            F::from(carry_hi as u64),
+        )
+    }
+
+    /// Compute `lhs - rhs` modulo 2^256 together with the borrow into the lo limb and the borrow
+    /// into the whole word, i.e. the witness expected by
+    /// [`WordLoHi::sub_with_borrow_unchecked`].
+    pub fn sub_with_borrow(lhs: eth_types::Word, rhs: eth_types::Word) -> (Self, F, F) {
+        let (lhs_lo, rhs_lo) = (lhs.low_u128(), rhs.low_u128());
+        let (_, borrow_lo) = lhs_lo.overflowing_sub(rhs_lo);
+        let (diff, borrow_hi) = lhs.overflowing_sub(rhs);
+        (
+            diff.into(),
+            F::from(borrow_lo as u64),
+            F::from(borrow_hi as u64),
+        )
     }
 }
 
@@ -408,6 +464,43 @@ impl<F: Field> WordLoHi<Expression<F>> {
         WordLoHi::new([self.lo() - rhs.lo(), self.hi() - rhs.hi()])
     }
 
+    /// Add `rhs` with carry propagation between the limbs, returning the sum modulo 2^256 and the
+    /// carry out of the word. `carry_lo` is the carry out of the lo limb and `carry_hi` the carry
+    /// out of the hi limb, see [`WordLoHi::add_with_carry`] for their witness. Caller need to
+    /// constrain both carries to be boolean and the resulting limbs to be 128 bits.
+    pub fn add_with_carry_unchecked(
+        self,
+        rhs: Self,
+        carry_lo: Expression<F>,
+        carry_hi: Expression<F>,
+    ) -> (Self, Expression<F>) {
+        let base = Expression::Constant(F::from_repr(BASE_128_BYTES).unwrap());
+        let sum = WordLoHi::new([
+            self.lo() + rhs.lo() - carry_lo.clone() * base.clone(),
+            self.hi() + rhs.hi() + carry_lo - carry_hi.clone() * base,
+        ]);
+        (sum, carry_hi)
+    }
+
+    /// Subtract `rhs` with borrow propagation between the limbs, returning the difference modulo
+    /// 2^256 and the borrow into the word. `borrow_lo` is the borrow into the lo limb and
+    /// `borrow_hi` the borrow into the hi limb, see [`WordLoHi::sub_with_borrow`] for their
+    /// witness. Caller need to constrain both borrows to be boolean and the resulting limbs to be
+    /// 128 bits.
+    pub fn sub_with_borrow_unchecked(
+        self,
+        rhs: Self,
+        borrow_lo: Expression<F>,
+        borrow_hi: Expression<F>,
+    ) -> (Self, Expression<F>) {
+        let base = Expression::Constant(F::from_repr(BASE_128_BYTES).unwrap());
+        let diff = WordLoHi::new([
+            self.lo() - rhs.lo() + borrow_lo.clone() * base.clone(),
+            self.hi() - rhs.hi() - borrow_lo + borrow_hi.clone() * base,
+        ]);
+        (diff, borrow_hi)
+    }
+
     /// No overflow check on lo/hi limbs
     pub fn mul_unchecked(self, rhs: Self) -> Self {
         WordLoHi::new([self.lo() * rhs.lo(), self.hi() * rhs.hi()])
@@ -416,7 +509,7 @@ impl<F: Field> WordLoHi<Expression<F>> {
     /// Convert address (h160) to single expression.
     /// This method is Address specific
     pub fn compress(&self) -> Expression<F> {
-        self.lo() + self.hi() * Expression::Constant(F::from_repr(BASE_128_BYTES).unwrap())
+        compress_address(self.lo(), self.hi())
     }
 }
 
@@ -459,9 +552,184 @@ impl<F: Field, const N1: usize> WordExpr<F> for WordLimbs<Expression<F>, N1> {
     }
 }
 
+/// Compress an address given as its lo and hi 128 bits limbs into a single expression,
+/// `lo + hi * 2^128`.  This is the form of the `address` column of the RwTable.  As an address
+/// only has 160 bits, the compression is its integer value, which is collision-free for the whole
+/// 20-byte address space since 2^160 is less than the field modulus.
+pub fn compress_address<F: Field>(lo: Expression<F>, hi: Expression<F>) -> Expression<F> {
+    lo + hi * Expression::Constant(F::from_repr(BASE_128_BYTES).unwrap())
+}
+
+fn compress_address_f<F: Field>(lo: F, hi: F) -> F {
+    lo + hi * F::from_repr(BASE_128_BYTES).unwrap()
+}
+
+/// Compress an address into a single field element, the assignment-side equivalent of
+/// [`compress_address`].
+pub fn compress_address_value<F: Field>(address: H160) -> F {
+    WordLoHi::<F>::from(address).compress_f()
+}
+
 /// Return the hash of the empty code as a `WordLoHi<Value<F>>` in little-endian.
 pub fn empty_code_hash_word_value<F: Field>() -> WordLoHi<Value<F>> {
-    WordLoHi::from(CodeDB::empty_code_hash()).into_value()
+    WordLoHi::empty_code_hash().into_value()
 }
 
-// TODO unittest
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_circuit::util::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        math_gadget::test_util::*,
+    };
+    use bus_mapping::state_db::{CodeDB, EMPTY_CODE_HASH_LE};
+    use eth_types::{ToBigEndian, ToWord, Word};
+    use halo2_proofs::{arithmetic::Field as Halo2Field, halo2curves::bn256::Fr};
+
+    fn eval(expr: Expression<Fr>) -> Fr {
+        expr.evaluate(
+            &|constant| constant,
+            &|_| unreachable!(),
+            &|_| unreachable!(),
+            &|_| unreachable!(),
+            &|_| unreachable!(),
+            &|_| unreachable!(),
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, scalar| a * scalar,
+        )
+    }
+
+    fn constant(word: WordLoHi<Fr>) -> WordLoHi<Expression<Fr>> {
+        word.map(Expression::Constant)
+    }
+
+    #[test]
+    fn add_with_carry_into_hi_limb() {
+        let lhs = Word::from(u128::MAX);
+        let rhs = Word::one();
+        let (sum, carry_lo, carry_hi) = WordLoHi::<Fr>::add_with_carry(lhs, rhs);
+        assert_eq!(sum, WordLoHi::new([Fr::ZERO, Fr::ONE]));
+        assert_eq!(carry_lo, Fr::ONE);
+        assert_eq!(carry_hi, Fr::ZERO);
+
+        let (sum_expr, carry_expr) = constant(lhs.into()).add_with_carry_unchecked(
+            constant(rhs.into()),
+            Expression::Constant(carry_lo),
+            Expression::Constant(carry_hi),
+        );
+        assert_eq!(sum_expr.map(eval), sum);
+        assert_eq!(eval(carry_expr), Fr::ZERO);
+    }
+
+    #[test]
+    fn empty_code_hash() {
+        assert_eq!(EMPTY_CODE_HASH, CodeDB::empty_code_hash().to_word());
+        // The in-circuit form, composed from the little-endian bytes of the hash
+        let in_circuit = Word32::new(EMPTY_CODE_HASH_LE.map(|byte| byte.expr())).to_word();
+        assert_eq!(in_circuit.map(eval), WordLoHi::<Fr>::empty_code_hash());
+    }
+
+    #[test]
+    fn add_with_carry_overflow() {
+        let (sum, carry_lo, carry_hi) = WordLoHi::<Fr>::add_with_carry(Word::MAX, Word::one());
+        assert_eq!(sum, WordLoHi::new([Fr::ZERO, Fr::ZERO]));
+        assert_eq!((carry_lo, carry_hi), (Fr::ONE, Fr::ONE));
+    }
+
+    #[test]
+    fn sub_with_borrow_from_hi_limb() {
+        let lhs = Word::from(u128::MAX) + 1;
+        let rhs = Word::one();
+        let (diff, borrow_lo, borrow_hi) = WordLoHi::<Fr>::sub_with_borrow(lhs, rhs);
+        assert_eq!(diff, WordLoHi::from(Word::from(u128::MAX)));
+        assert_eq!((borrow_lo, borrow_hi), (Fr::ONE, Fr::ZERO));
+
+        let (diff_expr, borrow_expr) = constant(lhs.into()).sub_with_borrow_unchecked(
+            constant(rhs.into()),
+            Expression::Constant(borrow_lo),
+            Expression::Constant(borrow_hi),
+        );
+        assert_eq!(diff_expr.map(eval), diff);
+        assert_eq!(eval(borrow_expr), Fr::ZERO);
+
+        let (diff, _, borrow_hi) = WordLoHi::<Fr>::sub_with_borrow(Word::zero(), Word::one());
+        assert_eq!(diff, WordLoHi::from(Word::MAX));
+        assert_eq!(borrow_hi, Fr::ONE);
+    }
+
+    #[derive(Clone)]
+    /// AssignBeTestContainer: assign the last 20 big-endian bytes of the witness and require
+    /// the word to be `address()`
+    struct AssignBeTestContainer<F> {
+        word: WordLoHiCell<F>,
+    }
+
+    fn address() -> H160 {
+        H160::from_slice(&(1..=20).collect::<Vec<u8>>())
+    }
+
+    impl<F: Field> MathGadgetContainer<F> for AssignBeTestContainer<F> {
+        fn configure_gadget_container(cb: &mut EVMConstraintBuilder<F>) -> Self {
+            let word = cb.query_word_unchecked();
+            cb.require_equal_word(
+                "word == address",
+                word.to_word(),
+                WordLoHi::<F>::from(address()).map(Expression::Constant),
+            );
+            AssignBeTestContainer { word }
+        }
+
+        fn assign_gadget_container(
+            &self,
+            witnesses: &[Word],
+            region: &mut CachedRegion<'_, '_, F>,
+        ) -> Result<(), Error> {
+            let bytes = witnesses[0].to_be_bytes();
+            self.word.assign_be(region, 0, &bytes[12..])?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn assign_be_pads_address() {
+        try_test!(AssignBeTestContainer<Fr>, [address().to_word()], true);
+        // Bytes above the 20-byte slice are not assigned.
+        try_test!(
+            AssignBeTestContainer<Fr>,
+            [address().to_word() + (Word::one() << 160)],
+            true
+        );
+        // The slice is read as big-endian.
+        let mut reversed = address();
+        reversed.0.reverse();
+        try_test!(AssignBeTestContainer<Fr>, [reversed.to_word()], false);
+    }
+
+    #[test]
+    fn compress_address_matches_rw_table() {
+        use eth_types::{ToScalar, ToWord};
+
+        for address in [
+            H160::zero(),
+            H160::from_low_u64_be(0xcafe),
+            H160::repeat_byte(0x5a),
+            H160::repeat_byte(0xff),
+        ] {
+            // The RwTable assigns the address as its integer value.
+            let expected: Fr = address.to_word().to_scalar().unwrap();
+            assert_eq!(compress_address_value::<Fr>(address), expected);
+
+            let (lo, hi) = constant(address.into()).into_lo_hi();
+            assert_eq!(eval(compress_address(lo, hi)), expected);
+        }
+
+        // The largest address doesn't wrap around the field modulus.
+        let max_plus_one: Fr = (Word::one() << 160).to_scalar().unwrap();
+        assert_eq!(
+            compress_address_value::<Fr>(H160::repeat_byte(0xff)) + Fr::ONE,
+            max_plus_one
+        );
+    }
+}
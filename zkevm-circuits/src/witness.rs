@@ -5,10 +5,11 @@
 mod block;
 ///
 pub mod chunk;
-pub use block::{block_convert, Block, BlockContext};
+mod codec;
+pub use block::{block_convert, block_convert_trusted, Block, BlockContext};
 pub use chunk::{chunk_convert, Chunk};
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates};
 pub mod rw;
 pub use bus_mapping::circuit_input_builder::{Call, ExecStep, Transaction, Withdrawal};
-pub use rw::{Rw, RwMap, RwRow};
+pub use rw::{AccountData, RevertedWritesError, Rw, RwDiff, RwMap, RwRow};
@@ -1,6 +1,10 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{self, Read, Write},
+};
 
 use super::{
+    codec,
     rw::{RwFingerprints, ToVec},
     ExecStep, Rw, RwMap, Transaction,
 };
@@ -8,18 +12,27 @@ use crate::{
     evm_circuit::{detect_fixed_table_tags, EvmCircuit},
     exp_circuit::param::OFFSET_INCREMENT,
     instance::public_data_convert,
-    table::BlockContextFieldTag,
+    table::{BlockContextFieldTag, CallContextFieldTag, TxReceiptFieldTag},
     util::{log2_ceil, unwrap_value, word::WordLoHi, SubCircuit},
     witness::Chunk,
 };
 use bus_mapping::{
     circuit_input_builder::{
-        self, CopyEvent, ExpEvent, FeatureConfig, FixedCParams, PrecompileEvents, Withdrawal,
+        self, CopyDataType, CopyEvent, ExpEvent, FeatureConfig, FixedCParams, NumberOrHash,
+        PrecompileEvents, Withdrawal,
     },
+    exec_trace::OperationRef,
+    mock::BlockData,
+    operation::{RWCounter, Target},
     state_db::CodeDB,
     Error,
 };
-use eth_types::{sign_types::SignData, Address, Field, ToScalar, Word, H256};
+use eth_types::{
+    evm_types::GasCost,
+    geth_types::{Account, GethData},
+    sign_types::SignData,
+    Address, Field, GethExecTrace, ToScalar, ToWord, Word, H256, U64,
+};
 
 use gadgets::permutation::get_permutation_fingerprints;
 use halo2_proofs::circuit::Value;
@@ -67,6 +80,10 @@ pub struct Block<F> {
     pub eth_block: eth_types::Block<eth_types::Transaction>,
     /// rw_table padding meta data
     pub rw_padding_meta: BTreeMap<usize, i32>,
+    /// Skip the debug-only rw checks (`RwMap::check_value` and
+    /// `RwMap::check_rw_counter_sanity`) for trusted witnesses, see
+    /// [`block_convert_trusted`].
+    pub skip_rw_value_check: bool,
 }
 
 impl<F: Field> Block<F> {
@@ -112,6 +129,48 @@ impl<F: Field> Block<F> {
         self.rws[step.rw_index(index)]
     }
 
+    /// Return the [`Target`]s of the read-write records in this block, e.g. to
+    /// check whether a workload touches the storage at all.
+    pub fn used_rw_targets(&self) -> HashSet<Target> {
+        self.rws
+            .0
+            .iter()
+            .filter(|(_, rws)| !rws.is_empty())
+            .map(|(target, _)| *target)
+            .collect()
+    }
+
+    /// Return the code hashes of the calls in this block whose bytecode is
+    /// missing from [`Block::bytecodes`], sorted and without duplicates.
+    /// Calls without code (to an account with the empty code hash) are not
+    /// reported.
+    pub fn missing_bytecodes(&self) -> Vec<Word> {
+        self.txs
+            .iter()
+            .flat_map(|tx| tx.calls())
+            .map(|call| call.code_hash)
+            .filter(|code_hash| !code_hash.is_zero() && *code_hash != CodeDB::empty_code_hash())
+            .sorted()
+            .dedup()
+            .filter(|code_hash| self.bytecodes.get_from_h256(code_hash).is_none())
+            .map(|code_hash| code_hash.to_word())
+            .collect()
+    }
+
+    /// Write the block in a compact binary format, so that it can be cached
+    /// and loaded back with [`Block::deserialize_from`] instead of being
+    /// converted again from the circuit input builder.
+    pub fn serialize_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(writer);
+        codec::encode_block(self, &mut writer)?;
+        writer.flush()
+    }
+
+    /// Read a block written by [`Block::serialize_to`].
+    pub fn deserialize_from<R: Read>(reader: R) -> io::Result<Self> {
+        codec::decode_block(&mut io::BufReader::new(reader))
+    }
+
     /// Return the list of withdrawals of this block.
     pub fn withdrawals(&self) -> Vec<Withdrawal> {
         let eth_withdrawals = self.eth_block.withdrawals.clone().unwrap_or_default();
@@ -306,12 +365,32 @@ impl From<&circuit_input_builder::Block> for BlockContext {
 /// Convert a block struct in bus-mapping to a witness block used in circuits
 pub fn block_convert<F: Field>(
     builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+) -> Result<Block<F>, Error> {
+    convert(builder, false)
+}
+
+/// Same as [`block_convert`], for trusted witnesses: the returned block has
+/// [`Block::skip_rw_value_check`] set, and the debug rw checks are skipped
+/// both here and in synthesis.
+pub fn block_convert_trusted<F: Field>(
+    builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+) -> Result<Block<F>, Error> {
+    convert(builder, true)
+}
+
+fn convert<F: Field>(
+    builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
+    skip_rw_value_check: bool,
 ) -> Result<Block<F>, Error> {
     let block = &builder.block;
     let code_db = &builder.code_db;
     let rws = RwMap::from(&block.container);
     let by_address_rws = rws.table_assignments(false);
-    rws.check_value();
+    if !skip_rw_value_check {
+        rws.check_value();
+        rws.check_rw_counter_sanity();
+        rws.check_cumulative_gas_used(block.txs());
+    }
 
     // get padding statistics data via BtreeMap
     // TODO we can implement it in more efficient version via range sum
@@ -352,6 +431,7 @@ pub fn block_convert<F: Field>(
         eth_block: block.eth_block.clone(),
         end_block: block.end_block.clone(),
         rw_padding_meta,
+        skip_rw_value_check,
     };
     let public_data = public_data_convert(&block);
 
@@ -365,9 +445,322 @@ pub fn block_convert<F: Field>(
     // PI Circuit
     block.keccak_inputs.extend_from_slice(&[rpi_bytes]);
 
+    // Fail early instead of with a bytecode lookup error during assignment.
+    if let Some(code_hash) = block.missing_bytecodes().first() {
+        return Err(Error::CodeNotFound(H256::from_uint(code_hash)));
+    }
+
     Ok(block)
 }
 
+impl<F: Field> Block<F> {
+    /// Witness of a block with a single transfer of `value` from `from` to
+    /// `to`, two accounts without code.  `from` is funded with exactly the
+    /// value and the fee of the transfer, at a gas price of 1 wei.
+    ///
+    /// The geth trace of such a transfer has no steps, so the block is built
+    /// from a synthesized trace instead of running the external tracer, and
+    /// then goes through the same bus-mapping path as any other block.  The
+    /// transaction is left unsigned, so the block is only suitable for the
+    /// EVM and State circuits.
+    pub fn simple_transfer(from: Address, to: Address, value: Word) -> Result<Self, Error> {
+        let chain_id = Word::one();
+        let gas_price = Word::one();
+        let balance = value
+            .checked_add(gas_price * GasCost::TX)
+            .ok_or(Error::InternalError(
+                "transfer value overflows the sender balance",
+            ))?;
+
+        let tx = eth_types::Transaction {
+            from,
+            to: Some(to),
+            value,
+            gas: GasCost::TX.into(),
+            gas_price: Some(gas_price),
+            chain_id: Some(chain_id),
+            block_number: Some(U64::one()),
+            transaction_index: Some(U64::zero()),
+            ..Default::default()
+        };
+        let eth_block = eth_types::Block {
+            author: Some(Address::zero()),
+            number: Some(U64::one()),
+            gas_limit: GasCost::TX.into(),
+            gas_used: GasCost::TX.into(),
+            base_fee_per_gas: Some(Word::zero()),
+            mix_hash: Some(H256::zero()),
+            withdrawals: Some(vec![]),
+            transactions: vec![tx],
+            ..Default::default()
+        };
+        let geth_data = GethData {
+            chain_id,
+            history_hashes: vec![],
+            eth_block,
+            geth_traces: vec![GethExecTrace {
+                gas: GasCost::TX,
+                failed: false,
+                invalid: false,
+                return_value: String::new(),
+                struct_logs: vec![],
+            }],
+            accounts: vec![Account {
+                address: from,
+                balance,
+                ..Default::default()
+            }],
+        };
+
+        let builder = BlockData::new_from_geth_data(geth_data.clone())
+            .new_circuit_input_builder()
+            .handle_block(&geth_data.eth_block, &geth_data.geth_traces)?;
+        block_convert(&builder)
+    }
+
+    /// Witness of a block with only the transaction at `tx_index`, to
+    /// reproduce the failure of a single transaction of a large block.
+    ///
+    /// The steps, rws, copy and exp events of the transaction are kept, with
+    /// their rw counters and call ids rebased to start at 1 and the tx id set
+    /// to 1.  The EndTx read of the cumulative gas used by the previous
+    /// transaction is dropped, since the first transaction of a block doesn't
+    /// do it, and the EndBlock step is rebuilt for the new last transaction.
+    /// The block is only suitable for the EVM and State circuits.
+    pub fn extract_tx(&self, tx_index: usize) -> Self {
+        let tx = &self.txs[tx_index];
+        let start = tx.steps()[0].rwc.0;
+        let end = self
+            .txs
+            .get(tx_index + 1)
+            .map_or(self.end_block.rwc.0, |next_tx| next_tx.steps()[0].rwc.0);
+
+        let is_prev_receipt = |rw: &Rw| matches!(rw, Rw::TxReceipt { is_write: false, tx_id, .. } if *tx_id as u64 + 1 == tx.id);
+        let is_kept = |rw: &Rw| {
+            !matches!(rw.tag(), Target::Start | Target::Padding)
+                && (start..end).contains(&rw.rw_counter())
+                && !is_prev_receipt(rw)
+        };
+        let prev_cumulative_gas_used = self
+            .rws
+            .0
+            .values()
+            .flatten()
+            .find(|rw| (start..end).contains(&rw.rw_counter()) && is_prev_receipt(rw))
+            .map_or(0, |rw| rw.receipt_value());
+
+        // Call ids are the rw counter at the beginning of the call, so both are
+        // rebased to the number of kept rws before them.
+        let kept_rw_counters = self
+            .rws
+            .0
+            .values()
+            .flatten()
+            .filter(|rw| is_kept(rw))
+            .map(|rw| rw.rw_counter())
+            .sorted()
+            .collect_vec();
+        let rebase =
+            |rw_counter: usize| kept_rw_counters.partition_point(|kept| *kept < rw_counter) + 1;
+        let rebase_id = |id: usize| if id == 0 { 0 } else { rebase(id) };
+        let rebase_rw = |rw: &Rw| {
+            let mut rw = *rw;
+            match &mut rw {
+                Rw::Start { rw_counter }
+                | Rw::Padding { rw_counter }
+                | Rw::Account { rw_counter, .. }
+                | Rw::StepState { rw_counter, .. } => *rw_counter = rebase(*rw_counter),
+                Rw::TxAccessListAccount {
+                    rw_counter, tx_id, ..
+                }
+                | Rw::TxAccessListAccountStorage {
+                    rw_counter, tx_id, ..
+                }
+                | Rw::TxRefund {
+                    rw_counter, tx_id, ..
+                }
+                | Rw::AccountStorage {
+                    rw_counter, tx_id, ..
+                }
+                | Rw::AccountTransientStorage {
+                    rw_counter, tx_id, ..
+                }
+                | Rw::TxLog {
+                    rw_counter, tx_id, ..
+                }
+                | Rw::TxReceipt {
+                    rw_counter, tx_id, ..
+                } => {
+                    *rw_counter = rebase(*rw_counter);
+                    *tx_id = 1;
+                }
+                Rw::Stack {
+                    rw_counter,
+                    call_id,
+                    ..
+                }
+                | Rw::Memory {
+                    rw_counter,
+                    call_id,
+                    ..
+                } => {
+                    *rw_counter = rebase(*rw_counter);
+                    *call_id = rebase(*call_id);
+                }
+                Rw::CallContext {
+                    rw_counter,
+                    call_id,
+                    field_tag,
+                    value,
+                    ..
+                } => {
+                    *rw_counter = rebase(*rw_counter);
+                    *call_id = rebase(*call_id);
+                    match field_tag {
+                        CallContextFieldTag::TxId => *value = Word::one(),
+                        CallContextFieldTag::CallerId
+                        | CallContextFieldTag::LastCalleeId
+                        | CallContextFieldTag::RwCounterEndOfReversion => {
+                            *value = rebase_id(value.as_usize()).to_word()
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if let Rw::TxReceipt {
+                field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+                value,
+                ..
+            } = &mut rw
+            {
+                *value -= prev_cumulative_gas_used;
+            }
+            rw
+        };
+
+        let mut rws = HashMap::new();
+        let mut rw_indices = HashMap::new();
+        for (tag, tag_rws) in self.rws.0.iter() {
+            let mut kept_rws = vec![];
+            for (idx, rw) in tag_rws.iter().enumerate() {
+                if is_kept(rw) {
+                    rw_indices.insert((*tag, idx), kept_rws.len());
+                    kept_rws.push(rebase_rw(rw));
+                }
+            }
+            rws.insert(*tag, kept_rws);
+        }
+
+        let mut tx = tx.clone();
+        tx.id = 1;
+        for step in tx.steps_mut() {
+            step.rwc = RWCounter(rebase(step.rwc.0));
+            step.rwc_inner_chunk = step.rwc;
+            step.bus_mapping_instance = step
+                .bus_mapping_instance
+                .iter()
+                .filter_map(|OperationRef(tag, idx)| {
+                    rw_indices
+                        .get(&(*tag, *idx))
+                        .map(|idx| OperationRef(*tag, *idx))
+                })
+                .collect();
+        }
+        for call in tx.calls_mut() {
+            call.call_id = rebase(call.call_id);
+            call.caller_id = rebase_id(call.caller_id);
+            call.last_callee_id = rebase_id(call.last_callee_id);
+            call.rw_counter_end_of_reversion = rebase_id(call.rw_counter_end_of_reversion);
+        }
+
+        // EndBlock reads the tx id of the last transaction, followed by the
+        // Start and Padding rws, as in `CircuitInputBuilder::set_end_block`.
+        let end_rwc = kept_rw_counters.len() + 1;
+        let max_rws = self.circuits_params.max_rws;
+        let mut end_block = self.end_block.clone();
+        end_block.rwc = RWCounter(end_rwc);
+        end_block.rwc_inner_chunk = RWCounter(end_rwc);
+        let call_contexts = rws.entry(Target::CallContext).or_default();
+        end_block.bus_mapping_instance = vec![
+            OperationRef(Target::CallContext, call_contexts.len()),
+            OperationRef(Target::Start, 0),
+        ];
+        call_contexts.push(Rw::CallContext {
+            rw_counter: end_rwc,
+            is_write: false,
+            call_id: tx.calls()[0].call_id,
+            field_tag: CallContextFieldTag::TxId,
+            value: Word::one(),
+        });
+        rws.insert(Target::Start, vec![Rw::Start { rw_counter: 1 }]);
+        let paddings = [end_rwc + 1, max_rws - 1]
+            .into_iter()
+            .filter(|_| max_rws > end_rwc + 1)
+            .dedup()
+            .map(|rw_counter| Rw::Padding { rw_counter })
+            .collect_vec();
+        end_block
+            .bus_mapping_instance
+            .extend((0..paddings.len()).map(|idx| OperationRef(Target::Padding, idx)));
+        rws.insert(Target::Padding, paddings);
+        let rws = RwMap(rws);
+
+        let copy_events = self
+            .copy_events
+            .iter()
+            .filter(|event| (start..end).contains(&event.rw_counter_start.0))
+            .map(|event| {
+                let mut event = event.clone();
+                event.rw_counter_start = RWCounter(rebase(event.rw_counter_start.0));
+                for (data_type, id) in [
+                    (event.src_type, &mut event.src_id),
+                    (event.dst_type, &mut event.dst_id),
+                ] {
+                    if let NumberOrHash::Number(id) = id {
+                        match data_type {
+                            CopyDataType::TxCalldata | CopyDataType::TxLog => *id = 1,
+                            CopyDataType::Memory | CopyDataType::RlcAcc => *id = rebase_id(*id),
+                            _ => {}
+                        }
+                    }
+                }
+                event
+            })
+            .collect();
+        let exp_events = self
+            .exp_events
+            .iter()
+            .filter(|event| (start..end).contains(&event.identifier))
+            .map(|event| ExpEvent {
+                identifier: rebase(event.identifier),
+                ..event.clone()
+            })
+            .collect();
+
+        let mut eth_block = self.eth_block.clone();
+        eth_block.transactions = eth_block
+            .transactions
+            .get(tx_index)
+            .cloned()
+            .into_iter()
+            .collect();
+
+        Self {
+            txs: vec![tx],
+            end_block,
+            by_address_rws: rws.table_assignments(false),
+            rws,
+            copy_events,
+            exp_events,
+            eth_block,
+            rw_padding_meta: (end_rwc + 1..max_rws)
+                .map(|rw_counter| (rw_counter, 1))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
 #[allow(dead_code)]
 fn get_rwtable_fingerprints<F: Field>(
     alpha: F,
@@ -396,3 +789,260 @@ fn get_rwtable_fingerprints<F: Field>(
         })
         .unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_util::CircuitTestBuilder,
+        witness::{chunk_convert, Chunk},
+    };
+    use bus_mapping::{
+        circuit_input_builder::CircuitInputBuilder,
+        operation::{Operation, StackOp, RW},
+    };
+    use eth_types::{bytecode, evm_types::StackAddress};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+    #[test]
+    fn used_rw_targets_arithmetic() {
+        let code = bytecode! {
+            PUSH1(0x03)
+            PUSH1(0x04)
+            ADD
+            PUSH1(0x05)
+            MUL
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let targets = block.used_rw_targets();
+        assert!(targets.contains(&Target::Stack));
+        assert!(targets.contains(&Target::CallContext));
+        for target in [
+            Target::Memory,
+            Target::Storage,
+            Target::TransientStorage,
+            Target::TxAccessListAccountStorage,
+            Target::TxLog,
+        ] {
+            assert!(!targets.contains(&target), "unexpected {:?} rws", target);
+        }
+    }
+
+    #[test]
+    fn block_convert_trusted_skips_rw_value_check() {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP })
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        assert!(!block_convert::<Fr>(&builder).unwrap().skip_rw_value_check);
+        let block = block_convert_trusted::<Fr>(&builder).unwrap();
+        assert!(block.skip_rw_value_check);
+
+        let chunks = chunk_convert(&block, &builder).unwrap();
+        CircuitTestBuilder::<2, 1>::new_from_block(block, chunks).run();
+    }
+
+    /// Builder of a block with a stack read appended far after the last rw
+    /// counter, so that the rw counters have a gap.
+    fn builder_with_rw_counter_gap() -> CircuitInputBuilder<FixedCParams> {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP })
+            .unwrap()
+            .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        builder.block.container.insert(Operation::new(
+            RWCounter(100_000),
+            RWCounter(100_000),
+            RW::READ,
+            StackOp::new(1, StackAddress(1023), Word::zero()),
+        ));
+        builder
+    }
+
+    #[test]
+    #[should_panic(expected = "rw counter gap")]
+    fn block_convert_checks_rw_counters() {
+        let _ = block_convert::<Fr>(&builder_with_rw_counter_gap());
+    }
+
+    #[test]
+    fn block_convert_trusted_skips_rw_counter_check() {
+        let block = block_convert_trusted::<Fr>(&builder_with_rw_counter_gap()).unwrap();
+        assert!(block.skip_rw_value_check);
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            SHA3
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20)
+            PUSH1(0x00)
+            LOG0
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunks = chunk_convert(&block, &builder).unwrap();
+
+        let mut bytes = Vec::new();
+        block.serialize_to(&mut bytes).unwrap();
+        let decoded = Block::<Fr>::deserialize_from(bytes.as_slice()).unwrap();
+
+        let mut decoded_bytes = Vec::new();
+        decoded.serialize_to(&mut decoded_bytes).unwrap();
+        assert_eq!(bytes, decoded_bytes);
+        assert_eq!(
+            format!("{:?}", block.by_address_rws),
+            format!("{:?}", decoded.by_address_rws)
+        );
+        decoded.rws.check_rw_counter_sanity();
+
+        // A truncated cache is reported instead of silently decoded.
+        assert!(Block::<Fr>::deserialize_from(&bytes[..bytes.len() - 1]).is_err());
+
+        CircuitTestBuilder::<2, 1>::new_from_block(decoded, chunks).run();
+    }
+
+    #[test]
+    fn missing_bytecodes() {
+        let callee = bytecode! {
+            PUSH1(0x01)
+            STOP
+        };
+        let callee_hash = CodeDB::hash(&callee.code());
+        let caller = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(Address::repeat_byte(0xff).to_word())
+            PUSH2(0xffff) // gas
+            CALL
+            STOP
+        };
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(Address::repeat_byte(0xfe)).code(caller);
+                accs[1].address(Address::repeat_byte(0xff)).code(callee);
+                accs[2]
+                    .address(Address::repeat_byte(0x01))
+                    .balance(Word::from(1u64 << 60));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _| block,
+        )
+        .unwrap()
+        .into();
+        let mut builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        assert!(block.missing_bytecodes().is_empty());
+
+        // Uninstall the callee code, which is still referenced by the CALL.
+        builder.code_db = CodeDB::from(
+            builder
+                .code_db
+                .clone()
+                .into_iter()
+                .map(|bytecode| bytecode.code())
+                .filter(|code| CodeDB::hash(code) != callee_hash)
+                .collect::<Vec<_>>(),
+        );
+        match block_convert::<Fr>(&builder) {
+            Err(Error::CodeNotFound(code_hash)) => assert_eq!(code_hash, callee_hash),
+            other => panic!("expected CodeNotFound, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn simple_transfer() {
+        let from = Address::repeat_byte(0x10);
+        let to = Address::repeat_byte(0x20);
+        let value = Word::from(0xabcdefu64);
+        let block = Block::<Fr>::simple_transfer(from, to, value).unwrap();
+
+        let accounts = block.rws.final_accounts();
+        assert_eq!(accounts[&from].nonce, Word::one());
+        assert_eq!(accounts[&from].balance, Word::zero());
+        assert_eq!(accounts[&to].balance, value);
+
+        let chunk = Chunk::single(&block, block.circuits_params);
+        CircuitTestBuilder::<2, 1>::new_from_block(block, vec![chunk]).run();
+
+        // The sender can't be funded with the value and the fee
+        assert!(matches!(
+            Block::<Fr>::simple_transfer(from, to, Word::MAX),
+            Err(Error::InternalError(_))
+        ));
+    }
+
+    #[test]
+    fn extract_tx() {
+        let block: GethData = TestContext::<2, 3>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            |mut txs, accs| {
+                for tx in txs.iter_mut() {
+                    tx.to(accs[0].address).from(accs[1].address).value(eth(1));
+                }
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let extracted = block.extract_tx(1);
+        assert_eq!(extracted.txs.len(), 1);
+        assert_eq!(extracted.txs[0].id, 1);
+        assert_eq!(extracted.txs[0].steps()[0].rwc.0, 1);
+        assert_eq!(extracted.txs[0].steps().len(), block.txs[1].steps().len());
+        // The rws of the sender continue from its state after the first transaction
+        assert_eq!(
+            extracted.rws.final_accounts()[&block.txs[1].tx.from].nonce,
+            Word::from(2)
+        );
+
+        let chunk = Chunk::single(&extracted, extracted.circuits_params);
+        CircuitTestBuilder::<2, 3>::new_from_block(extracted, vec![chunk]).run();
+    }
+}
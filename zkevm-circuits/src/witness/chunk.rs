@@ -7,8 +7,8 @@ use super::{
 };
 use crate::util::unwrap_value;
 use bus_mapping::{
-    circuit_input_builder::{self, Call, ChunkContext, FixedCParams},
-    operation::Target,
+    circuit_input_builder::{self, Call, ChunkContext, ExecState, FixedCParams},
+    operation::{RWCounter, Target},
     Error,
 };
 use eth_types::Field;
@@ -76,16 +76,125 @@ impl<F: Field> Default for Chunk<F> {
     }
 }
 
+impl<F: Field> Chunk<F> {
+    /// Number of rws of the chunk, in the global rw counter range
+    /// `[initial_rwc, end_rwc)`.  Together with the Start row of the rw table
+    /// it must stay below `fixed_param.max_rws`.
+    pub fn rw_count(&self) -> usize {
+        self.chunk_context
+            .end_rwc
+            .saturating_sub(self.chunk_context.initial_rwc)
+    }
+
+    /// Build the only chunk of a block proven in single-chunk mode, spanning
+    /// all the rws of the block.  Equivalent to the first and only chunk
+    /// returned by [`chunk_convert`] for a block built with `total_chunks = 1`.
+    pub fn single(block: &Block<F>, params: FixedCParams) -> Self {
+        let (alpha, gamma) = permutation_challenges();
+
+        // The Start and Padding rows are pushed when the rw table is padded,
+        // after the execution, so they don't count in the rw range.
+        let end_rwc = block
+            .rws
+            .0
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, Target::Start | Target::Padding))
+            .flat_map(|(_, rws)| rws.iter().map(|rw| rw.rw_counter()))
+            .max()
+            .map_or(1, |rw_counter| rw_counter + 1);
+        let chunk_context = ChunkContext {
+            idx: 0,
+            rwc: RWCounter(end_rwc),
+            total_chunks: 1,
+            initial_rwc: 1,
+            end_rwc,
+            initial_tx_index: 0,
+            end_tx_index: block.txs.len(),
+            initial_copy_index: 0,
+            end_copy_index: block.copy_events.len(),
+        };
+
+        let chrono_rws = {
+            let mut chrono_rws = block.rws.clone();
+            if let Some(padding_vec) = chrono_rws.0.get_mut(&Target::Padding) {
+                padding_vec.clear()
+            }
+            chrono_rws.take_rw_counter_range(1, end_rwc)
+        };
+        let by_address_rws = RwMap::from(
+            padded_by_address_rws(block)
+                .take(params.max_rws)
+                .collect::<Vec<_>>(),
+        );
+
+        let by_address_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
+            &by_address_rws,
+            params.max_rws,
+            alpha,
+            gamma,
+            F::from(1),
+            false,
+            None,
+        );
+        let chrono_rw_fingerprints = get_permutation_fingerprint_of_rwmap(
+            &chrono_rws,
+            params.max_rws,
+            alpha,
+            gamma,
+            F::from(1),
+            true,
+            None,
+        );
+
+        // The padding step repeats the EndBlock step state, without its rws
+        let mut padding = block.end_block.clone();
+        padding.exec_state = ExecState::Padding;
+        padding.bus_mapping_instance = vec![];
+
+        Self {
+            begin_chunk: None,
+            end_chunk: None,
+            padding: Some(padding),
+            chunk_context,
+            chrono_rws,
+            by_address_rws,
+            permu_alpha: alpha,
+            permu_gamma: gamma,
+            by_address_rw_fingerprints,
+            chrono_rw_fingerprints,
+            fixed_param: params,
+            prev_last_call: None,
+            prev_chunk_last_chrono_rw: None,
+            prev_chunk_last_by_address_rw: None,
+        }
+    }
+}
+
+// Todo: poseidon hash to compute alpha/gamma
+fn permutation_challenges<F: Field>() -> (F, F) {
+    (F::from(103), F::from(101))
+}
+
+/// The by address sorted rws of the block, with the padding rows attached at
+/// the end.
+fn padded_by_address_rws<F: Field>(block: &Block<F>) -> impl Iterator<Item = Rw> + '_ {
+    block
+        .by_address_rws
+        .iter()
+        // remove paading here since it will be attached later
+        .filter(|rw| rw.tag() != Target::Padding)
+        .cloned() // TODO avoid clone here
+        .chain(block.rw_padding_meta.iter().flat_map(|(k, v)| {
+            vec![Rw::Padding { rw_counter: *k }; <i32 as TryInto<usize>>::try_into(*v).unwrap()]
+        }))
+}
+
 /// Convert the idx-th chunk struct in bus-mapping to a witness chunk used in circuits
 pub fn chunk_convert<F: Field>(
     block: &Block<F>,
     builder: &circuit_input_builder::CircuitInputBuilder<FixedCParams>,
 ) -> Result<Vec<Chunk<F>>, Error> {
-    let (by_address_rws, padding_meta) = (&block.by_address_rws, &block.rw_padding_meta);
-
-    // Todo: poseidon hash to compute alpha/gamma
-    let alpha = F::from(103);
-    let gamma = F::from(101);
+    let (alpha, gamma) = permutation_challenges();
 
     let mut chunks: Vec<Chunk<F>> = Vec::with_capacity(builder.chunks.len());
     for (i, (prev_chunk, chunk)) in iter::once(None) // left append `None` to make iteration easier
@@ -125,17 +234,7 @@ pub fn chunk_convert<F: Field>(
             let size = builder.circuits_params.max_rws;
             // by_address_rws[start..end].to_vec()
 
-            let skipped = by_address_rws
-                .iter()
-                // remove paading here since it will be attached later
-                .filter(|rw| rw.tag() != Target::Padding)
-                .cloned() // TODO avoid clone here
-                .chain(padding_meta.iter().flat_map(|(k, v)| {
-                    vec![
-                        Rw::Padding { rw_counter: *k };
-                        <i32 as TryInto<usize>>::try_into(*v).unwrap()
-                    ]
-                }));
+            let skipped = padded_by_address_rws(block);
             // there is no previous chunk
             if start == 0 {
                 (None, RwMap::from(skipped.take(size).collect::<Vec<_>>()))
@@ -315,3 +414,72 @@ pub fn get_permutation_randomness<F: Field>() -> (F, F) {
     // Todo
     (F::from(1), F::from(1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::CircuitTestBuilder, witness::block_convert};
+    use bus_mapping::mock::BlockData;
+    use eth_types::{bytecode, geth_types::GethData};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::TestContext;
+
+    // Check `Chunk::single` matches the only chunk of `chunk_convert`, and
+    // that the block is proven with it.
+    fn test_single_chunk<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
+        let block: GethData = ctx.into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+        let chunk = Chunk::single(&block, builder.circuits_params);
+
+        let mut chunks = chunk_convert(&block, &builder).unwrap();
+        assert_eq!(chunks.len(), 1);
+        let expected = chunks.remove(0);
+        assert_eq!(
+            format!("{:?}", chunk.chunk_context),
+            format!("{:?}", expected.chunk_context)
+        );
+        assert_eq!(
+            format!("{:?}", chunk.chrono_rws),
+            format!("{:?}", expected.chrono_rws)
+        );
+        assert_eq!(
+            format!("{:?}", chunk.by_address_rws),
+            format!("{:?}", expected.by_address_rws)
+        );
+        assert_eq!(
+            format!("{:?}", chunk.by_address_rw_fingerprints),
+            format!("{:?}", expected.by_address_rw_fingerprints)
+        );
+        assert_eq!(
+            format!("{:?}", chunk.chrono_rw_fingerprints),
+            format!("{:?}", expected.chrono_rw_fingerprints)
+        );
+
+        CircuitTestBuilder::<NACC, NTX>::new_from_block(block, vec![chunk]).run();
+    }
+
+    #[test]
+    fn single_chunk_empty_block() {
+        test_single_chunk(TestContext::<0, 0>::new(None, |_| {}, |_, _| {}, |b, _| b).unwrap());
+    }
+
+    #[test]
+    fn single_chunk_memory_and_storage() {
+        let code = bytecode! {
+            PUSH1(0x2a)
+            PUSH1(0)
+            MSTORE
+            PUSH1(0x2a)
+            PUSH1(0)
+            SSTORE
+            PUSH1(0)
+            SLOAD
+            STOP
+        };
+        test_single_chunk(TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap());
+    }
+}
@@ -0,0 +1,1305 @@
+//! Compact binary encoding of the [`Block`] witness, used to cache converted
+//! blocks between runs.
+//!
+//! Integers are written little-endian with a fixed width, sequences and maps
+//! are prefixed by their length as a `u64` and enums by their variant index
+//! as a `u8`. Hash maps are written sorted by their encoded keys so that the
+//! encoding of a block is deterministic. Field elements are written as their
+//! canonical byte representation. The original `eth_block` is the only field
+//! stored as JSON, since it is an `ethers` type which already implements
+//! serde.
+
+use super::{Block, BlockContext, Rw, RwMap};
+use crate::table::{
+    AccountFieldTag, CallContextFieldTag, StepStateFieldTag, TxLogFieldTag, TxReceiptFieldTag,
+};
+use bus_mapping::{
+    circuit_input_builder::{
+        Call, CallKind, CodeSource, CopyDataType, CopyEvent, ExecState, ExecStep, ExpEvent,
+        ExpStep, FeatureConfig, FixedCParams, Hardfork, NumberOrHash, PrecompileEvent,
+        PrecompileEvents, Transaction,
+    },
+    error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
+    exec_trace::OperationRef,
+    operation::{RWCounter, Target},
+    precompile::{EcrecoverAuxData, PrecompileAuxData, PrecompileCalls},
+    state_db::CodeDB,
+};
+use eth_types::{
+    evm_types::OpcodeId,
+    geth_types::{self, TxType},
+    sign_types::SignData,
+    AccessList, AccessListItem, Address, Bytes, Field, Word, H256, U64,
+};
+use halo2_proofs::halo2curves::{
+    ff::PrimeField,
+    secp256k1::{self, Secp256k1Affine},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    io::{self, Read, Write},
+};
+use strum::IntoEnumIterator;
+
+/// A value that can be written in the witness cache format.
+pub(crate) trait Encode {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+/// A value that can be read back from the witness cache format.
+pub(crate) trait Decode: Sized {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+fn invalid_data(msg: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn encode_variant<W: Write>(writer: &mut W, variant: u8) -> io::Result<()> {
+    variant.encode(writer)
+}
+
+/// Encode a field element as its canonical byte representation.
+pub(crate) fn encode_field<F: Field, W: Write>(value: &F, writer: &mut W) -> io::Result<()> {
+    writer.write_all(&value.to_repr())
+}
+
+/// Decode a field element, rejecting non canonical representations.
+pub(crate) fn decode_field<F: Field, R: Read>(reader: &mut R) -> io::Result<F> {
+    Option::from(F::from_repr(read_array(reader)?))
+        .ok_or_else(|| invalid_data("non canonical field element"))
+}
+
+macro_rules! impl_codec_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    Ok(<$ty>::from_le_bytes(read_array(reader)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_int!(u8, i32, u64);
+
+impl Encode for usize {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).encode(writer)
+    }
+}
+
+impl Decode for usize {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        usize::try_from(u64::decode(reader)?).map_err(invalid_data)
+    }
+}
+
+impl Encode for bool {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).encode(writer)
+    }
+}
+
+impl Decode for bool {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(invalid_data(format!("invalid bool {}", value))),
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.len().encode(writer)?;
+        self.iter().try_for_each(|item| item.encode(writer))
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.as_slice().encode(writer)
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = usize::decode(reader)?;
+        (0..len).map(|_| T::decode(reader)).collect()
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.is_some().encode(writer)?;
+        match self {
+            Some(value) => value.encode(writer),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(if bool::decode(reader)? {
+            Some(T::decode(reader)?)
+        } else {
+            None
+        })
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.encode(writer)?;
+        self.1.encode(writer)
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok((A::decode(reader)?, B::decode(reader)?))
+    }
+}
+
+impl<A: Encode, B: Encode, C: Encode> Encode for (A, B, C) {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.encode(writer)?;
+        self.1.encode(writer)?;
+        self.2.encode(writer)
+    }
+}
+
+impl<A: Decode, B: Decode, C: Decode> Decode for (A, B, C) {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok((A::decode(reader)?, B::decode(reader)?, C::decode(reader)?))
+    }
+}
+
+impl<K: Encode, V: Encode> Encode for BTreeMap<K, V> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.len().encode(writer)?;
+        self.iter().try_for_each(|entry| entry.encode(writer))
+    }
+}
+
+impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Vec::<(K, V)>::decode(reader).map(BTreeMap::from_iter)
+    }
+}
+
+impl<K: Encode, V: Encode> Encode for HashMap<K, V> {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut entries = self
+            .iter()
+            .map(|(key, value)| {
+                let mut key_bytes = Vec::new();
+                key.encode(&mut key_bytes)?;
+                Ok((key_bytes, value))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        entries.len().encode(writer)?;
+        entries.into_iter().try_for_each(|(key_bytes, value)| {
+            writer.write_all(&key_bytes)?;
+            value.encode(writer)
+        })
+    }
+}
+
+impl<K: Decode + Eq + Hash, V: Decode> Decode for HashMap<K, V> {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Vec::<(K, V)>::decode(reader).map(HashMap::from_iter)
+    }
+}
+
+impl Encode for Word {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut bytes = [0u8; 32];
+        self.to_little_endian(&mut bytes);
+        writer.write_all(&bytes)
+    }
+}
+
+impl Decode for Word {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Word::from_little_endian(&read_array::<_, 32>(reader)?))
+    }
+}
+
+impl Encode for U64 {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.as_u64().encode(writer)
+    }
+}
+
+impl Decode for U64 {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        u64::decode(reader).map(U64::from)
+    }
+}
+
+impl Encode for Address {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl Decode for Address {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Address::from(read_array::<_, 20>(reader)?))
+    }
+}
+
+impl Encode for H256 {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl Decode for H256 {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(H256::from(read_array::<_, 32>(reader)?))
+    }
+}
+
+impl Encode for Bytes {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self[..].encode(writer)
+    }
+}
+
+impl Decode for Bytes {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Vec::<u8>::decode(reader).map(Bytes::from)
+    }
+}
+
+impl Encode for secp256k1::Fq {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.to_repr().as_ref())
+    }
+}
+
+impl Decode for secp256k1::Fq {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut repr = <secp256k1::Fq as PrimeField>::Repr::default();
+        reader.read_exact(repr.as_mut())?;
+        Option::from(secp256k1::Fq::from_repr(repr))
+            .ok_or_else(|| invalid_data("non canonical secp256k1 scalar"))
+    }
+}
+
+impl Encode for secp256k1::Fp {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.to_repr().as_ref())
+    }
+}
+
+impl Decode for secp256k1::Fp {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut repr = <secp256k1::Fp as PrimeField>::Repr::default();
+        reader.read_exact(repr.as_mut())?;
+        Option::from(secp256k1::Fp::from_repr(repr))
+            .ok_or_else(|| invalid_data("non canonical secp256k1 base field element"))
+    }
+}
+
+// The affine coordinates are stored as is, so that the identity (used by the
+// default SignData) round trips as well.
+impl Encode for Secp256k1Affine {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.x.encode(writer)?;
+        self.y.encode(writer)
+    }
+}
+
+impl Decode for Secp256k1Affine {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Secp256k1Affine {
+            x: Decode::decode(reader)?,
+            y: Decode::decode(reader)?,
+        })
+    }
+}
+
+/// Implement the codec of a struct with public fields, field by field.
+macro_rules! impl_codec_struct {
+    ($ty:ty { $($field:ident),* $(,)? }) => {
+        impl Encode for $ty {
+            fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                $(self.$field.encode(writer)?;)*
+                Ok(())
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+                Ok(Self {
+                    $($field: Decode::decode(reader)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Implement the codec of a fieldless enum by its index in `$ty::iter()`.
+macro_rules! impl_codec_enum_iter {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Encode for $ty {
+                fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    let variant = <$ty>::iter().position(|variant| variant == *self).unwrap();
+                    encode_variant(writer, variant as u8)
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let variant = u8::decode(reader)?;
+                    <$ty>::iter().nth(variant as usize).ok_or_else(|| {
+                        invalid_data(format!("invalid {} {}", stringify!($ty), variant))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+/// Implement the codec of a fieldless enum by its index in `$variants`.
+macro_rules! impl_codec_enum {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl Encode for $ty {
+            fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                let variants = [$($ty::$variant),*];
+                let variant = variants.iter().position(|variant| variant == self).unwrap();
+                encode_variant(writer, variant as u8)
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let variants = [$($ty::$variant),*];
+                let variant = u8::decode(reader)?;
+                variants.get(variant as usize).cloned().ok_or_else(|| {
+                    invalid_data(format!("invalid {} {}", stringify!($ty), variant))
+                })
+            }
+        }
+    };
+}
+
+impl_codec_enum_iter!(
+    Target,
+    CopyDataType,
+    TxType,
+    AccountFieldTag,
+    CallContextFieldTag,
+    StepStateFieldTag,
+    TxLogFieldTag,
+    TxReceiptFieldTag,
+);
+
+impl_codec_enum!(CallKind {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+});
+
+impl_codec_enum!(OogError {
+    Constant,
+    StaticMemoryExpansion,
+    DynamicMemoryExpansion,
+    MemoryCopy,
+    AccountAccess,
+    CodeStore,
+    Log,
+    Exp,
+    Sha3,
+    SloadSstore,
+    Call,
+    Precompile,
+    Create,
+    SelfDestruct,
+});
+
+impl_codec_enum!(InsufficientBalanceError {
+    Call,
+    Create,
+    Create2
+});
+
+impl_codec_enum!(NonceUintOverflowError { Create, Create2 });
+
+impl_codec_enum!(Hardfork {
+    Istanbul,
+    Berlin,
+    London
+});
+
+impl_codec_enum!(DepthError {
+    Call,
+    Create,
+    Create2
+});
+
+impl Encode for OpcodeId {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.as_u8().encode(writer)
+    }
+}
+
+impl Decode for OpcodeId {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        u8::decode(reader).map(OpcodeId::from)
+    }
+}
+
+impl Encode for PrecompileCalls {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).encode(writer)
+    }
+}
+
+impl Decode for PrecompileCalls {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::decode(reader)? {
+            address @ 0x01..=0x09 => Ok(PrecompileCalls::from(address)),
+            address => Err(invalid_data(format!("invalid PrecompileCalls {}", address))),
+        }
+    }
+}
+
+impl Encode for ExecState {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            ExecState::Op(opcode) => {
+                encode_variant(writer, 0)?;
+                opcode.encode(writer)
+            }
+            ExecState::Precompile(precompile) => {
+                encode_variant(writer, 1)?;
+                precompile.encode(writer)
+            }
+            ExecState::BeginChunk => encode_variant(writer, 2),
+            ExecState::BeginTx => encode_variant(writer, 3),
+            ExecState::EndTx => encode_variant(writer, 4),
+            ExecState::Padding => encode_variant(writer, 5),
+            ExecState::EndBlock => encode_variant(writer, 6),
+            ExecState::EndChunk => encode_variant(writer, 7),
+            ExecState::InvalidTx => encode_variant(writer, 8),
+        }
+    }
+}
+
+impl Decode for ExecState {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => ExecState::Op(Decode::decode(reader)?),
+            1 => ExecState::Precompile(Decode::decode(reader)?),
+            2 => ExecState::BeginChunk,
+            3 => ExecState::BeginTx,
+            4 => ExecState::EndTx,
+            5 => ExecState::Padding,
+            6 => ExecState::EndBlock,
+            7 => ExecState::EndChunk,
+            8 => ExecState::InvalidTx,
+            variant => return Err(invalid_data(format!("invalid ExecState {}", variant))),
+        })
+    }
+}
+
+impl Encode for ExecError {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            ExecError::InvalidOpcode => encode_variant(writer, 0),
+            ExecError::StackOverflow => encode_variant(writer, 1),
+            ExecError::StackUnderflow => encode_variant(writer, 2),
+            ExecError::OutOfGas(err) => {
+                encode_variant(writer, 3)?;
+                err.encode(writer)
+            }
+            ExecError::WriteProtection => encode_variant(writer, 4),
+            ExecError::Depth(err) => {
+                encode_variant(writer, 5)?;
+                err.encode(writer)
+            }
+            ExecError::InsufficientBalance(err) => {
+                encode_variant(writer, 6)?;
+                err.encode(writer)
+            }
+            ExecError::ContractAddressCollision => encode_variant(writer, 7),
+            ExecError::InvalidCreationCode => encode_variant(writer, 8),
+            ExecError::InvalidJump => encode_variant(writer, 9),
+            ExecError::ReturnDataOutOfBounds => encode_variant(writer, 10),
+            ExecError::CodeStoreOutOfGas => encode_variant(writer, 11),
+            ExecError::MaxCodeSizeExceeded => encode_variant(writer, 12),
+            ExecError::UnimplementedPrecompiles => encode_variant(writer, 13),
+            ExecError::NonceUintOverflow(err) => {
+                encode_variant(writer, 14)?;
+                err.encode(writer)
+            }
+        }
+    }
+}
+
+impl Decode for ExecError {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => ExecError::InvalidOpcode,
+            1 => ExecError::StackOverflow,
+            2 => ExecError::StackUnderflow,
+            3 => ExecError::OutOfGas(Decode::decode(reader)?),
+            4 => ExecError::WriteProtection,
+            5 => ExecError::Depth(Decode::decode(reader)?),
+            6 => ExecError::InsufficientBalance(Decode::decode(reader)?),
+            7 => ExecError::ContractAddressCollision,
+            8 => ExecError::InvalidCreationCode,
+            9 => ExecError::InvalidJump,
+            10 => ExecError::ReturnDataOutOfBounds,
+            11 => ExecError::CodeStoreOutOfGas,
+            12 => ExecError::MaxCodeSizeExceeded,
+            13 => ExecError::UnimplementedPrecompiles,
+            14 => ExecError::NonceUintOverflow(Decode::decode(reader)?),
+            variant => return Err(invalid_data(format!("invalid ExecError {}", variant))),
+        })
+    }
+}
+
+impl Encode for CodeSource {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            CodeSource::Address(address) => {
+                encode_variant(writer, 0)?;
+                address.encode(writer)
+            }
+            CodeSource::Tx => encode_variant(writer, 1),
+            CodeSource::Memory => encode_variant(writer, 2),
+        }
+    }
+}
+
+impl Decode for CodeSource {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => CodeSource::Address(Decode::decode(reader)?),
+            1 => CodeSource::Tx,
+            2 => CodeSource::Memory,
+            variant => return Err(invalid_data(format!("invalid CodeSource {}", variant))),
+        })
+    }
+}
+
+impl Encode for NumberOrHash {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            NumberOrHash::Number(number) => {
+                encode_variant(writer, 0)?;
+                number.encode(writer)
+            }
+            NumberOrHash::Hash(hash) => {
+                encode_variant(writer, 1)?;
+                hash.encode(writer)
+            }
+        }
+    }
+}
+
+impl Decode for NumberOrHash {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => NumberOrHash::Number(Decode::decode(reader)?),
+            1 => NumberOrHash::Hash(Decode::decode(reader)?),
+            variant => return Err(invalid_data(format!("invalid NumberOrHash {}", variant))),
+        })
+    }
+}
+
+impl Encode for PrecompileAuxData {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            PrecompileAuxData::Base {
+                input_bytes,
+                output_bytes,
+                return_bytes,
+            } => {
+                encode_variant(writer, 0)?;
+                input_bytes.encode(writer)?;
+                output_bytes.encode(writer)?;
+                return_bytes.encode(writer)
+            }
+            PrecompileAuxData::Ecrecover(aux_data) => {
+                encode_variant(writer, 1)?;
+                aux_data.encode(writer)
+            }
+        }
+    }
+}
+
+impl Decode for PrecompileAuxData {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => PrecompileAuxData::Base {
+                input_bytes: Decode::decode(reader)?,
+                output_bytes: Decode::decode(reader)?,
+                return_bytes: Decode::decode(reader)?,
+            },
+            1 => PrecompileAuxData::Ecrecover(Decode::decode(reader)?),
+            variant => {
+                return Err(invalid_data(format!(
+                    "invalid PrecompileAuxData {}",
+                    variant
+                )))
+            }
+        })
+    }
+}
+
+impl Encode for PrecompileEvent {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let PrecompileEvent::Ecrecover(sign_data) = self;
+        encode_variant(writer, 0)?;
+        sign_data.encode(writer)
+    }
+}
+
+impl Decode for PrecompileEvent {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => PrecompileEvent::Ecrecover(Decode::decode(reader)?),
+            variant => return Err(invalid_data(format!("invalid PrecompileEvent {}", variant))),
+        })
+    }
+}
+
+impl Encode for OperationRef {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.encode(writer)?;
+        self.1.encode(writer)
+    }
+}
+
+impl Decode for OperationRef {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(OperationRef(
+            Decode::decode(reader)?,
+            Decode::decode(reader)?,
+        ))
+    }
+}
+
+impl Encode for RWCounter {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.encode(writer)
+    }
+}
+
+impl Decode for RWCounter {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        usize::decode(reader).map(RWCounter)
+    }
+}
+
+impl Encode for AccessList {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.encode(writer)
+    }
+}
+
+impl Decode for AccessList {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Vec::decode(reader).map(AccessList)
+    }
+}
+
+// Codes are written sorted, the code hashes are recomputed when reading.
+impl Encode for CodeDB {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut codes = self
+            .clone()
+            .into_iter()
+            .map(|bytecode| bytecode.code())
+            .collect::<Vec<_>>();
+        codes.sort();
+        codes.encode(writer)
+    }
+}
+
+impl Decode for CodeDB {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Vec::<Vec<u8>>::decode(reader).map(CodeDB::from)
+    }
+}
+
+impl Encode for Transaction {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.id.encode(writer)?;
+        self.tx.encode(writer)?;
+        self.calls().encode(writer)?;
+        self.steps().encode(writer)
+    }
+}
+
+impl Decode for Transaction {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        // Calls and steps are private to bus-mapping, so the transaction is
+        // filled in place instead of being built as a struct literal.
+        let mut tx = Transaction::default();
+        tx.id = Decode::decode(reader)?;
+        tx.tx = Decode::decode(reader)?;
+        *tx.calls_mut() = Decode::decode(reader)?;
+        *tx.steps_mut() = Decode::decode(reader)?;
+        Ok(tx)
+    }
+}
+
+impl_codec_struct!(AccessListItem {
+    address,
+    storage_keys
+});
+
+impl_codec_struct!(geth_types::Transaction {
+    tx_type,
+    from,
+    to,
+    nonce,
+    gas_limit,
+    value,
+    gas_price,
+    gas_fee_cap,
+    gas_tip_cap,
+    call_data,
+    access_list,
+    v,
+    r,
+    s,
+    rlp_bytes,
+    rlp_unsigned_bytes,
+    hash,
+});
+
+impl_codec_struct!(Call {
+    call_id,
+    caller_id,
+    last_callee_id,
+    kind,
+    is_static,
+    is_root,
+    is_persistent,
+    is_success,
+    rw_counter_end_of_reversion,
+    caller_address,
+    address,
+    code_source,
+    code_hash,
+    depth,
+    value,
+    call_data_offset,
+    call_data_length,
+    return_data_offset,
+    return_data_length,
+    last_callee_return_data_offset,
+    last_callee_return_data_length,
+});
+
+impl_codec_struct!(ExecStep {
+    exec_state,
+    pc,
+    stack_size,
+    memory_size,
+    gas_left,
+    gas_cost,
+    gas_refund,
+    call_index,
+    rwc,
+    rwc_inner_chunk,
+    reversible_write_counter,
+    reversible_write_counter_delta,
+    log_id,
+    bus_mapping_instance,
+    copy_rw_counter_delta,
+    error,
+    aux_data,
+});
+
+impl_codec_struct!(EcrecoverAuxData {
+    msg_hash,
+    sig_v,
+    sig_r,
+    sig_s,
+    recovered_addr,
+    input_bytes,
+    output_bytes,
+    return_bytes,
+});
+
+impl_codec_struct!(SignData {
+    signature,
+    pk,
+    msg,
+    msg_hash
+});
+
+impl_codec_struct!(PrecompileEvents { events });
+
+impl_codec_struct!(CopyEvent {
+    src_addr,
+    src_addr_end,
+    src_type,
+    src_id,
+    dst_addr,
+    dst_type,
+    dst_id,
+    log_id,
+    rw_counter_start,
+    bytes,
+});
+
+impl_codec_struct!(ExpStep { a, b, d });
+
+impl_codec_struct!(ExpEvent {
+    identifier,
+    base,
+    exponent,
+    exponentiation,
+    steps,
+});
+
+impl_codec_struct!(FixedCParams {
+    total_chunks,
+    max_rws,
+    max_txs,
+    max_withdrawals,
+    max_calldata,
+    max_copy_rows,
+    max_exp_steps,
+    max_bytecode,
+    max_evm_rows,
+    max_keccak_rows,
+    max_vertical_circuit_rows,
+});
+
+impl_codec_struct!(FeatureConfig {
+    zero_difficulty,
+    free_first_tx,
+    enable_eip1559,
+    invalid_tx,
+    warm_coinbase,
+    limit_init_code_size,
+    is_merged,
+    override_gas_left,
+    disable_rw_permutation,
+    hardfork,
+});
+
+impl_codec_struct!(BlockContext {
+    coinbase,
+    gas_limit,
+    number,
+    timestamp,
+    difficulty,
+    base_fee,
+    history_hashes,
+    chain_id,
+    withdrawals_root,
+});
+
+impl Encode for Rw {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            Rw::Start { rw_counter } => {
+                encode_variant(writer, 0)?;
+                rw_counter.encode(writer)
+            }
+            Rw::TxAccessListAccount {
+                rw_counter,
+                is_write,
+                tx_id,
+                account_address,
+                is_warm,
+                is_warm_prev,
+            } => {
+                encode_variant(writer, 1)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                tx_id.encode(writer)?;
+                account_address.encode(writer)?;
+                is_warm.encode(writer)?;
+                is_warm_prev.encode(writer)
+            }
+            Rw::TxAccessListAccountStorage {
+                rw_counter,
+                is_write,
+                tx_id,
+                account_address,
+                storage_key,
+                is_warm,
+                is_warm_prev,
+            } => {
+                encode_variant(writer, 2)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                tx_id.encode(writer)?;
+                account_address.encode(writer)?;
+                storage_key.encode(writer)?;
+                is_warm.encode(writer)?;
+                is_warm_prev.encode(writer)
+            }
+            Rw::TxRefund {
+                rw_counter,
+                is_write,
+                tx_id,
+                value,
+                value_prev,
+            } => {
+                encode_variant(writer, 3)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                tx_id.encode(writer)?;
+                value.encode(writer)?;
+                value_prev.encode(writer)
+            }
+            Rw::Account {
+                rw_counter,
+                is_write,
+                account_address,
+                field_tag,
+                value,
+                value_prev,
+            } => {
+                encode_variant(writer, 4)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                account_address.encode(writer)?;
+                field_tag.encode(writer)?;
+                value.encode(writer)?;
+                value_prev.encode(writer)
+            }
+            Rw::AccountStorage {
+                rw_counter,
+                is_write,
+                account_address,
+                storage_key,
+                value,
+                value_prev,
+                tx_id,
+                committed_value,
+            } => {
+                encode_variant(writer, 5)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                account_address.encode(writer)?;
+                storage_key.encode(writer)?;
+                value.encode(writer)?;
+                value_prev.encode(writer)?;
+                tx_id.encode(writer)?;
+                committed_value.encode(writer)
+            }
+            Rw::AccountTransientStorage {
+                rw_counter,
+                is_write,
+                account_address,
+                storage_key,
+                value,
+                value_prev,
+                tx_id,
+            } => {
+                encode_variant(writer, 6)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                account_address.encode(writer)?;
+                storage_key.encode(writer)?;
+                value.encode(writer)?;
+                value_prev.encode(writer)?;
+                tx_id.encode(writer)
+            }
+            Rw::CallContext {
+                rw_counter,
+                is_write,
+                call_id,
+                field_tag,
+                value,
+            } => {
+                encode_variant(writer, 7)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                call_id.encode(writer)?;
+                field_tag.encode(writer)?;
+                value.encode(writer)
+            }
+            Rw::Stack {
+                rw_counter,
+                is_write,
+                call_id,
+                stack_pointer,
+                value,
+            } => {
+                encode_variant(writer, 8)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                call_id.encode(writer)?;
+                stack_pointer.encode(writer)?;
+                value.encode(writer)
+            }
+            Rw::Memory {
+                rw_counter,
+                is_write,
+                call_id,
+                memory_address,
+                byte,
+            } => {
+                encode_variant(writer, 9)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                call_id.encode(writer)?;
+                memory_address.encode(writer)?;
+                byte.encode(writer)
+            }
+            Rw::TxLog {
+                rw_counter,
+                is_write,
+                tx_id,
+                log_id,
+                field_tag,
+                index,
+                value,
+            } => {
+                encode_variant(writer, 10)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                tx_id.encode(writer)?;
+                log_id.encode(writer)?;
+                field_tag.encode(writer)?;
+                index.encode(writer)?;
+                value.encode(writer)
+            }
+            Rw::TxReceipt {
+                rw_counter,
+                is_write,
+                tx_id,
+                field_tag,
+                value,
+            } => {
+                encode_variant(writer, 11)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                tx_id.encode(writer)?;
+                field_tag.encode(writer)?;
+                value.encode(writer)
+            }
+            Rw::StepState {
+                rw_counter,
+                is_write,
+                field_tag,
+                value,
+            } => {
+                encode_variant(writer, 12)?;
+                rw_counter.encode(writer)?;
+                is_write.encode(writer)?;
+                field_tag.encode(writer)?;
+                value.encode(writer)
+            }
+            Rw::Padding { rw_counter } => {
+                encode_variant(writer, 13)?;
+                rw_counter.encode(writer)
+            }
+        }
+    }
+}
+
+impl Decode for Rw {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(match u8::decode(reader)? {
+            0 => Rw::Start {
+                rw_counter: Decode::decode(reader)?,
+            },
+            1 => Rw::TxAccessListAccount {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+                account_address: Decode::decode(reader)?,
+                is_warm: Decode::decode(reader)?,
+                is_warm_prev: Decode::decode(reader)?,
+            },
+            2 => Rw::TxAccessListAccountStorage {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+                account_address: Decode::decode(reader)?,
+                storage_key: Decode::decode(reader)?,
+                is_warm: Decode::decode(reader)?,
+                is_warm_prev: Decode::decode(reader)?,
+            },
+            3 => Rw::TxRefund {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+                value_prev: Decode::decode(reader)?,
+            },
+            4 => Rw::Account {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                account_address: Decode::decode(reader)?,
+                field_tag: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+                value_prev: Decode::decode(reader)?,
+            },
+            5 => Rw::AccountStorage {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                account_address: Decode::decode(reader)?,
+                storage_key: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+                value_prev: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+                committed_value: Decode::decode(reader)?,
+            },
+            6 => Rw::AccountTransientStorage {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                account_address: Decode::decode(reader)?,
+                storage_key: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+                value_prev: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+            },
+            7 => Rw::CallContext {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                call_id: Decode::decode(reader)?,
+                field_tag: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+            },
+            8 => Rw::Stack {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                call_id: Decode::decode(reader)?,
+                stack_pointer: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+            },
+            9 => Rw::Memory {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                call_id: Decode::decode(reader)?,
+                memory_address: Decode::decode(reader)?,
+                byte: Decode::decode(reader)?,
+            },
+            10 => Rw::TxLog {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+                log_id: Decode::decode(reader)?,
+                field_tag: Decode::decode(reader)?,
+                index: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+            },
+            11 => Rw::TxReceipt {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                tx_id: Decode::decode(reader)?,
+                field_tag: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+            },
+            12 => Rw::StepState {
+                rw_counter: Decode::decode(reader)?,
+                is_write: Decode::decode(reader)?,
+                field_tag: Decode::decode(reader)?,
+                value: Decode::decode(reader)?,
+            },
+            13 => Rw::Padding {
+                rw_counter: Decode::decode(reader)?,
+            },
+            variant => return Err(invalid_data(format!("invalid Rw {}", variant))),
+        })
+    }
+}
+
+impl Encode for RwMap {
+    fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.encode(writer)
+    }
+}
+
+impl Decode for RwMap {
+    fn decode<R: Read>(reader: &mut R) -> io::Result<Self> {
+        HashMap::decode(reader).map(RwMap)
+    }
+}
+
+/// Magic bytes and version at the start of an encoded block, bumped whenever
+/// the encoding changes so that stale caches are rejected.
+const BLOCK_MAGIC: [u8; 8] = *b"zkevmwc\x01";
+
+pub(crate) fn encode_block<F: Field, W: Write>(block: &Block<F>, writer: &mut W) -> io::Result<()> {
+    writer.write_all(&BLOCK_MAGIC)?;
+    encode_field(&block.randomness, writer)?;
+    block.txs.encode(writer)?;
+    block.end_block.encode(writer)?;
+    block.rws.encode(writer)?;
+    block.by_address_rws.encode(writer)?;
+    block.bytecodes.encode(writer)?;
+    block.context.encode(writer)?;
+    block.copy_events.encode(writer)?;
+    block.exp_events.encode(writer)?;
+    block.exp_circuit_pad_to.encode(writer)?;
+    block.circuits_params.encode(writer)?;
+    block.feature_config.encode(writer)?;
+    block.sha3_inputs.encode(writer)?;
+    block.prev_state_root.encode(writer)?;
+    block.keccak_inputs.encode(writer)?;
+    block.precompile_events.encode(writer)?;
+    serde_json::to_vec(&block.eth_block)
+        .map_err(invalid_data)?
+        .encode(writer)?;
+    block.rw_padding_meta.encode(writer)?;
+    block.skip_rw_value_check.encode(writer)
+}
+
+pub(crate) fn decode_block<F: Field, R: Read>(reader: &mut R) -> io::Result<Block<F>> {
+    if read_array(reader)? != BLOCK_MAGIC {
+        return Err(invalid_data("not an encoded block of this version"));
+    }
+    Ok(Block {
+        randomness: decode_field(reader)?,
+        txs: Decode::decode(reader)?,
+        end_block: Decode::decode(reader)?,
+        rws: Decode::decode(reader)?,
+        by_address_rws: Decode::decode(reader)?,
+        bytecodes: Decode::decode(reader)?,
+        context: Decode::decode(reader)?,
+        copy_events: Decode::decode(reader)?,
+        exp_events: Decode::decode(reader)?,
+        exp_circuit_pad_to: Decode::decode(reader)?,
+        circuits_params: Decode::decode(reader)?,
+        feature_config: Decode::decode(reader)?,
+        sha3_inputs: Decode::decode(reader)?,
+        prev_state_root: Decode::decode(reader)?,
+        keccak_inputs: Decode::decode(reader)?,
+        precompile_events: Decode::decode(reader)?,
+        eth_block: serde_json::from_slice(&Vec::<u8>::decode(reader)?).map_err(invalid_data)?,
+        rw_padding_meta: Decode::decode(reader)?,
+        skip_rw_value_check: Decode::decode(reader)?,
+    })
+}
@@ -54,6 +54,21 @@ pub struct MptUpdateRow<F: Clone> {
 }
 
 impl MptUpdates {
+    /// Iterate over the updates, ordered by key
+    pub fn iter(&self) -> impl Iterator<Item = &MptUpdate> {
+        self.updates.values()
+    }
+
+    /// Number of updates
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Whether there are no updates
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
     pub(crate) fn old_root(&self) -> Word {
         self.old_root
     }
@@ -114,6 +129,29 @@ impl MptUpdates {
 }
 
 impl MptUpdate {
+    /// Address of the modified account
+    pub fn address(&self) -> Address {
+        self.key.address()
+    }
+
+    /// Storage key of the modified slot, or `None` for an account field update
+    pub fn storage_key(&self) -> Option<Word> {
+        match self.key {
+            Key::Account { .. } => None,
+            Key::AccountStorage { storage_key, .. } => Some(storage_key),
+        }
+    }
+
+    /// Value before the update
+    pub fn old_value(&self) -> Word {
+        self.old_value
+    }
+
+    /// Value after the update
+    pub fn new_value(&self) -> Word {
+        self.new_value
+    }
+
     pub(crate) fn value_assignments(&self) -> (Word, Word) {
         (self.new_value, self.old_value)
     }
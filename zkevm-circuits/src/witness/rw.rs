@@ -1,6 +1,6 @@
 //! The Read-Write table related structs
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     iter,
 };
 
@@ -13,6 +13,8 @@ use bus_mapping::{
 use eth_types::{Address, Field, ToAddress, ToScalar, Word, U256};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
+use strum::IntoEnumIterator;
+use thiserror::Error;
 
 use crate::{
     table::{
@@ -21,7 +23,7 @@ use crate::{
     util::{build_tx_log_address, unwrap_value, word::WordLoHi},
 };
 
-use super::MptUpdates;
+use super::{MptUpdates, Transaction};
 
 const U64_BYTES: usize = u64::BITS as usize / 8usize;
 
@@ -57,9 +59,53 @@ impl RwMap {
             .sorted()
             .tuple_windows()
         {
-            debug_assert_eq!(rw_counter_cur - rw_counter_prev, 1);
+            debug_assert_eq!(
+                rw_counter_cur - rw_counter_prev,
+                1,
+                "rw counter gap after {}",
+                rw_counter_prev
+            );
         }
     }
+    /// Check the cumulative gas used of the receipt of each tx grows by the gas used of the tx
+    pub fn check_cumulative_gas_used(&self, txs: &[Transaction]) {
+        let cumulative_gas_used: HashMap<usize, u64> = self
+            .0
+            .get(&Target::TxReceipt)
+            .into_iter()
+            .flatten()
+            .filter_map(|rw| match rw {
+                Rw::TxReceipt {
+                    is_write: true,
+                    tx_id,
+                    field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+                    value,
+                    ..
+                } => Some((*tx_id, *value)),
+                _ => None,
+            })
+            .collect();
+        for tx in txs {
+            let tx_id = tx.id as usize;
+            let cumulative_gas_used_prev = cumulative_gas_used
+                .get(&(tx_id - 1))
+                .copied()
+                .unwrap_or_default();
+            debug_assert_eq!(
+                cumulative_gas_used.get(&tx_id).copied().unwrap_or_default(),
+                cumulative_gas_used_prev + tx.gas_used(),
+                "cumulative gas used of tx {}",
+                tx_id
+            );
+        }
+    }
+    /// Get the account and storage updates that the MptCircuit must prove for these rows.
+    ///
+    /// Only the keys and the old/new values are derived from the rows: the state roots are mock
+    /// values (`0xcafe + i` before the i-th update) and don't match any real trie.
+    pub fn mpt_updates(&self) -> MptUpdates {
+        MptUpdates::mock_from(&self.table_assignments(false))
+    }
     /// Check value in the same way like StateCircuit
     pub fn check_value(&self) {
         let err_msg_first = "first access reads don't change value";
@@ -178,22 +224,48 @@ impl RwMap {
             padding_length,
         )
     }
+    /// Check that `padding_start_rw`, the last row of the previous chunk, can take the place of
+    /// the `Start` row in front of the address sorted `rows`: every row of this chunk must come
+    /// after it in the address order, and only padding rows can follow a padding row.
+    pub fn validate_padding_start_rw(rows: &[Rw], padding_start_rw: &Rw) -> Result<(), String> {
+        let first_row = rows
+            .iter()
+            .filter(|rw| !matches!(rw, Rw::Start { .. } | Rw::Padding { .. }))
+            .min_by_key(|rw| Self::by_address_key(rw));
+        match first_row {
+            Some(first_row) if matches!(padding_start_rw, Rw::Padding { .. }) => Err(format!(
+                "padding start row is a padding row but the chunk has the row {:?}",
+                first_row
+            )),
+            Some(first_row)
+                if Self::by_address_key(first_row) <= Self::by_address_key(padding_start_rw) =>
+            {
+                Err(format!(
+                    "padding start row {:?} isn't before the first row {:?}",
+                    padding_start_rw, first_row
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// Key of the address order of the rows in the state circuit
+    fn by_address_key(row: &Rw) -> (u64, usize, Address, u64, Word, usize) {
+        (
+            row.tag() as u64,
+            row.id().unwrap_or_default(),
+            row.address().unwrap_or_default(),
+            row.field_tag().unwrap_or_default(),
+            row.storage_key().unwrap_or_default(),
+            row.rw_counter(),
+        )
+    }
     /// Build Rws for assignment
     pub fn table_assignments(&self, keep_chronological_order: bool) -> Vec<Rw> {
         let mut rows: Vec<Rw> = self.0.values().flatten().cloned().collect();
         if keep_chronological_order {
             rows.sort_by_key(|row| (row.rw_counter(), row.tag() as u64));
         } else {
-            rows.sort_by_key(|row| {
-                (
-                    row.tag() as u64,
-                    row.id().unwrap_or_default(),
-                    row.address().unwrap_or_default(),
-                    row.field_tag().unwrap_or_default(),
-                    row.storage_key().unwrap_or_default(),
-                    row.rw_counter(),
-                )
-            });
+            rows.sort_by_key(Self::by_address_key);
         }
 
         rows
@@ -206,6 +278,502 @@ impl RwMap {
         }
         self
     }
+    /// Collect the final value of each [`CallContextFieldTag`] accessed by the
+    /// call with `call_id`.  When a field is accessed multiple times, the value
+    /// of the access with the highest rw_counter is kept.
+    pub fn call_context(&self, call_id: usize) -> HashMap<CallContextFieldTag, Word> {
+        self.0
+            .get(&Target::CallContext)
+            .map(|rws| {
+                rws.iter()
+                    .filter_map(|rw| match rw {
+                        Rw::CallContext {
+                            rw_counter,
+                            call_id: id,
+                            field_tag,
+                            value,
+                            ..
+                        } if *id == call_id => Some((*rw_counter, *field_tag, *value)),
+                        _ => None,
+                    })
+                    .sorted_by_key(|(rw_counter, _, _)| *rw_counter)
+                    .map(|(_, field_tag, value)| (field_tag, value))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    /// Reconstruct the final state of each account accessed in the block, by
+    /// applying the [`Rw::Account`] and [`Rw::AccountStorage`] rows in
+    /// rw_counter order.  Fields never accessed are left at zero.
+    pub fn final_accounts(&self) -> HashMap<Address, AccountData> {
+        let mut accounts: HashMap<Address, AccountData> = HashMap::new();
+        let account_rws = [Target::Account, Target::Storage]
+            .iter()
+            .filter_map(|target| self.0.get(target))
+            .flatten()
+            .sorted_by_key(|rw| rw.rw_counter());
+        for rw in account_rws {
+            match rw {
+                Rw::Account {
+                    account_address,
+                    field_tag,
+                    value,
+                    ..
+                } => {
+                    let account = accounts.entry(*account_address).or_default();
+                    match field_tag {
+                        AccountFieldTag::Nonce => account.nonce = *value,
+                        AccountFieldTag::Balance => account.balance = *value,
+                        AccountFieldTag::CodeHash => account.code_hash = *value,
+                        AccountFieldTag::NonExisting => (),
+                    }
+                }
+                Rw::AccountStorage {
+                    account_address,
+                    storage_key,
+                    value,
+                    ..
+                } => {
+                    accounts
+                        .entry(*account_address)
+                        .or_default()
+                        .storage
+                        .insert(*storage_key, *value);
+                }
+                _ => unreachable!(),
+            }
+        }
+        accounts
+    }
+    /// Collect the accounts in the access list of the transaction `tx_id`
+    /// once it ends, by replaying its [`Rw::TxAccessListAccount`] rows in
+    /// rw_counter order.  The accesses of reverted calls are undone by rows
+    /// writing back their `is_warm_prev`, so an account is warm if the last
+    /// row accessing it leaves it warm.
+    pub fn warm_accounts(&self, tx_id: usize) -> HashSet<Address> {
+        self.final_warm_keys(Target::TxAccessListAccount, |rw| match rw {
+            Rw::TxAccessListAccount {
+                tx_id: id,
+                account_address,
+                is_warm,
+                ..
+            } if *id == tx_id => Some((*account_address, *is_warm)),
+            _ => None,
+        })
+    }
+    /// Collect the storage slots in the access list of the transaction
+    /// `tx_id` once it ends, from its [`Rw::TxAccessListAccountStorage`] rows.
+    /// See [`RwMap::warm_accounts`].
+    pub fn warm_storage_slots(&self, tx_id: usize) -> HashSet<(Address, Word)> {
+        self.final_warm_keys(Target::TxAccessListAccountStorage, |rw| match rw {
+            Rw::TxAccessListAccountStorage {
+                tx_id: id,
+                account_address,
+                storage_key,
+                is_warm,
+                ..
+            } if *id == tx_id => Some(((*account_address, *storage_key), *is_warm)),
+            _ => None,
+        })
+    }
+    fn final_warm_keys<K: Eq + std::hash::Hash>(
+        &self,
+        target: Target,
+        key_is_warm: impl Fn(&Rw) -> Option<(K, bool)>,
+    ) -> HashSet<K> {
+        let mut is_warm_by_key = HashMap::new();
+        for (key, is_warm) in self
+            .0
+            .get(&target)
+            .into_iter()
+            .flatten()
+            .sorted_by_key(|rw| rw.rw_counter())
+            .filter_map(key_is_warm)
+        {
+            is_warm_by_key.insert(key, is_warm);
+        }
+        is_warm_by_key
+            .into_iter()
+            .filter_map(|(key, is_warm)| is_warm.then_some(key))
+            .collect()
+    }
+
+    /// Collect the reversible writes of the call with `call_id`, including the
+    /// ones of its successful subcalls, that have been reverted.  Following
+    /// the scheme of `ReversionInfo`, the write done with reversible write
+    /// counter `i` is reverted by the write at `rw_counter_end_of_reversion -
+    /// i`, which restores the previous value.  The writes of failed subcalls
+    /// are left out, as they are reverted by the subcalls themselves.
+    ///
+    /// A persistent call has nothing reverted.  For a reverted call, a
+    /// storage, transient storage, storage access list or refund write that
+    /// isn't reverted is reported as an error.  Account and account access
+    /// list writes aren't checked, as the ones done by BeginTx aren't
+    /// reversible.
+    pub fn reverted_writes(&self, call_id: usize) -> Result<Vec<Rw>, RevertedWritesError> {
+        let call_context = self.call_context(call_id);
+        if call_context
+            .get(&CallContextFieldTag::IsPersistent)
+            .map_or(true, |is_persistent| !is_persistent.is_zero())
+        {
+            return Ok(vec![]);
+        }
+        let rw_counter_end_of_reversion = call_context
+            .get(&CallContextFieldTag::RwCounterEndOfReversion)
+            .ok_or(RevertedWritesError::MissingRwCounterEndOfReversion(call_id))?
+            .as_usize();
+
+        let rws: BTreeMap<usize, Rw> = self
+            .0
+            .values()
+            .flatten()
+            .map(|rw| (rw.rw_counter(), *rw))
+            .collect();
+        // The first and last rw_counter of the rows scoped to each call.
+        let mut call_ranges: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        for rw in rws.values().filter(|rw| {
+            matches!(
+                rw.tag(),
+                Target::CallContext | Target::Stack | Target::Memory
+            )
+        }) {
+            let range = call_ranges
+                .entry(rw.id().expect("call scoped rows have a call id"))
+                .or_insert((rw.rw_counter(), rw.rw_counter()));
+            range.1 = rw.rw_counter();
+        }
+        let (start, last) = *call_ranges
+            .get(&call_id)
+            .ok_or(RevertedWritesError::CallNotFound(call_id))?;
+        let end = last.min(rw_counter_end_of_reversion);
+        let failed_subcalls = call_ranges
+            .iter()
+            .filter(|(id, (first, _))| **id != call_id && start < *first && *first <= end)
+            .filter_map(|(id, (first, _))| {
+                let call_context = self.call_context(*id);
+                match (
+                    call_context.get(&CallContextFieldTag::IsSuccess),
+                    call_context.get(&CallContextFieldTag::RwCounterEndOfReversion),
+                ) {
+                    (Some(is_success), Some(rw_counter_end_of_reversion))
+                        if is_success.is_zero() =>
+                    {
+                        Some(*first..=rw_counter_end_of_reversion.as_usize())
+                    }
+                    _ => None,
+                }
+            })
+            .collect_vec();
+        let writes = rws
+            .range(start..=end)
+            .map(|(_, rw)| rw)
+            .filter(|rw| {
+                rw.is_write()
+                    && matches!(
+                        rw.tag(),
+                        Target::TxAccessListAccount
+                            | Target::TxAccessListAccountStorage
+                            | Target::TxRefund
+                            | Target::Account
+                            | Target::Storage
+                            | Target::TransientStorage
+                    )
+                    && !failed_subcalls
+                        .iter()
+                        .any(|range| range.contains(&rw.rw_counter()))
+            })
+            .collect_vec();
+        let reverts = |write: &Rw, revert: &Rw| {
+            revert.is_write()
+                && revert.tag() == write.tag()
+                && revert.id() == write.id()
+                && revert.address() == write.address()
+                && revert.field_tag() == write.field_tag()
+                && revert.storage_key() == write.storage_key()
+                && Some(revert.value_assignment()) == write.value_prev_assignment()
+                && revert.value_prev_assignment() == Some(write.value_assignment())
+        };
+
+        // Match the revert of each reversible write in order, skipping the
+        // writes which aren't reversible.
+        let mut reverted = vec![];
+        let mut unreverted = vec![];
+        let mut writes = writes.into_iter();
+        'reverts: while let Some(revert) = rws.get(&(rw_counter_end_of_reversion - reverted.len()))
+        {
+            for write in writes.by_ref() {
+                if write.rw_counter() >= revert.rw_counter() {
+                    if write.rw_counter() == revert.rw_counter() {
+                        unreverted.push(write);
+                    }
+                    break 'reverts;
+                }
+                if reverts(write, revert) {
+                    reverted.push(*write);
+                    continue 'reverts;
+                }
+                unreverted.push(write);
+            }
+            break;
+        }
+        let rw_counter_of_reversion = rw_counter_end_of_reversion - reverted.len();
+        unreverted.extend(writes.take_while(|write| write.rw_counter() <= rw_counter_of_reversion));
+        if let Some(write) = unreverted.into_iter().find(|write| {
+            matches!(
+                write.tag(),
+                Target::TxAccessListAccountStorage
+                    | Target::TxRefund
+                    | Target::Storage
+                    | Target::TransientStorage
+            )
+        }) {
+            return Err(RevertedWritesError::NotReverted {
+                call_id,
+                write: *write,
+            });
+        }
+        Ok(reverted)
+    }
+
+    /// Compare the rows of `self` with the ones of `other`, matching them by
+    /// tag and rw_counter.  Rows sharing a tag and rw_counter, like the
+    /// padding rows, are matched in the order they appear in the map.  Rows
+    /// only in `other` are reported as added, rows only in `self` as removed,
+    /// and matching rows with different column values as changed.  The
+    /// differences are sorted by rw_counter.
+    pub fn diff(&self, other: &RwMap) -> Vec<RwDiff> {
+        let rows_by_key = |rw_map: &RwMap| {
+            let mut occurrences = HashMap::<(usize, u64), usize>::new();
+            rw_map
+                .0
+                .values()
+                .flatten()
+                .map(|rw| {
+                    let (rw_counter, tag) = (rw.rw_counter(), rw.tag() as u64);
+                    let occurrence = occurrences.entry((rw_counter, tag)).or_default();
+                    *occurrence += 1;
+                    ((rw_counter, tag, *occurrence), *rw)
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+        let (old_rows, mut new_rows) = (rows_by_key(self), rows_by_key(other));
+
+        let mut diffs = Vec::new();
+        for (key, old) in old_rows {
+            match new_rows.remove(&key) {
+                Some(new) => {
+                    let fields = old.changed_fields(&new);
+                    if !fields.is_empty() {
+                        diffs.push((key, RwDiff::Changed { old, new, fields }));
+                    }
+                }
+                None => diffs.push((key, RwDiff::Removed(old))),
+            }
+        }
+        diffs.extend(
+            new_rows
+                .into_iter()
+                .map(|(key, new)| (key, RwDiff::Added(new))),
+        );
+        diffs
+            .into_iter()
+            .sorted_by_key(|(key, _)| *key)
+            .map(|(_, diff)| diff)
+            .collect()
+    }
+    /// Build a `RwMap` holding `count` dummy rows of `tag`, with sequential
+    /// `rw_counter`s starting at 1.  The rows are read-only accesses with
+    /// default values, meant to exercise padding and table sizing rather
+    /// than to pass the state circuit constraints.
+    pub fn with_dummy_rows(tag: Target, count: usize) -> Self {
+        let rows = (1..=count)
+            .map(|rw_counter| {
+                let index = rw_counter - 1;
+                match tag {
+                    Target::Start => panic!("RwMap::with_dummy_rows does not support Start rows"),
+                    Target::Memory => Rw::Memory {
+                        rw_counter,
+                        is_write: false,
+                        call_id: 1,
+                        memory_address: index as u64,
+                        byte: 0,
+                    },
+                    Target::Stack => Rw::Stack {
+                        rw_counter,
+                        is_write: false,
+                        call_id: 1,
+                        stack_pointer: index,
+                        value: Word::zero(),
+                    },
+                    Target::Storage => Rw::AccountStorage {
+                        rw_counter,
+                        is_write: false,
+                        account_address: Address::zero(),
+                        storage_key: Word::from(index),
+                        value: Word::zero(),
+                        value_prev: Word::zero(),
+                        tx_id: 1,
+                        committed_value: Word::zero(),
+                    },
+                    Target::TransientStorage => Rw::AccountTransientStorage {
+                        rw_counter,
+                        is_write: false,
+                        account_address: Address::zero(),
+                        storage_key: Word::from(index),
+                        value: Word::zero(),
+                        value_prev: Word::zero(),
+                        tx_id: 1,
+                    },
+                    Target::TxAccessListAccount => Rw::TxAccessListAccount {
+                        rw_counter,
+                        is_write: false,
+                        tx_id: 1,
+                        account_address: Address::from_low_u64_be(index as u64),
+                        is_warm: false,
+                        is_warm_prev: false,
+                    },
+                    Target::TxAccessListAccountStorage => Rw::TxAccessListAccountStorage {
+                        rw_counter,
+                        is_write: false,
+                        tx_id: 1,
+                        account_address: Address::zero(),
+                        storage_key: Word::from(index),
+                        is_warm: false,
+                        is_warm_prev: false,
+                    },
+                    Target::TxRefund => Rw::TxRefund {
+                        rw_counter,
+                        is_write: false,
+                        tx_id: 1,
+                        value: 0,
+                        value_prev: 0,
+                    },
+                    Target::Account => Rw::Account {
+                        rw_counter,
+                        is_write: false,
+                        account_address: Address::from_low_u64_be(index as u64),
+                        field_tag: AccountFieldTag::Nonce,
+                        value: Word::zero(),
+                        value_prev: Word::zero(),
+                    },
+                    Target::CallContext => Rw::CallContext {
+                        rw_counter,
+                        is_write: false,
+                        call_id: index + 1,
+                        field_tag: CallContextFieldTag::RwCounterEndOfReversion,
+                        value: Word::zero(),
+                    },
+                    Target::TxReceipt => Rw::TxReceipt {
+                        rw_counter,
+                        is_write: false,
+                        tx_id: index + 1,
+                        field_tag: TxReceiptFieldTag::PostStateOrStatus,
+                        value: 0,
+                    },
+                    Target::TxLog => Rw::TxLog {
+                        rw_counter,
+                        is_write: false,
+                        tx_id: 1,
+                        log_id: 0,
+                        field_tag: TxLogFieldTag::Data,
+                        index,
+                        value: Word::zero(),
+                    },
+                    Target::StepState => Rw::StepState {
+                        rw_counter,
+                        is_write: false,
+                        field_tag: StepStateFieldTag::CallID,
+                        value: Word::zero(),
+                    },
+                    Target::Padding => Rw::Padding { rw_counter },
+                }
+            })
+            .collect();
+        Self(HashMap::from([(tag, rows)]))
+    }
+    /// Check that the [`Rw::StepState`] rows bracket the chunks: the step
+    /// state written by the EndChunk of a chunk must be read back with the
+    /// same values by the BeginChunk of the next chunk, and each of them must
+    /// cover every [`StepStateFieldTag`] exactly once.
+    pub fn validate_step_state_bracketing(&self) -> Result<(), String> {
+        let rows = self
+            .0
+            .get(&Target::StepState)
+            .map(|rws| rws.iter().sorted_by_key(|rw| rw.rw_counter()).collect_vec())
+            .unwrap_or_default();
+
+        // Split into groups of consecutive writes or reads.
+        let groups = rows
+            .into_iter()
+            .group_by(|rw| rw.is_write())
+            .into_iter()
+            .map(|(_, group)| group.collect_vec())
+            .collect_vec();
+
+        let mut written: Option<(usize, BTreeMap<StepStateFieldTag, Word>)> = None;
+        for group in groups {
+            let (rw_counter, is_write) = (group[0].rw_counter(), group[0].is_write());
+            let kind = if is_write { "write" } else { "read" };
+            let mut fields = BTreeMap::new();
+            for rw in group {
+                let (field_tag, value) = match rw {
+                    Rw::StepState {
+                        field_tag, value, ..
+                    } => (*field_tag, *value),
+                    _ => unreachable!("only StepState rows are stored under Target::StepState"),
+                };
+                if fields.insert(field_tag, value).is_some() {
+                    return Err(format!(
+                        "StepState {} at rw_counter {} has duplicated {:?}",
+                        kind, rw_counter, field_tag
+                    ));
+                }
+            }
+            let missing = StepStateFieldTag::iter()
+                .filter(|field_tag| !fields.contains_key(field_tag))
+                .collect_vec();
+            if !missing.is_empty() {
+                return Err(format!(
+                    "StepState {} at rw_counter {} is missing {:?}",
+                    kind, rw_counter, missing
+                ));
+            }
+
+            match (written.take(), is_write) {
+                (None, true) => written = Some((rw_counter, fields)),
+                (Some((write_rw_counter, _)), true) => {
+                    return Err(format!(
+                        "StepState write at rw_counter {} is not read back before the write at rw_counter {}",
+                        write_rw_counter, rw_counter
+                    ))
+                }
+                (None, false) => {
+                    return Err(format!(
+                        "StepState read at rw_counter {} has no preceding write",
+                        rw_counter
+                    ))
+                }
+                (Some((write_rw_counter, written_fields)), false) => {
+                    if let Some((field_tag, _)) = written_fields
+                        .iter()
+                        .find(|(field_tag, value)| fields[field_tag] != **value)
+                    {
+                        return Err(format!(
+                            "StepState {:?} read at rw_counter {} mismatches the write at rw_counter {}",
+                            field_tag, rw_counter, write_rw_counter
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some((rw_counter, _)) = written {
+            return Err(format!(
+                "StepState write at rw_counter {} is never read back",
+                rw_counter
+            ));
+        }
+        Ok(())
+    }
     /// Get one Rw for a chunk specified by index
     pub fn get_rw(container: &operation::OperationContainer, counter: usize) -> Option<Rw> {
         let rws: Self = container.into();
@@ -219,6 +787,58 @@ impl RwMap {
         None
     }
 }
+
+/// Account state reconstructed by [`RwMap::final_accounts`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountData {
+    /// Nonce
+    pub nonce: Word,
+    /// Balance
+    pub balance: Word,
+    /// Code hash
+    pub code_hash: Word,
+    /// Storage key-value map
+    pub storage: HashMap<Word, Word>,
+}
+
+/// Errors of [`RwMap::reverted_writes`]
+#[derive(Clone, Debug, Error)]
+pub enum RevertedWritesError {
+    /// No call scoped row has the call id
+    #[error("CallNotFound({0})")]
+    CallNotFound(usize),
+    /// The reverted call has no `RwCounterEndOfReversion` call context row
+    #[error("MissingRwCounterEndOfReversion(call {0})")]
+    MissingRwCounterEndOfReversion(usize),
+    /// A reversible write of the reverted call is not reverted
+    #[error("NotReverted(call {call_id}, {write:?})")]
+    NotReverted {
+        /// The id of the reverted call
+        call_id: usize,
+        /// The write that is not reverted
+        write: Rw,
+    },
+}
+
+/// Difference between two [`RwMap`]s found by [`RwMap::diff`]
+#[derive(Clone, Debug)]
+pub enum RwDiff {
+    /// Row only in the new map
+    Added(Rw),
+    /// Row only in the old map
+    Removed(Rw),
+    /// Row with the same tag and rw_counter in both maps, but different
+    /// values in the `fields` columns of the rw table
+    Changed {
+        /// Row in the old map
+        old: Rw,
+        /// Row in the new map
+        new: Rw,
+        /// Names of the rw table columns that differ
+        fields: Vec<&'static str>,
+    },
+}
+
 #[allow(
     missing_docs,
     reason = "Some of the docs are tedious and can be found at https://github.com/privacy-scaling-explorations/zkevm-specs/blob/master/specs/tables.md"
@@ -657,6 +1277,65 @@ impl Rw {
         }
     }
 
+    /// The `is_warm` value of an access list row, or `None` for other rows
+    pub fn as_bool(&self) -> Option<bool> {
+        matches!(
+            self,
+            Self::TxAccessListAccount { .. } | Self::TxAccessListAccountStorage { .. }
+        )
+        .then(|| self.tx_access_list_value_pair().0)
+    }
+
+    /// The value of a refund or receipt row, or `None` for other rows
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::TxRefund { .. } => Some(self.tx_refund_value_pair().0),
+            Self::TxReceipt { .. } => Some(self.receipt_value()),
+            _ => None,
+        }
+    }
+
+    /// The value of a row holding an address: the caller or callee address
+    /// of a call context, or the address of a log.  `None` for other rows.
+    pub fn as_address(&self) -> Option<Address> {
+        match self {
+            Self::CallContext {
+                field_tag: CallContextFieldTag::CallerAddress | CallContextFieldTag::CalleeAddress,
+                ..
+            } => Some(self.call_context_value().to_address()),
+            Self::TxLog {
+                field_tag: TxLogFieldTag::Address,
+                value,
+                ..
+            } => Some(value.to_address()),
+            _ => None,
+        }
+    }
+
+    /// Names of the rw table columns, other than rw_counter and tag, whose
+    /// values differ between `self` and `other`
+    fn changed_fields(&self, other: &Rw) -> Vec<&'static str> {
+        [
+            ("is_write", self.is_write() != other.is_write()),
+            ("id", self.id() != other.id()),
+            ("address", self.address() != other.address()),
+            ("field_tag", self.field_tag() != other.field_tag()),
+            ("storage_key", self.storage_key() != other.storage_key()),
+            ("value", self.value_assignment() != other.value_assignment()),
+            (
+                "value_prev",
+                self.value_prev_assignment() != other.value_prev_assignment(),
+            ),
+            (
+                "init_val",
+                self.committed_value_assignment() != other.committed_value_assignment(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+
     pub(crate) fn table_assignment<F: Field>(&self) -> RwRow<Value<F>> {
         RwRow {
             rw_counter: Value::known(F::from(self.rw_counter() as u64)),
@@ -1283,3 +1962,655 @@ impl<F: Field> Default for RwFingerprints<F> {
         Self::new(F::from(0), F::from(0), F::from(1), F::from(1))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::block_convert;
+    use bus_mapping::mock::BlockData;
+    use eth_types::{
+        bytecode, evm_types::PRECOMPILE_COUNT, geth_types::GethData, AccessList, AccessListItem,
+        ToWord,
+    };
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    #[test]
+    fn rw_map_mpt_updates_sstore() {
+        let code = bytecode! {
+            PUSH1(0x42) // value
+            PUSH1(0x01) // key
+            SSTORE
+            STOP
+        };
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .code(code)
+                    .storage(vec![(Word::from(0x01), Word::from(0x07))].into_iter());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let storage_updates = block
+            .rws
+            .mpt_updates()
+            .iter()
+            .filter(|update| update.storage_key().is_some())
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(storage_updates.len(), 1);
+        let update = storage_updates[0];
+        assert_eq!(update.address(), MOCK_ACCOUNTS[0]);
+        assert_eq!(update.storage_key(), Some(Word::from(0x01)));
+        assert_eq!(update.old_value(), Word::from(0x07));
+        assert_eq!(update.new_value(), Word::from(0x42));
+    }
+
+    #[test]
+    fn rw_map_warm_access_list() {
+        let code_b = bytecode! {
+            PUSH1(0x42)
+            SLOAD
+            PUSH20(MOCK_ACCOUNTS[3].to_word())
+            BALANCE
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        };
+        let code_a = bytecode! {
+            PUSH1(0x01)
+            SLOAD
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH20(MOCK_ACCOUNTS[1].to_word()) // addr
+            PUSH2(0xffff) // gas
+            STATICCALL
+            STOP
+        };
+        let block: GethData = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code_a);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_b);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+                accs[3].address(MOCK_ACCOUNTS[3]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        // The precompiles, the caller and the callee are warmed when the tx
+        // begins, the address called by STATICCALL when it's called.  The
+        // account read by BALANCE in the reverted call is cold again.
+        let mut expected_accounts = (1..=PRECOMPILE_COUNT)
+            .map(Address::from_low_u64_be)
+            .collect::<HashSet<_>>();
+        expected_accounts.extend([MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1], MOCK_ACCOUNTS[2]]);
+        if block.feature_config.warm_coinbase {
+            expected_accounts.insert(block.context.coinbase);
+        }
+        assert_eq!(block.rws.warm_accounts(1), expected_accounts);
+
+        // Likewise, only the slot read by the root call stays warm.
+        assert_eq!(
+            block.rws.warm_storage_slots(1),
+            HashSet::from([(MOCK_ACCOUNTS[0], Word::from(0x01))])
+        );
+
+        assert!(block.rws.warm_accounts(2).is_empty());
+    }
+
+    #[test]
+    fn rw_map_precompiles_warm_at_tx_start() {
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode! { STOP })
+            .unwrap()
+            .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        // Every precompile is warmed from cold by the BeginTx step, before the
+        // first opcode runs.
+        let first_opcode_rwc = block.txs[0].steps()[1].rwc.0;
+        for precompile in (1..=PRECOMPILE_COUNT).map(Address::from_low_u64_be) {
+            let rows = block.rws.0[&Target::TxAccessListAccount]
+                .iter()
+                .filter(|rw| rw.address() == Some(precompile))
+                .collect_vec();
+            assert_eq!(rows.len(), 1, "{precompile:?}");
+            assert_eq!(rows[0].tx_access_list_value_pair(), (true, false));
+            assert!(rows[0].rw_counter() < first_opcode_rwc);
+        }
+    }
+
+    #[test]
+    fn rw_map_warm_coinbase_in_access_list() {
+        let warm_coinbase_rows = |coinbase: Address, callee: Address| {
+            let block: GethData = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(MOCK_ACCOUNTS[0]).code(bytecode! { STOP });
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(1));
+                    accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[1].address)
+                        .to(callee)
+                        .access_list(AccessList(vec![AccessListItem {
+                            address: coinbase,
+                            storage_keys: vec![],
+                        }]));
+                },
+                |block, _tx| block.author(coinbase),
+            )
+            .unwrap()
+            .into();
+            let builder = BlockData::new_from_geth_data(block.clone())
+                .new_circuit_input_builder()
+                .handle_block(&block.eth_block, &block.geth_traces)
+                .unwrap();
+            let block = block_convert::<Fr>(&builder).unwrap();
+            assert!(block.feature_config.warm_coinbase);
+            let access_list = block.txs[0].tx.access_list.clone().unwrap();
+            assert_eq!(access_list.0[0].address, coinbase);
+            assert!(block.rws.warm_accounts(1).contains(&coinbase));
+
+            block.rws.0[&Target::TxAccessListAccount]
+                .iter()
+                .filter(|rw| rw.address() == Some(coinbase))
+                .map(|rw| rw.tx_access_list_value_pair())
+                .collect_vec()
+        };
+
+        // The coinbase is warmed from cold by EIP-3651, and the access list
+        // entry rewrites it as already warm.
+        assert_eq!(
+            warm_coinbase_rows(MOCK_ACCOUNTS[2], MOCK_ACCOUNTS[0]),
+            vec![(true, false), (true, true)]
+        );
+        // When it's also the callee, only the first write is from cold.
+        assert_eq!(
+            warm_coinbase_rows(MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[0]),
+            vec![(true, false), (true, true), (true, true)]
+        );
+    }
+
+    #[test]
+    fn rw_map_call_context_nested_call() {
+        let code_b = bytecode! {
+            STOP
+        };
+        let code_a = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH20(MOCK_ACCOUNTS[1].to_word()) // addr
+            PUSH2(0xffff) // gas
+            STATICCALL
+            STOP
+        };
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code_a);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_b);
+                accs[2]
+                    .address(MOCK_ACCOUNTS[2])
+                    .balance(Word::from(1u64 << 30));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let calls = block.txs[0].calls();
+        assert_eq!(calls.len(), 2);
+        let (root, callee) = (&calls[0], &calls[1]);
+
+        let root_ctx = block.rws.call_context(root.call_id);
+        assert_eq!(root_ctx[&CallContextFieldTag::Depth], Word::from(1));
+        assert_eq!(root_ctx[&CallContextFieldTag::IsStatic], Word::zero());
+        assert_eq!(
+            root_ctx[&CallContextFieldTag::CallerAddress],
+            MOCK_ACCOUNTS[2].to_word()
+        );
+        // The root call is updated with the callee id once the STATICCALL
+        // returns.
+        assert_eq!(
+            root_ctx[&CallContextFieldTag::LastCalleeId],
+            Word::from(callee.call_id)
+        );
+
+        let callee_ctx = block.rws.call_context(callee.call_id);
+        assert_eq!(callee_ctx[&CallContextFieldTag::Depth], Word::from(2));
+        assert_eq!(callee_ctx[&CallContextFieldTag::IsStatic], Word::one());
+        assert_eq!(
+            callee_ctx[&CallContextFieldTag::CallerId],
+            Word::from(root.call_id)
+        );
+        assert_eq!(
+            callee_ctx[&CallContextFieldTag::CallerAddress],
+            MOCK_ACCOUNTS[0].to_word()
+        );
+        assert_eq!(
+            callee_ctx[&CallContextFieldTag::CalleeAddress],
+            MOCK_ACCOUNTS[1].to_word()
+        );
+
+        // Unknown call ids have no fields.
+        assert!(block.rws.call_context(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn rw_map_reverted_writes() {
+        let code_b = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            SSTORE
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        };
+        let code_a = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            PUSH20(MOCK_ACCOUNTS[1].to_word()) // addr
+            PUSH2(0xffff) // gas
+            CALL
+            STOP
+        };
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(code_a);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(code_b);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let calls = block.txs[0].calls();
+        let (root, callee) = (&calls[0], &calls[1]);
+        assert!(block.rws.reverted_writes(root.call_id).unwrap().is_empty());
+
+        let reverted = block.rws.reverted_writes(callee.call_id).unwrap();
+        let is_sstore = |rw: &Rw| {
+            matches!(
+                rw,
+                Rw::AccountStorage {
+                    account_address,
+                    storage_key,
+                    ..
+                } if *account_address == MOCK_ACCOUNTS[1] && storage_key.is_zero()
+            )
+        };
+        let sstore = reverted
+            .iter()
+            .find(|rw| is_sstore(rw))
+            .expect("SSTORE is reverted");
+        assert_eq!(sstore.value_assignment(), Word::one());
+        assert!(reverted
+            .iter()
+            .any(|rw| rw.tag() == Target::TxAccessListAccountStorage));
+
+        // Dropping the revert entry of the SSTORE is reported.
+        let mut rws = block.rws.clone();
+        rws.0
+            .get_mut(&Target::Storage)
+            .unwrap()
+            .retain(|rw| !(is_sstore(rw) && rw.value_assignment().is_zero()));
+        assert!(matches!(
+            rws.reverted_writes(callee.call_id),
+            Err(RevertedWritesError::NotReverted { call_id, .. }) if call_id == callee.call_id
+        ));
+
+        // So is a reverted call without a RwCounterEndOfReversion.
+        let mut rws = block.rws.clone();
+        rws.0.get_mut(&Target::CallContext).unwrap().retain(|rw| {
+            !matches!(
+                rw,
+                Rw::CallContext {
+                    call_id,
+                    field_tag: CallContextFieldTag::RwCounterEndOfReversion,
+                    ..
+                } if *call_id == callee.call_id
+            )
+        });
+        assert!(matches!(
+            rws.reverted_writes(callee.call_id),
+            Err(RevertedWritesError::MissingRwCounterEndOfReversion(call_id)) if call_id == callee.call_id
+        ));
+    }
+
+    #[test]
+    fn rw_map_final_accounts_transfer() {
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .value(eth(2));
+            },
+            |block, _tx| block,
+        )
+        .unwrap()
+        .into();
+        let builder = BlockData::new_from_geth_data(block.clone())
+            .new_circuit_input_builder()
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        let block = block_convert::<Fr>(&builder).unwrap();
+
+        let tx = &block.txs[0];
+        let fee = tx.gas_price * Word::from(GasCost::TX);
+        let accounts = block.rws.final_accounts();
+        let sender = &accounts[&MOCK_ACCOUNTS[0]];
+        assert_eq!(sender.balance, eth(10) - eth(2) - fee);
+        assert_eq!(sender.nonce, Word::one());
+        assert_eq!(accounts[&MOCK_ACCOUNTS[1]].balance, eth(3));
+    }
+
+    #[test]
+    fn padding_len_boundaries() {
+        // Exact fit needs no padding.
+        assert_eq!(RwMap::padding_len(10, 10), 0);
+        // `target_len == 0` is the "auto" mode and never pads.
+        assert_eq!(RwMap::padding_len(10, 0), 0);
+        assert_eq!(RwMap::padding_len(0, 0), 0);
+        // Spare rows are all padding.
+        assert_eq!(RwMap::padding_len(10, 11), 1);
+        assert_eq!(RwMap::padding_len(0, 5), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "RwMap::padding_len overflow")]
+    fn padding_len_overflow() {
+        RwMap::padding_len(11, 10);
+    }
+
+    #[test]
+    fn table_assignments_padding_dummy_rows() {
+        let rws = RwMap::with_dummy_rows(Target::Stack, 4);
+        let rows = rws.table_assignments(true);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows.iter().map(|rw| rw.rw_counter()).collect_vec(),
+            vec![1, 2, 3, 4]
+        );
+
+        // Start row plus the dummy rows fill the table exactly.
+        let (padded, padding_len) = RwMap::table_assignments_padding(&rows, 5, None);
+        assert_eq!(padded.len(), 5);
+        assert_eq!(padding_len, 0);
+        assert!(matches!(padded[0], Rw::Start { rw_counter: 1 }));
+        assert!(!padded.iter().any(|rw| matches!(rw, Rw::Padding { .. })));
+
+        // Remaining rows are padding with rw_counter following the dummy rows.
+        let (padded, padding_len) = RwMap::table_assignments_padding(&rows, 8, None);
+        assert_eq!(padded.len(), 8);
+        assert_eq!(padding_len, 3);
+        assert_eq!(
+            padded[5..].iter().map(|rw| rw.rw_counter()).collect_vec(),
+            vec![5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn table_assignments_padding_middle_chunk() {
+        let rows = (1000..1004)
+            .map(|rw_counter| Rw::Stack {
+                rw_counter,
+                is_write: true,
+                call_id: 1,
+                stack_pointer: 1023,
+                value: Word::from(rw_counter),
+            })
+            .collect_vec();
+        let prev_chunk_last_rw = Rw::Stack {
+            rw_counter: 999,
+            is_write: true,
+            call_id: 1,
+            stack_pointer: 1023,
+            value: Word::zero(),
+        };
+        assert_eq!(
+            RwMap::validate_padding_start_rw(&rows, &prev_chunk_last_rw),
+            Ok(())
+        );
+
+        let (padded, padding_len) =
+            RwMap::table_assignments_padding(&rows, 8, Some(prev_chunk_last_rw));
+        assert_eq!(padding_len, 3);
+        assert_eq!(
+            padded.iter().map(|rw| rw.rw_counter()).collect_vec(),
+            vec![999, 1000, 1001, 1002, 1003, 5, 6, 7]
+        );
+        assert!(padded[5..]
+            .iter()
+            .all(|rw| matches!(rw, Rw::Padding { .. })));
+
+        // The previous chunk can't end at or after the start of this one.
+        for rw_counter in [1000, 1002] {
+            assert!(RwMap::validate_padding_start_rw(
+                &rows,
+                &Rw::Stack {
+                    rw_counter,
+                    is_write: true,
+                    call_id: 1,
+                    stack_pointer: 1023,
+                    value: Word::zero(),
+                }
+            )
+            .is_err());
+        }
+        assert!(RwMap::validate_padding_start_rw(&rows, &Rw::Padding { rw_counter: 999 }).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "RwMap::padding_len overflow")]
+    fn table_assignments_padding_overflow() {
+        let rows = RwMap::with_dummy_rows(Target::Memory, 4).table_assignments(true);
+        RwMap::table_assignments_padding(&rows, 3, None);
+    }
+
+    #[test]
+    fn rw_typed_accessors() {
+        let access_list = Rw::TxAccessListAccount {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address: Address::repeat_byte(0xaa),
+            is_warm: true,
+            is_warm_prev: false,
+        };
+        let refund = Rw::TxRefund {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            value: 4800,
+            value_prev: 0,
+        };
+        let caller = Rw::CallContext {
+            rw_counter: 3,
+            is_write: false,
+            call_id: 1,
+            field_tag: CallContextFieldTag::CallerAddress,
+            value: Address::repeat_byte(0xbb).to_word(),
+        };
+        let depth = Rw::CallContext {
+            rw_counter: 4,
+            is_write: false,
+            call_id: 1,
+            field_tag: CallContextFieldTag::Depth,
+            value: Word::one(),
+        };
+
+        assert_eq!(access_list.as_bool(), Some(true));
+        assert_eq!(refund.as_u64(), Some(4800));
+        assert_eq!(caller.as_address(), Some(Address::repeat_byte(0xbb)));
+
+        for rw in [refund, caller, depth] {
+            assert_eq!(rw.as_bool(), None);
+        }
+        for rw in [access_list, caller, depth] {
+            assert_eq!(rw.as_u64(), None);
+        }
+        for rw in [access_list, refund, depth] {
+            assert_eq!(rw.as_address(), None);
+        }
+    }
+
+    #[test]
+    fn rw_map_diff() {
+        let rw_map = RwMap::with_dummy_rows(Target::Stack, 4);
+        assert!(rw_map.diff(&rw_map).is_empty());
+
+        let mut added = rw_map.clone();
+        added.0.get_mut(&Target::Stack).unwrap().push(Rw::Stack {
+            rw_counter: 5,
+            is_write: true,
+            call_id: 1,
+            stack_pointer: 1023,
+            value: Word::from(0x2a),
+        });
+        let diffs = rw_map.diff(&added);
+        assert_eq!(diffs.len(), 1);
+        assert!(matches!(
+            diffs[0],
+            RwDiff::Added(Rw::Stack {
+                rw_counter: 5,
+                is_write: true,
+                ..
+            })
+        ));
+        assert!(matches!(
+            added.diff(&rw_map)[..],
+            [RwDiff::Removed(Rw::Stack { rw_counter: 5, .. })]
+        ));
+
+        let mut changed = rw_map.clone();
+        if let Rw::Stack { value, .. } = &mut changed.0.get_mut(&Target::Stack).unwrap()[1] {
+            *value = Word::one();
+        }
+        match &rw_map.diff(&changed)[..] {
+            [RwDiff::Changed { old, fields, .. }] => {
+                assert_eq!(old.rw_counter(), 2);
+                assert_eq!(fields, &vec!["value"]);
+            }
+            diffs => panic!("unexpected diff {:?}", diffs),
+        }
+
+        // Rows sharing a tag and rw_counter are all compared
+        let padding = RwMap(HashMap::from([(
+            Target::Padding,
+            vec![Rw::Padding { rw_counter: 0 }; 3],
+        )]));
+        let mut fewer_padding = padding.clone();
+        fewer_padding.0.get_mut(&Target::Padding).unwrap().pop();
+        assert!(padding.diff(&padding).is_empty());
+        assert!(matches!(
+            padding.diff(&fewer_padding)[..],
+            [RwDiff::Removed(Rw::Padding { rw_counter: 0 })]
+        ));
+        assert!(matches!(
+            fewer_padding.diff(&padding)[..],
+            [RwDiff::Added(Rw::Padding { rw_counter: 0 })]
+        ));
+    }
+
+    fn step_state_rows(rw_counter: usize, is_write: bool) -> Vec<Rw> {
+        StepStateFieldTag::iter()
+            .enumerate()
+            .map(|(idx, field_tag)| Rw::StepState {
+                rw_counter: rw_counter + idx,
+                is_write,
+                field_tag,
+                value: Word::from(idx),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn step_state_bracketing() {
+        let rws = step_state_rows(100, true)
+            .into_iter()
+            .chain(step_state_rows(110, false))
+            .collect_vec();
+        let rw_map = RwMap(HashMap::from([(Target::StepState, rws.clone())]));
+        assert_eq!(rw_map.validate_step_state_bracketing(), Ok(()));
+        assert_eq!(RwMap::default().validate_step_state_bracketing(), Ok(()));
+
+        // Drop the ProgramCounter read of the BeginChunk.
+        let dropped = rws
+            .into_iter()
+            .filter(|rw| {
+                !matches!(
+                    rw,
+                    Rw::StepState {
+                        is_write: false,
+                        field_tag: StepStateFieldTag::ProgramCounter,
+                        ..
+                    }
+                )
+            })
+            .collect_vec();
+        let rw_map = RwMap(HashMap::from([(Target::StepState, dropped)]));
+        let err = rw_map.validate_step_state_bracketing().unwrap_err();
+        assert!(err.contains("missing [ProgramCounter]"), "{}", err);
+    }
+}